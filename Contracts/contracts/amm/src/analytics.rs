@@ -0,0 +1,168 @@
+#![allow(dead_code)]
+
+/// Read-side pool analytics for the Stellara Advanced AMM.
+///
+/// Swaps feed two accumulators so dashboards can query pool activity directly
+/// instead of replaying events:
+///
+/// ```text
+/// cumulative fees  = Σ fee_paid per token since pool creation
+/// volume bucket[h] = Σ amount_in per token during hour h (h = timestamp / 3600)
+/// ```
+///
+/// Only the last `VOLUME_BUCKETS` hours are ever read; older buckets are left
+/// in storage and simply ignored.
+use soroban_sdk::{symbol_short, Address, Env, Vec};
+
+use crate::pool::{fee_growth_inside, MAX_TICK, MIN_TICK};
+use crate::position::{accrue_position_fees, get_pool_positions};
+use crate::{AmmError, LpPosition, PoolKey, PoolState, TickLiquidity, VolumeBucket};
+
+// ─── Constants ────────────────────────────────────────────────────────────────
+
+/// Width of one volume bucket in ledger seconds (1 hour).
+pub const VOLUME_BUCKET_SECS: u64 = 3_600;
+
+/// Number of buckets returned by the rolling volume query (24 hours).
+pub const VOLUME_BUCKETS: u32 = 24;
+
+/// Upper bound on the number of tick buckets a single distribution query may
+/// return, to keep the query within resource limits.
+pub const MAX_DISTRIBUTION_BUCKETS: u32 = 128;
+
+/// Upper bound on the positions a single distribution query sums.
+pub const MAX_DISTRIBUTION_POSITIONS: u32 = 64;
+
+// ─── Storage helpers ───────────────────────────────────────────────────────────
+
+fn cumulative_fees_key(pool_key: &PoolKey) -> (soroban_sdk::Symbol, Address, Address) {
+    (symbol_short!("fees_cum"), pool_key.token_a.clone(), pool_key.token_b.clone())
+}
+
+fn volume_bucket_key(pool_key: &PoolKey, bucket: u64) -> (soroban_sdk::Symbol, Address, Address, u64) {
+    (
+        symbol_short!("vol_bkt"),
+        pool_key.token_a.clone(),
+        pool_key.token_b.clone(),
+        bucket,
+    )
+}
+
+/// Return `(fees_a, fees_b)` collected by the pool since creation.
+pub fn read_cumulative_fees(env: &Env, pool_key: &PoolKey) -> (i128, i128) {
+    env.storage()
+        .persistent()
+        .get(&cumulative_fees_key(pool_key))
+        .unwrap_or((0, 0))
+}
+
+// ─── Recording ────────────────────────────────────────────────────────────────
+
+/// Record one swap step's input volume and fee against the current bucket.
+///
+/// `zero_for_one` — true when token_a was the input token.
+pub fn record_swap(env: &Env, pool_key: &PoolKey, zero_for_one: bool, amount_in: i128, fee_paid: i128) {
+    let (mut fees_a, mut fees_b) = read_cumulative_fees(env, pool_key);
+    if zero_for_one {
+        fees_a += fee_paid;
+    } else {
+        fees_b += fee_paid;
+    }
+    env.storage()
+        .persistent()
+        .set(&cumulative_fees_key(pool_key), &(fees_a, fees_b));
+
+    let bucket = env.ledger().timestamp() / VOLUME_BUCKET_SECS;
+    let key = volume_bucket_key(pool_key, bucket);
+    let (mut vol_a, mut vol_b): (i128, i128) = env.storage().persistent().get(&key).unwrap_or((0, 0));
+    if zero_for_one {
+        vol_a += amount_in;
+    } else {
+        vol_b += amount_in;
+    }
+    env.storage().persistent().set(&key, &(vol_a, vol_b));
+}
+
+// ─── Queries ──────────────────────────────────────────────────────────────────
+
+/// Return the last `VOLUME_BUCKETS` hourly buckets, oldest first, ending with
+/// the bucket containing the current ledger timestamp. Empty hours are included
+/// with zero volume.
+pub fn volume_buckets(env: &Env, pool_key: &PoolKey) -> Vec<VolumeBucket> {
+    let current = env.ledger().timestamp() / VOLUME_BUCKET_SECS;
+    let mut buckets = Vec::new(env);
+
+    for i in (0..VOLUME_BUCKETS as u64).rev() {
+        if i > current {
+            continue;
+        }
+        let bucket = current - i;
+        let (volume_a, volume_b): (i128, i128) = env
+            .storage()
+            .persistent()
+            .get(&volume_bucket_key(pool_key, bucket))
+            .unwrap_or((0, 0));
+        buckets.push_back(VolumeBucket {
+            start: bucket * VOLUME_BUCKET_SECS,
+            volume_a,
+            volume_b,
+        });
+    }
+    buckets
+}
+
+/// Sum position liquidity over `[tick_lower, tick_upper)` in buckets of
+/// `tick_spacing` ticks. A position contributes its full liquidity to every
+/// bucket its range overlaps.
+///
+/// Only the positions in pool index slots `[start, start + limit)` are
+/// summed, at most `MAX_DISTRIBUTION_POSITIONS`; callers page up to
+/// `pool_position_count` and add the pages bucket by bucket.
+#[allow(clippy::too_many_arguments)]
+pub fn liquidity_distribution(
+    env: &Env,
+    pool_key: &PoolKey,
+    tick_lower: i32,
+    tick_upper: i32,
+    tick_spacing: i32,
+    start: u32,
+    limit: u32,
+) -> Result<Vec<TickLiquidity>, AmmError> {
+    if tick_spacing <= 0
+        || tick_lower >= tick_upper
+        || tick_lower < MIN_TICK
+        || tick_upper > MAX_TICK
+    {
+        return Err(AmmError::InvalidTickRange);
+    }
+    let span = (tick_upper - tick_lower) as u32;
+    let count = span.div_ceil(tick_spacing as u32);
+    if count > MAX_DISTRIBUTION_BUCKETS {
+        return Err(AmmError::InvalidTickRange);
+    }
+
+    let positions = get_pool_positions(env, pool_key, start, limit.min(MAX_DISTRIBUTION_POSITIONS));
+    let mut out = Vec::new(env);
+    let mut lower = tick_lower;
+    while lower < tick_upper {
+        let upper = (lower + tick_spacing).min(tick_upper);
+        let mut liquidity: i128 = 0;
+        for pos in positions.iter() {
+            if pos.tick_lower < upper && pos.tick_upper > lower {
+                liquidity += pos.liquidity;
+            }
+        }
+        out.push_back(TickLiquidity { tick_lower: lower, tick_upper: upper, liquidity });
+        lower = upper;
+    }
+    Ok(out)
+}
+
+/// Fees a position would receive from `collect_fees` right now, without
+/// touching storage.
+pub fn uncollected_fees(env: &Env, position: &LpPosition, pool: &PoolState) -> (i128, i128) {
+    let mut pos = position.clone();
+    let (fgi_a, fgi_b) = fee_growth_inside(env, &pos.pool_key.clone(), pool, pos.tick_lower, pos.tick_upper);
+    accrue_position_fees(&mut pos, fgi_a, fgi_b);
+    (pos.tokens_owed_a, pos.tokens_owed_b)
+}
@@ -1,10 +1,15 @@
 #![no_std]
 
+mod analytics;
 mod fees;
 mod il_hedge;
 mod pool;
 mod position;
+mod range_order;
 
+use analytics::{
+    liquidity_distribution, read_cumulative_fees, record_swap, uncollected_fees, volume_buckets,
+};
 use fees::{accumulate_fee_growth, compute_dynamic_fee, is_valid_fee_tier, record_price_sample};
 use il_hedge::{capital_efficiency_bps, compute_hedge_release, compute_hedge_reserve, estimate_il_bps};
 use pool::{
@@ -13,7 +18,13 @@ use pool::{
 };
 use position::{
     accrue_position_fees, deindex_owner_position, delete_position, get_owner_positions,
-    mint_position, read_position, require_position, write_position,
+    mint_position, pool_position_count, read_position, require_position, write_position,
+};
+use range_order::{
+    close_range_order, delete_rebalance_config, fill_tick, open_range_order, range_order_side,
+    read_range_order, read_rebalance_config, read_tick_orders, recentered_range,
+    settle_crossed_orders, settle_tick, write_rebalance_config, MAX_FILLS_PER_SETTLE,
+    MIN_RANGE_ORDER_AMOUNT,
 };
 
 use shared::acl::{ACL, ROLE_ADMIN, PERMISSION_PAUSE, PERMISSION_UNPAUSE, PERMISSION_NEW_POOL, PERMISSION_MGR_ACL};
@@ -73,6 +84,45 @@ pub struct LpPosition {
     pub created_at: u64,
 }
 
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RangeOrder {
+    pub position_id: u64,
+    pub pool_key: PoolKey,
+    /// `true` = sells token_a for token_b as price rises through the range.
+    pub sell_token_a: bool,
+    /// Tick whose crossing fills the order; the order is indexed under it.
+    pub fill_tick: i32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RebalanceConfig {
+    pub position_id: u64,
+    pub min_tick: i32,
+    pub max_tick: i32,
+    // Owner's sqrt price band for the keeper's swap; it never trades past it
+    pub min_sqrt_price: i128,
+    pub max_sqrt_price: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TickLiquidity {
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VolumeBucket {
+    /// Ledger timestamp at which the bucket starts.
+    pub start: u64,
+    pub volume_a: i128,
+    pub volume_b: i128,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct SwapResult {
@@ -117,6 +167,12 @@ pub enum AmmError {
     InvalidFeeTier = 4009,
     PositionNotFound = 4010,
     SlippageExceeded = 4011,
+    RebalanceNotEnabled = 4012,
+    RebalanceOutOfBounds = 4013,
+    PositionInRange = 4014,
+    RangeOrderPosition = 4015,
+    DeadlineExpired = 4016,
+    PriceLimitExceeded = 4017,
 }
 
 impl From<AmmError> for soroban_sdk::Error {
@@ -132,8 +188,31 @@ impl From<&AmmError> for soroban_sdk::Error {
 }
 
 impl From<soroban_sdk::Error> for AmmError {
-    fn from(_: soroban_sdk::Error) -> Self {
-        AmmError::Unauthorized
+    /// Decode a contract error raised by this contract, so `try_` client
+    /// calls report the variant. Anything else maps to `Unauthorized`.
+    fn from(e: soroban_sdk::Error) -> Self {
+        if !e.is_type(soroban_sdk::xdr::ScErrorType::Contract) {
+            return AmmError::Unauthorized;
+        }
+        match e.get_code() {
+            4001 => AmmError::NotInitialized,
+            4002 => AmmError::PoolAlreadyExists,
+            4003 => AmmError::PoolNotFound,
+            4004 => AmmError::InvalidTickRange,
+            4005 => AmmError::InsufficientLiquidity,
+            4006 => AmmError::InvalidAmount,
+            4008 => AmmError::Paused,
+            4009 => AmmError::InvalidFeeTier,
+            4010 => AmmError::PositionNotFound,
+            4011 => AmmError::SlippageExceeded,
+            4012 => AmmError::RebalanceNotEnabled,
+            4013 => AmmError::RebalanceOutOfBounds,
+            4014 => AmmError::PositionInRange,
+            4015 => AmmError::RangeOrderPosition,
+            4016 => AmmError::DeadlineExpired,
+            4017 => AmmError::PriceLimitExceeded,
+            _ => AmmError::Unauthorized,
+        }
     }
 }
 
@@ -156,6 +235,202 @@ fn require_not_paused(env: &Env) -> Result<(), AmmError> {
     }
 }
 
+fn require_deadline(env: &Env, deadline: u64) -> Result<(), AmmError> {
+    if env.ledger().timestamp() > deadline {
+        Err(AmmError::DeadlineExpired)
+    } else {
+        Ok(())
+    }
+}
+
+/// Liquidity calls take an inclusive `(min, max)` sqrt price band: the call
+/// reverts if the pool's price has moved outside it in either direction.
+fn require_price_within(
+    sqrt_price: i128,
+    sqrt_price_range: Option<(i128, i128)>,
+) -> Result<(), AmmError> {
+    match sqrt_price_range {
+        Some((min, max)) if sqrt_price < min || sqrt_price > max => {
+            Err(AmmError::PriceLimitExceeded)
+        }
+        _ => Ok(()),
+    }
+}
+
+// ── Shared liquidity / swap helpers ──────────────────────────────────────────
+
+/// Mint a new position in `pool_key` for `owner`, pulling the required tokens
+/// into the contract. Auth, init and pause checks are the caller's job.
+fn provide_liquidity(
+    env: &Env,
+    owner: &Address,
+    pool_key: &PoolKey,
+    tick_lower: i32,
+    tick_upper: i32,
+    amounts_desired: (i128, i128),
+    amounts_min: (i128, i128),
+) -> Result<AddLiquidityResult, AmmError> {
+    let (amount_desired_a, amount_desired_b) = amounts_desired;
+    let (min_a, min_b) = amounts_min;
+
+    if tick_lower >= tick_upper {
+        return Err(AmmError::InvalidTickRange);
+    }
+    if amount_desired_a <= 0 && amount_desired_b <= 0 {
+        return Err(AmmError::InvalidAmount);
+    }
+
+    let mut pool = require_pool(env, pool_key)?;
+
+    let sqrt_lower = tick_to_sqrt_price(tick_lower)?;
+    let sqrt_upper = tick_to_sqrt_price(tick_upper)?;
+
+    let liquidity = liquidity_from_amounts(
+        amount_desired_a,
+        amount_desired_b,
+        sqrt_lower,
+        sqrt_upper,
+        pool.sqrt_price,
+    );
+
+    if liquidity == 0 {
+        return Err(AmmError::InsufficientLiquidity);
+    }
+
+    let (actual_a, actual_b) = amounts_for_liquidity(
+        liquidity,
+        sqrt_lower,
+        sqrt_upper,
+        pool.sqrt_price,
+    );
+
+    if actual_a < min_a || actual_b < min_b {
+        return Err(AmmError::SlippageExceeded);
+    }
+
+    // Transfer tokens into the contract
+    if actual_a > 0 {
+        let tok = token::Client::new(env, &pool_key.token_a);
+        tok.transfer(owner, &env.current_contract_address(), &actual_a);
+    }
+    if actual_b > 0 {
+        let tok = token::Client::new(env, &pool_key.token_b);
+        tok.transfer(owner, &env.current_contract_address(), &actual_b);
+    }
+
+    // Update active liquidity if in range
+    if pool.current_tick >= tick_lower && pool.current_tick < tick_upper {
+        pool.liquidity += liquidity;
+        pool.active_liquidity += liquidity;
+    }
+    pool.active_liquidity = pool.active_liquidity.max(0);
+
+    // IL hedge check
+    let (fgi_a, fgi_b) = fee_growth_inside(env, pool_key, &pool, tick_lower, tick_upper);
+    let position_id = mint_position(
+        env,
+        owner.clone(),
+        pool_key.clone(),
+        tick_lower,
+        tick_upper,
+        liquidity,
+        fgi_a,
+        fgi_b,
+    );
+
+    // Compute IL at entry (should be 0, but trigger reserve if it's not)
+    let pos = require_position(env, position_id)?;
+    let il_bps = estimate_il_bps(&pos, &pool);
+    let (res_a, res_b) = compute_hedge_reserve(il_bps, actual_a, actual_b);
+    pool.il_reserve_a += res_a;
+    pool.il_reserve_b += res_b;
+
+    write_pool(env, pool_key, &pool);
+
+    env.events().publish(
+        (symbol_short!("liq_add"),),
+        (position_id, owner.clone(), actual_a, actual_b, liquidity),
+    );
+
+    Ok(AddLiquidityResult { position_id, amount_a: actual_a, amount_b: actual_b, liquidity })
+}
+
+/// Run a single swap step against `pool` (moving at most one tick), updating
+/// price, fee growth, tick crossings and volume in place. Token transfers and
+/// persisting the pool are left to the caller.
+///
+/// If `sqrt_price_limit` is set the step stops at that price; a limit the pool
+/// price is already at or beyond returns `PriceLimitExceeded`.
+fn execute_swap_step(
+    env: &Env,
+    pool_key: &PoolKey,
+    pool: &mut PoolState,
+    zero_for_one: bool,
+    amount_in: i128,
+    sqrt_price_limit: Option<i128>,
+) -> Result<pool::SwapStep, AmmError> {
+    if pool.liquidity == 0 {
+        return Err(AmmError::InsufficientLiquidity);
+    }
+
+    // Record price sample for volatility oracle
+    record_price_sample(env, pool_key, pool.sqrt_price);
+
+    let fee_bps = pool.dynamic_fee_bps;
+
+    // Determine price target (tick boundary)
+    let target_tick = if zero_for_one {
+        pool.current_tick - 1
+    } else {
+        pool.current_tick + 1
+    };
+    let target_tick = target_tick
+        .max(pool::MIN_TICK)
+        .min(pool::MAX_TICK);
+    let mut target_sqrt = tick_to_sqrt_price(target_tick)?;
+
+    if let Some(limit) = sqrt_price_limit {
+        let already_past = if zero_for_one {
+            limit >= pool.sqrt_price
+        } else {
+            limit <= pool.sqrt_price
+        };
+        if already_past {
+            return Err(AmmError::PriceLimitExceeded);
+        }
+        target_sqrt = if zero_for_one { target_sqrt.max(limit) } else { target_sqrt.min(limit) };
+    }
+
+    let step = compute_swap_step(
+        pool.sqrt_price,
+        target_sqrt,
+        pool.liquidity,
+        amount_in,
+        fee_bps,
+    );
+
+    // Update fee growth globals
+    if zero_for_one {
+        pool.fee_growth_global_a = accumulate_fee_growth(step.fee_paid, pool.liquidity, pool.fee_growth_global_a);
+    } else {
+        pool.fee_growth_global_b = accumulate_fee_growth(step.fee_paid, pool.liquidity, pool.fee_growth_global_b);
+    }
+
+    // Cross tick if price boundary was reached
+    let new_tick = pool::sqrt_price_to_tick(step.new_sqrt_price);
+    if new_tick != pool.current_tick {
+        cross_tick(env, pool_key, pool, if zero_for_one { pool.current_tick } else { target_tick });
+    }
+
+    pool.sqrt_price = step.new_sqrt_price;
+    pool.current_tick = new_tick;
+    pool.total_volume += step.amount_in;
+
+    record_swap(env, pool_key, zero_for_one, step.amount_in, step.fee_paid);
+
+    Ok(step)
+}
+
 // ── Contract ──────────────────────────────────────────────────────────────────
 
 #[contract]
@@ -256,92 +531,25 @@ impl AmmContract {
         amount_desired_b: i128,
         min_a: i128,
         min_b: i128,
+        deadline: u64,
+        sqrt_price_range: Option<(i128, i128)>,
     ) -> Result<AddLiquidityResult, AmmError> {
         caller.require_auth();
         require_init(&env)?;
         require_not_paused(&env)?;
+        require_deadline(&env, deadline)?;
 
-        if tick_lower >= tick_upper {
-            return Err(AmmError::InvalidTickRange);
-        }
-        if amount_desired_a <= 0 && amount_desired_b <= 0 {
-            return Err(AmmError::InvalidAmount);
-        }
-
-        let pool_key = PoolKey { token_a: token_a.clone(), token_b: token_b.clone() };
-        let mut pool = require_pool(&env, &pool_key)?;
-
-        let sqrt_lower = tick_to_sqrt_price(tick_lower)?;
-        let sqrt_upper = tick_to_sqrt_price(tick_upper)?;
-
-        let liquidity = liquidity_from_amounts(
-            amount_desired_a,
-            amount_desired_b,
-            sqrt_lower,
-            sqrt_upper,
-            pool.sqrt_price,
-        );
-
-        if liquidity == 0 {
-            return Err(AmmError::InsufficientLiquidity);
-        }
-
-        let (actual_a, actual_b) = amounts_for_liquidity(
-            liquidity,
-            sqrt_lower,
-            sqrt_upper,
-            pool.sqrt_price,
-        );
-
-        if actual_a < min_a || actual_b < min_b {
-            return Err(AmmError::SlippageExceeded);
-        }
-
-        // Transfer tokens into the contract
-        if actual_a > 0 {
-            let tok = token::Client::new(&env, &token_a);
-            tok.transfer(&caller, &env.current_contract_address(), &actual_a);
-        }
-        if actual_b > 0 {
-            let tok = token::Client::new(&env, &token_b);
-            tok.transfer(&caller, &env.current_contract_address(), &actual_b);
-        }
-
-        // Update active liquidity if in range
-        if pool.current_tick >= tick_lower && pool.current_tick < tick_upper {
-            pool.liquidity += liquidity;
-            pool.active_liquidity += liquidity;
-        }
-        pool.active_liquidity = pool.active_liquidity.max(0);
-
-        // IL hedge check
-        let (fgi_a, fgi_b) = fee_growth_inside(&env, &pool_key, &pool, tick_lower, tick_upper);
-        let position_id = mint_position(
+        let pool_key = PoolKey { token_a, token_b };
+        require_price_within(require_pool(&env, &pool_key)?.sqrt_price, sqrt_price_range)?;
+        provide_liquidity(
             &env,
-            caller.clone(),
-            pool_key.clone(),
+            &caller,
+            &pool_key,
             tick_lower,
             tick_upper,
-            liquidity,
-            fgi_a,
-            fgi_b,
-        );
-
-        // Compute IL at entry (should be 0, but trigger reserve if it's not)
-        let pos = require_position(&env, position_id)?;
-        let il_bps = estimate_il_bps(&pos, &pool);
-        let (res_a, res_b) = compute_hedge_reserve(il_bps, actual_a, actual_b);
-        pool.il_reserve_a += res_a;
-        pool.il_reserve_b += res_b;
-
-        write_pool(&env, &pool_key, &pool);
-
-        env.events().publish(
-            (symbol_short!("liq_add"),),
-            (position_id, caller, actual_a, actual_b, liquidity),
-        );
-
-        Ok(AddLiquidityResult { position_id, amount_a: actual_a, amount_b: actual_b, liquidity })
+            (amount_desired_a, amount_desired_b),
+            (min_a, min_b),
+        )
     }
 
     // ── Remove liquidity ──────────────────────────────────────────────────────
@@ -353,9 +561,12 @@ impl AmmContract {
         liquidity_to_remove: i128,
         min_a: i128,
         min_b: i128,
+        deadline: u64,
+        sqrt_price_range: Option<(i128, i128)>,
     ) -> Result<RemoveLiquidityResult, AmmError> {
         caller.require_auth();
         require_init(&env)?;
+        require_deadline(&env, deadline)?;
 
         let mut pos = require_position(&env, position_id)?;
         if pos.owner != caller {
@@ -367,6 +578,7 @@ impl AmmContract {
 
         let pool_key = pos.pool_key.clone();
         let mut pool = require_pool(&env, &pool_key)?;
+        require_price_within(pool.sqrt_price, sqrt_price_range)?;
 
         // Accrue fees
         let (fgi_a, fgi_b) = fee_growth_inside(&env, &pool_key, &pool, pos.tick_lower, pos.tick_upper);
@@ -415,6 +627,8 @@ impl AmmContract {
         if pos.liquidity == 0 {
             delete_position(&env, position_id);
             deindex_owner_position(&env, &caller, position_id);
+            close_range_order(&env, position_id);
+            delete_rebalance_config(&env, position_id);
         } else {
             write_position(&env, &pos);
         }
@@ -479,10 +693,13 @@ impl AmmContract {
         token_out: Address,
         amount_in: i128,
         min_amount_out: i128,
+        deadline: u64,
+        sqrt_price_limit: Option<i128>,
     ) -> Result<SwapResult, AmmError> {
         caller.require_auth();
         require_init(&env)?;
         require_not_paused(&env)?;
+        require_deadline(&env, deadline)?;
 
         if amount_in <= 0 {
             return Err(AmmError::InvalidAmount);
@@ -497,62 +714,14 @@ impl AmmContract {
 
         let pool_key = PoolKey { token_a: token_a.clone(), token_b: token_b.clone() };
         let mut pool = require_pool(&env, &pool_key)?;
+        let old_tick = pool.current_tick;
 
-        if pool.liquidity == 0 {
-            return Err(AmmError::InsufficientLiquidity);
-        }
-
-        // Record price sample for volatility oracle
-        record_price_sample(&env, &pool_key, pool.sqrt_price);
-
-        let fee_bps = pool.dynamic_fee_bps;
-
-        // Determine price target (tick boundary)
-        let target_tick = if zero_for_one {
-            pool.current_tick - 1
-        } else {
-            pool.current_tick + 1
-        };
-        let target_tick = target_tick
-            .max(pool::MIN_TICK)
-            .min(pool::MAX_TICK);
-        let target_sqrt = tick_to_sqrt_price(target_tick)?;
-
-        let step = compute_swap_step(
-            pool.sqrt_price,
-            target_sqrt,
-            pool.liquidity,
-            amount_in,
-            fee_bps,
-        );
+        let step = execute_swap_step(&env, &pool_key, &mut pool, zero_for_one, amount_in, sqrt_price_limit)?;
 
         if step.amount_out < min_amount_out {
             return Err(AmmError::SlippageExceeded);
         }
 
-        // Update fee growth globals
-        let new_fg_a;
-        let new_fg_b;
-        if zero_for_one {
-            new_fg_a = accumulate_fee_growth(step.fee_paid, pool.liquidity, pool.fee_growth_global_a);
-            new_fg_b = pool.fee_growth_global_b;
-        } else {
-            new_fg_a = pool.fee_growth_global_a;
-            new_fg_b = accumulate_fee_growth(step.fee_paid, pool.liquidity, pool.fee_growth_global_b);
-        }
-
-        // Cross tick if price boundary was reached
-        let new_tick = pool::sqrt_price_to_tick(step.new_sqrt_price);
-        if new_tick != pool.current_tick {
-            cross_tick(&env, &pool_key, &pool, if zero_for_one { pool.current_tick } else { target_tick });
-        }
-
-        pool.sqrt_price = step.new_sqrt_price;
-        pool.current_tick = new_tick;
-        pool.fee_growth_global_a = new_fg_a;
-        pool.fee_growth_global_b = new_fg_b;
-        pool.total_volume += step.amount_in;
-
         write_pool(&env, &pool_key, &pool);
 
         // Execute token transfers
@@ -562,6 +731,9 @@ impl AmmContract {
         let tok_out = token::Client::new(&env, &token_out);
         tok_out.transfer(&env.current_contract_address(), &caller, &step.amount_out);
 
+        // Close range orders on the ticks the price crossed
+        settle_crossed_orders(&env, &pool_key, &pool, old_tick);
+
         env.events().publish(
             (symbol_short!("swap"),),
             (caller, step.amount_in, step.amount_out, step.fee_paid),
@@ -572,10 +744,256 @@ impl AmmContract {
             amount_out: step.amount_out,
             fee_paid: step.fee_paid,
             new_sqrt_price: step.new_sqrt_price,
-            new_tick: new_tick,
+            new_tick: pool.current_tick,
         })
     }
 
+    // ── Range orders ──────────────────────────────────────────────────────────
+
+    /// Place a single-sided range order entirely above (sells token_a) or
+    /// entirely below (sells token_b) the current tick. The order closes
+    /// automatically into the other token once a swap moves the price fully
+    /// through `[tick_lower, tick_upper)`.
+    pub fn place_range_order(
+        env: Env,
+        caller: Address,
+        token_a: Address,
+        token_b: Address,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount: i128,
+        deadline: u64,
+    ) -> Result<AddLiquidityResult, AmmError> {
+        caller.require_auth();
+        require_init(&env)?;
+        require_not_paused(&env)?;
+        require_deadline(&env, deadline)?;
+
+        if tick_lower >= tick_upper {
+            return Err(AmmError::InvalidTickRange);
+        }
+        if amount < MIN_RANGE_ORDER_AMOUNT {
+            return Err(AmmError::InvalidAmount);
+        }
+
+        let pool_key = PoolKey { token_a, token_b };
+        let pool = require_pool(&env, &pool_key)?;
+        let sell_token_a = range_order_side(pool.current_tick, tick_lower, tick_upper)?;
+
+        let desired = if sell_token_a { (amount, 0) } else { (0, amount) };
+        let result = provide_liquidity(
+            &env,
+            &caller,
+            &pool_key,
+            tick_lower,
+            tick_upper,
+            desired,
+            (0, 0),
+        )?;
+
+        open_range_order(
+            &env,
+            &RangeOrder {
+                position_id: result.position_id,
+                pool_key,
+                sell_token_a,
+                fill_tick: fill_tick(sell_token_a, tick_lower, tick_upper),
+            },
+        );
+
+        env.events().publish(
+            (symbol_short!("ord_new"),),
+            (result.position_id, caller, sell_token_a, amount),
+        );
+
+        Ok(result)
+    }
+
+    pub fn get_range_order(env: Env, position_id: u64) -> Option<RangeOrder> {
+        read_range_order(&env, position_id)
+    }
+
+    /// Open range orders filled by crossing `tick` on the given sell side.
+    pub fn get_range_orders_at_tick(
+        env: Env,
+        token_a: Address,
+        token_b: Address,
+        tick: i32,
+        sell_token_a: bool,
+    ) -> Vec<u64> {
+        let pool_key = PoolKey { token_a, token_b };
+        read_tick_orders(&env, &pool_key, tick, sell_token_a)
+    }
+
+    /// Fill orders at `tick` that the price has crossed but a swap left open
+    /// because it reached its per-swap fill cap. Callable by anyone; fills at
+    /// most `MAX_FILLS_PER_SETTLE` orders per call and returns how many it
+    /// visited.
+    pub fn settle_range_orders(
+        env: Env,
+        token_a: Address,
+        token_b: Address,
+        tick: i32,
+        sell_token_a: bool,
+    ) -> Result<u32, AmmError> {
+        require_init(&env)?;
+        require_not_paused(&env)?;
+
+        let pool_key = PoolKey { token_a, token_b };
+        let pool = require_pool(&env, &pool_key)?;
+        Ok(settle_tick(&env, &pool_key, &pool, tick, sell_token_a, MAX_FILLS_PER_SETTLE))
+    }
+
+    // ── Position rebalancing ──────────────────────────────────────────────────
+
+    /// Opt a position into keeper-driven rebalancing. Re-centred ranges must
+    /// stay within `[min_tick, max_tick]`, and the rebalancing swap is limited
+    /// to `[min_sqrt_price, max_sqrt_price]`. Calling again replaces the bounds.
+    pub fn enable_rebalancing(
+        env: Env,
+        caller: Address,
+        position_id: u64,
+        min_tick: i32,
+        max_tick: i32,
+        min_sqrt_price: i128,
+        max_sqrt_price: i128,
+    ) -> Result<(), AmmError> {
+        caller.require_auth();
+        require_init(&env)?;
+
+        let pos = require_position(&env, position_id)?;
+        if pos.owner != caller {
+            return Err(AmmError::Unauthorized);
+        }
+        if read_range_order(&env, position_id).is_some() {
+            return Err(AmmError::RangeOrderPosition);
+        }
+        if min_tick >= max_tick
+            || min_tick < pool::MIN_TICK
+            || max_tick > pool::MAX_TICK
+            || max_tick - min_tick < pos.tick_upper - pos.tick_lower
+            || min_sqrt_price <= 0
+            || min_sqrt_price >= max_sqrt_price
+        {
+            return Err(AmmError::InvalidTickRange);
+        }
+
+        write_rebalance_config(
+            &env,
+            &RebalanceConfig { position_id, min_tick, max_tick, min_sqrt_price, max_sqrt_price },
+        );
+        env.events().publish(
+            (symbol_short!("rebal_on"),),
+            (position_id, min_tick, max_tick, min_sqrt_price, max_sqrt_price),
+        );
+        Ok(())
+    }
+
+    pub fn disable_rebalancing(env: Env, caller: Address, position_id: u64) -> Result<(), AmmError> {
+        caller.require_auth();
+        require_init(&env)?;
+
+        let pos = require_position(&env, position_id)?;
+        if pos.owner != caller {
+            return Err(AmmError::Unauthorized);
+        }
+
+        delete_rebalance_config(&env, position_id);
+        env.events().publish((symbol_short!("rebal_off"),), (position_id,));
+        Ok(())
+    }
+
+    pub fn get_rebalance_config(env: Env, position_id: u64) -> Option<RebalanceConfig> {
+        read_rebalance_config(&env, position_id)
+    }
+
+    /// Re-centre an out-of-range position around the current tick, keeping its
+    /// width. Callable by anyone once the owner has enabled rebalancing.
+    ///
+    /// An out-of-range position holds only one token, so half of it is first
+    /// swapped through the pool, never past the owner's sqrt price band; a
+    /// price already outside it fails with `PriceLimitExceeded`. Whatever
+    /// cannot be redeployed into the new range is credited to the position's
+    /// `tokens_owed` for later collection.
+    pub fn rebalance_position(
+        env: Env,
+        keeper: Address,
+        position_id: u64,
+    ) -> Result<LpPosition, AmmError> {
+        keeper.require_auth();
+        require_init(&env)?;
+        require_not_paused(&env)?;
+
+        let config = read_rebalance_config(&env, position_id).ok_or(AmmError::RebalanceNotEnabled)?;
+        let mut pos = require_position(&env, position_id)?;
+        let pool_key = pos.pool_key.clone();
+        let mut pool = require_pool(&env, &pool_key)?;
+
+        if pool.current_tick >= pos.tick_lower && pool.current_tick < pos.tick_upper {
+            return Err(AmmError::PositionInRange);
+        }
+        require_price_within(pool.sqrt_price, Some((config.min_sqrt_price, config.max_sqrt_price)))?;
+        let (new_lower, new_upper) =
+            recentered_range(&config, pos.tick_lower, pos.tick_upper, pool.current_tick)?;
+
+        // Settle fees and withdraw the old range's tokens (kept in the contract)
+        let (fgi_a, fgi_b) = fee_growth_inside(&env, &pool_key, &pool, pos.tick_lower, pos.tick_upper);
+        accrue_position_fees(&mut pos, fgi_a, fgi_b);
+        let (mut amt_a, mut amt_b) = amounts_for_liquidity(
+            pos.liquidity,
+            tick_to_sqrt_price(pos.tick_lower)?,
+            tick_to_sqrt_price(pos.tick_upper)?,
+            pool.sqrt_price,
+        );
+
+        // Swap half of the single-sided balance so both tokens are available
+        let old_tick = pool.current_tick;
+        if amt_b == 0 && amt_a > 1 {
+            let step = execute_swap_step(&env, &pool_key, &mut pool, true, amt_a / 2, Some(config.min_sqrt_price))?;
+            amt_a -= step.amount_in + step.fee_paid;
+            amt_b += step.amount_out;
+        } else if amt_a == 0 && amt_b > 1 {
+            let step = execute_swap_step(&env, &pool_key, &mut pool, false, amt_b / 2, Some(config.max_sqrt_price))?;
+            amt_b -= step.amount_in + step.fee_paid;
+            amt_a += step.amount_out;
+        }
+
+        let sqrt_lower = tick_to_sqrt_price(new_lower)?;
+        let sqrt_upper = tick_to_sqrt_price(new_upper)?;
+        let liquidity = liquidity_from_amounts(amt_a, amt_b, sqrt_lower, sqrt_upper, pool.sqrt_price);
+        if liquidity == 0 {
+            return Err(AmmError::InsufficientLiquidity);
+        }
+        let (used_a, used_b) = amounts_for_liquidity(liquidity, sqrt_lower, sqrt_upper, pool.sqrt_price);
+        pos.tokens_owed_a += (amt_a - used_a).max(0);
+        pos.tokens_owed_b += (amt_b - used_b).max(0);
+
+        if pool.current_tick >= new_lower && pool.current_tick < new_upper {
+            pool.liquidity += liquidity;
+            pool.active_liquidity += liquidity;
+        }
+
+        let (new_fgi_a, new_fgi_b) = fee_growth_inside(&env, &pool_key, &pool, new_lower, new_upper);
+        pos.tick_lower = new_lower;
+        pos.tick_upper = new_upper;
+        pos.liquidity = liquidity;
+        pos.fee_growth_inside_a_last = new_fgi_a;
+        pos.fee_growth_inside_b_last = new_fgi_b;
+
+        write_position(&env, &pos);
+        write_pool(&env, &pool_key, &pool);
+
+        // The keeper swap can cross ticks like any other swap
+        settle_crossed_orders(&env, &pool_key, &pool, old_tick);
+
+        env.events().publish(
+            (symbol_short!("rebal"),),
+            (position_id, keeper, new_lower, new_upper, liquidity),
+        );
+
+        Ok(pos)
+    }
+
     // ── Dynamic fee update ────────────────────────────────────────────────────
 
     pub fn update_dynamic_fee(
@@ -623,6 +1041,54 @@ impl AmmContract {
         Ok(capital_efficiency_bps(pool.active_liquidity, pool.liquidity))
     }
 
+    // ── Analytics ─────────────────────────────────────────────────────────────
+
+    /// Liquidity in `[tick_lower, tick_upper)` bucketed every `tick_spacing` ticks,
+    /// summed over one page of the pool's positions (see `get_pool_position_count`).
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_liquidity_distribution(
+        env: Env,
+        token_a: Address,
+        token_b: Address,
+        tick_lower: i32,
+        tick_upper: i32,
+        tick_spacing: i32,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<TickLiquidity>, AmmError> {
+        let pool_key = PoolKey { token_a, token_b };
+        require_pool(&env, &pool_key)?;
+        liquidity_distribution(&env, &pool_key, tick_lower, tick_upper, tick_spacing, start, limit)
+    }
+
+    /// Number of live positions in the pool, for paging `get_liquidity_distribution`.
+    pub fn get_pool_position_count(env: Env, token_a: Address, token_b: Address) -> Result<u32, AmmError> {
+        let pool_key = PoolKey { token_a, token_b };
+        require_pool(&env, &pool_key)?;
+        Ok(pool_position_count(&env, &pool_key))
+    }
+
+    /// Total swap fees `(fees_a, fees_b)` the pool has charged since creation.
+    pub fn get_cumulative_fees(env: Env, token_a: Address, token_b: Address) -> Result<(i128, i128), AmmError> {
+        let pool_key = PoolKey { token_a, token_b };
+        require_pool(&env, &pool_key)?;
+        Ok(read_cumulative_fees(&env, &pool_key))
+    }
+
+    /// Hourly swap volume for the last 24 hours, oldest first.
+    pub fn get_volume_24h(env: Env, token_a: Address, token_b: Address) -> Result<Vec<VolumeBucket>, AmmError> {
+        let pool_key = PoolKey { token_a, token_b };
+        require_pool(&env, &pool_key)?;
+        Ok(volume_buckets(&env, &pool_key))
+    }
+
+    /// Fees owed to a position as of now, without collecting them.
+    pub fn get_uncollected_fees(env: Env, position_id: u64) -> Result<(i128, i128), AmmError> {
+        let pos = require_position(&env, position_id)?;
+        let pool = require_pool(&env, &pos.pool_key.clone())?;
+        Ok(uncollected_fees(&env, &pos, &pool))
+    }
+
     // ── Pause / unpause ───────────────────────────────────────────────────────
 
     pub fn pause(env: Env, admin: Address) -> Result<(), AmmError> {
@@ -29,6 +29,24 @@ fn owner_positions_key(owner: &Address) -> (soroban_sdk::Symbol, Address) {
     (symbol_short!("own_pos"), owner.clone())
 }
 
+/// Key of one slot in a pool's index of live position IDs.
+fn pool_position_slot_key(
+    pool_key: &PoolKey,
+    slot: u32,
+) -> (soroban_sdk::Symbol, Address, Address, u32) {
+    (symbol_short!("pool_pos"), pool_key.token_a.clone(), pool_key.token_b.clone(), slot)
+}
+
+/// Key holding the number of slots in a pool's position index.
+fn pool_position_count_key(pool_key: &PoolKey) -> (soroban_sdk::Symbol, Address, Address) {
+    (symbol_short!("pool_pcnt"), pool_key.token_a.clone(), pool_key.token_b.clone())
+}
+
+/// Key that maps a position to its slot in its pool's index.
+fn position_slot_key(position_id: u64) -> (soroban_sdk::Symbol, u64) {
+    (symbol_short!("pos_slot"), position_id)
+}
+
 // ─── Position CRUD ─────────────────────────────────────────────────────────────
 
 /// Allocate the next position ID (monotonically increasing).
@@ -58,6 +76,9 @@ pub fn require_position(env: &Env, position_id: u64) -> Result<LpPosition, AmmEr
 
 /// Delete a position from storage (used on full burn).
 pub fn delete_position(env: &Env, position_id: u64) {
+    if let Some(pos) = read_position(env, position_id) {
+        deindex_pool_position(env, &pos.pool_key, position_id);
+    }
     let key = pos_key(position_id);
     env.storage().persistent().remove(&key);
 }
@@ -111,6 +132,60 @@ pub fn get_owner_positions(env: &Env, owner: &Address) -> Vec<LpPosition> {
     positions
 }
 
+// ─── Pool index ────────────────────────────────────────────────────────────────
+//
+// Each slot is its own entry, and removal moves the last slot into the freed
+// one, so minting or burning touches at most two slots however many
+// positions the pool has.
+
+/// Number of live positions in `pool_key`.
+pub fn pool_position_count(env: &Env, pool_key: &PoolKey) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&pool_position_count_key(pool_key))
+        .unwrap_or(0)
+}
+
+/// Add a position ID to the pool's index.
+pub fn index_pool_position(env: &Env, pool_key: &PoolKey, position_id: u64) {
+    let storage = env.storage().persistent();
+    let count = pool_position_count(env, pool_key);
+    storage.set(&pool_position_slot_key(pool_key, count), &position_id);
+    storage.set(&position_slot_key(position_id), &count);
+    storage.set(&pool_position_count_key(pool_key), &(count + 1));
+}
+
+/// Remove a position ID from the pool's index.
+pub fn deindex_pool_position(env: &Env, pool_key: &PoolKey, position_id: u64) {
+    let storage = env.storage().persistent();
+    let Some(slot) = storage.get::<_, u32>(&position_slot_key(position_id)) else {
+        return;
+    };
+    let last = pool_position_count(env, pool_key) - 1;
+    if slot != last {
+        let moved: u64 = storage.get(&pool_position_slot_key(pool_key, last)).unwrap();
+        storage.set(&pool_position_slot_key(pool_key, slot), &moved);
+        storage.set(&position_slot_key(moved), &slot);
+    }
+    storage.remove(&pool_position_slot_key(pool_key, last));
+    storage.remove(&position_slot_key(position_id));
+    storage.set(&pool_position_count_key(pool_key), &last);
+}
+
+/// Return up to `limit` live positions in `pool_key`, starting at index
+/// slot `start`. Slots are not ordered by ID.
+pub fn get_pool_positions(env: &Env, pool_key: &PoolKey, start: u32, limit: u32) -> Vec<LpPosition> {
+    let end = start.saturating_add(limit).min(pool_position_count(env, pool_key));
+    let mut positions = Vec::new(env);
+    for slot in start..end {
+        let id: Option<u64> = env.storage().persistent().get(&pool_position_slot_key(pool_key, slot));
+        if let Some(pos) = id.and_then(|id| read_position(env, id)) {
+            positions.push_back(pos);
+        }
+    }
+    positions
+}
+
 // ─── Mint helper ───────────────────────────────────────────────────────────────
 
 /// Create and persist a brand new LP position; returns the position ID.
@@ -129,7 +204,7 @@ pub fn mint_position(
     let position = LpPosition {
         id: position_id,
         owner: owner.clone(),
-        pool_key,
+        pool_key: pool_key.clone(),
         tick_lower,
         tick_upper,
         liquidity,
@@ -142,6 +217,7 @@ pub fn mint_position(
 
     write_position(env, &position);
     index_owner_position(env, &owner, position_id);
+    index_pool_position(env, &pool_key, position_id);
 
     position_id
 }
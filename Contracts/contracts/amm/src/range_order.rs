@@ -0,0 +1,284 @@
+#![allow(dead_code)]
+
+/// Range orders and keeper-driven rebalancing for the Stellara Advanced AMM.
+///
+/// # Range orders
+///
+/// A range order is a single-sided LP position placed entirely on one side of
+/// the current tick:
+///
+/// ```text
+/// tick_lower > current_tick   → deposits token_a, fills into token_b once current_tick >= tick_upper
+/// tick_upper <= current_tick  → deposits token_b, fills into token_a once current_tick <  tick_lower
+/// ```
+///
+/// Open orders are indexed by the tick that fills them (`tick_upper` for orders
+/// selling token_a, `tick_lower` for orders selling token_b).  After every swap
+/// only the ticks the price crossed are visited, and each order found there is
+/// closed: the position is burned and the output token (plus accrued fees) is
+/// paid straight to the owner.  A swap fills at most `MAX_FILLS_PER_SETTLE`
+/// orders; anything left on a crowded tick is filled by `settle_range_orders`,
+/// which anyone may call.
+///
+/// # Rebalancing
+///
+/// Owners may opt a position into rebalancing by setting `[min_tick, max_tick]`
+/// bounds.  Once the position falls out of range, anyone (typically a keeper)
+/// can re-center it around the current tick, keeping its width.  The new range
+/// must stay within the owner's bounds, and the swap that re-balances its
+/// tokens stops at the owner's sqrt price band, so a keeper cannot sandwich it.
+use soroban_sdk::{symbol_short, token, Address, Env, Vec};
+
+use crate::pool::{amounts_for_liquidity, fee_growth_inside, tick_to_sqrt_price};
+use crate::position::{accrue_position_fees, deindex_owner_position, delete_position, read_position};
+use crate::{AmmError, LpPosition, PoolKey, PoolState, RangeOrder, RebalanceConfig};
+
+/// Most orders one swap (or one keeper call) fills.
+pub const MAX_FILLS_PER_SETTLE: u32 = 6;
+
+/// Smallest amount a range order can be placed with, so a tick cannot be
+/// crowded with dust orders for free.
+pub const MIN_RANGE_ORDER_AMOUNT: i128 = 1_000;
+
+// ─── Storage helpers ───────────────────────────────────────────────────────────
+
+fn order_key(position_id: u64) -> (soroban_sdk::Symbol, u64) {
+    (symbol_short!("rng_ord"), position_id)
+}
+
+/// Key that maps a pool tick and sell side to the open orders that tick fills.
+fn tick_orders_key(
+    pool_key: &PoolKey,
+    tick: i32,
+    sell_token_a: bool,
+) -> (soroban_sdk::Symbol, Address, Address, i32, bool) {
+    (
+        symbol_short!("tick_ord"),
+        pool_key.token_a.clone(),
+        pool_key.token_b.clone(),
+        tick,
+        sell_token_a,
+    )
+}
+
+fn rebalance_key(position_id: u64) -> (soroban_sdk::Symbol, u64) {
+    (symbol_short!("rebal"), position_id)
+}
+
+pub fn read_range_order(env: &Env, position_id: u64) -> Option<RangeOrder> {
+    env.storage().persistent().get(&order_key(position_id))
+}
+
+pub fn read_tick_orders(env: &Env, pool_key: &PoolKey, tick: i32, sell_token_a: bool) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&tick_orders_key(pool_key, tick, sell_token_a))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn write_tick_orders(env: &Env, pool_key: &PoolKey, tick: i32, sell_token_a: bool, ids: &Vec<u64>) {
+    let key = tick_orders_key(pool_key, tick, sell_token_a);
+    if ids.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, ids);
+    }
+}
+
+/// Persist a range order and add it to the index of its fill tick.
+pub fn open_range_order(env: &Env, order: &RangeOrder) {
+    env.storage().persistent().set(&order_key(order.position_id), order);
+    let mut ids = read_tick_orders(env, &order.pool_key, order.fill_tick, order.sell_token_a);
+    ids.push_back(order.position_id);
+    write_tick_orders(env, &order.pool_key, order.fill_tick, order.sell_token_a, &ids);
+}
+
+/// Remove a range order record, if any, and drop it from its fill tick's index.
+pub fn close_range_order(env: &Env, position_id: u64) {
+    let order = match read_range_order(env, position_id) {
+        Some(o) => o,
+        None => return,
+    };
+    env.storage().persistent().remove(&order_key(position_id));
+    let mut ids = read_tick_orders(env, &order.pool_key, order.fill_tick, order.sell_token_a);
+    if let Some(i) = ids.first_index_of(position_id) {
+        ids.remove(i);
+    }
+    write_tick_orders(env, &order.pool_key, order.fill_tick, order.sell_token_a, &ids);
+}
+
+pub fn read_rebalance_config(env: &Env, position_id: u64) -> Option<RebalanceConfig> {
+    env.storage().persistent().get(&rebalance_key(position_id))
+}
+
+pub fn write_rebalance_config(env: &Env, config: &RebalanceConfig) {
+    env.storage().persistent().set(&rebalance_key(config.position_id), config);
+}
+
+pub fn delete_rebalance_config(env: &Env, position_id: u64) {
+    env.storage().persistent().remove(&rebalance_key(position_id));
+}
+
+// ─── Range order logic ─────────────────────────────────────────────────────────
+
+/// Decide which side a new range order sells, or `InvalidTickRange` if the
+/// range straddles the current tick (i.e. it would not be single-sided).
+///
+/// Returns `true` when the order sells token_a for token_b.
+pub fn range_order_side(current_tick: i32, tick_lower: i32, tick_upper: i32) -> Result<bool, AmmError> {
+    if tick_lower > current_tick {
+        Ok(true)
+    } else if tick_upper <= current_tick {
+        Ok(false)
+    } else {
+        Err(AmmError::InvalidTickRange)
+    }
+}
+
+/// The tick whose crossing fills an order: reaching `tick_upper` from below for
+/// token_a sellers, dropping below `tick_lower` for token_b sellers.
+pub fn fill_tick(sell_token_a: bool, tick_lower: i32, tick_upper: i32) -> i32 {
+    if sell_token_a {
+        tick_upper
+    } else {
+        tick_lower
+    }
+}
+
+/// Returns `true` once the pool price has fully crossed the order's range.
+pub fn is_filled(order: &RangeOrder, position: &LpPosition, current_tick: i32) -> bool {
+    if order.sell_token_a {
+        current_tick >= position.tick_upper
+    } else {
+        current_tick < position.tick_lower
+    }
+}
+
+/// Fill the orders on the ticks a swap crossed moving the pool from
+/// `old_tick` to its current tick, up to `MAX_FILLS_PER_SETTLE` orders.
+///
+/// Returns the number of orders visited.
+pub fn settle_crossed_orders(env: &Env, pool_key: &PoolKey, pool: &PoolState, old_tick: i32) -> u32 {
+    // Rising prices fill token_a sellers at ticks (old, new]; falling prices
+    // fill token_b sellers at ticks (new, old].
+    let (from, to, sell_token_a) = if pool.current_tick > old_tick {
+        (old_tick + 1, pool.current_tick, true)
+    } else if pool.current_tick < old_tick {
+        (pool.current_tick + 1, old_tick, false)
+    } else {
+        return 0;
+    };
+
+    let mut visited = 0u32;
+    for tick in from..=to {
+        if visited >= MAX_FILLS_PER_SETTLE {
+            break;
+        }
+        visited += settle_tick(env, pool_key, pool, tick, sell_token_a, MAX_FILLS_PER_SETTLE - visited);
+    }
+    visited
+}
+
+/// Fill up to `max` orders indexed at `tick` on the given side. Orders the
+/// price has since moved back out of stay indexed.
+///
+/// Returns the number of orders visited.
+pub fn settle_tick(
+    env: &Env,
+    pool_key: &PoolKey,
+    pool: &PoolState,
+    tick: i32,
+    sell_token_a: bool,
+    max: u32,
+) -> u32 {
+    let ids = read_tick_orders(env, pool_key, tick, sell_token_a);
+    if ids.is_empty() {
+        return 0;
+    }
+
+    let mut remaining = Vec::new(env);
+    let mut visited = 0u32;
+    for id in ids.iter() {
+        if visited >= max {
+            remaining.push_back(id);
+            continue;
+        }
+        visited += 1;
+        if !fill_order(env, pool_key, pool, id) {
+            remaining.push_back(id);
+        }
+    }
+
+    if remaining.len() != ids.len() {
+        write_tick_orders(env, pool_key, tick, sell_token_a, &remaining);
+    }
+    visited
+}
+
+/// Close one order if the price has fully crossed it, paying the output token
+/// and any accrued fees to the owner. Returns `false` if the order stays open.
+fn fill_order(env: &Env, pool_key: &PoolKey, pool: &PoolState, id: u64) -> bool {
+    let order = match read_range_order(env, id) {
+        Some(o) => o,
+        None => return true,
+    };
+    let mut pos = match read_position(env, id) {
+        Some(p) => p,
+        None => {
+            env.storage().persistent().remove(&order_key(id));
+            return true;
+        }
+    };
+    if !is_filled(&order, &pos, pool.current_tick) {
+        return false;
+    }
+
+    let (fgi_a, fgi_b) = fee_growth_inside(env, pool_key, pool, pos.tick_lower, pos.tick_upper);
+    accrue_position_fees(&mut pos, fgi_a, fgi_b);
+
+    let (sqrt_lower, sqrt_upper) = match (tick_to_sqrt_price(pos.tick_lower), tick_to_sqrt_price(pos.tick_upper)) {
+        (Ok(l), Ok(u)) => (l, u),
+        _ => return false,
+    };
+    let (amt_a, amt_b) = amounts_for_liquidity(pos.liquidity, sqrt_lower, sqrt_upper, pool.sqrt_price);
+    let out_a = amt_a + pos.tokens_owed_a;
+    let out_b = amt_b + pos.tokens_owed_b;
+
+    if out_a > 0 {
+        let tok = token::Client::new(env, &pool.token_a);
+        tok.transfer(&env.current_contract_address(), &pos.owner, &out_a);
+    }
+    if out_b > 0 {
+        let tok = token::Client::new(env, &pool.token_b);
+        tok.transfer(&env.current_contract_address(), &pos.owner, &out_b);
+    }
+
+    delete_position(env, id);
+    deindex_owner_position(env, &pos.owner, id);
+    env.storage().persistent().remove(&order_key(id));
+
+    env.events().publish(
+        (symbol_short!("ord_fill"),),
+        (id, pos.owner, out_a, out_b),
+    );
+    true
+}
+
+// ─── Rebalancing logic ─────────────────────────────────────────────────────────
+
+/// Compute a range of the same width as `[tick_lower, tick_upper)` centred on
+/// `current_tick`, or `RebalanceOutOfBounds` if it would leave the owner's bounds.
+pub fn recentered_range(
+    config: &RebalanceConfig,
+    tick_lower: i32,
+    tick_upper: i32,
+    current_tick: i32,
+) -> Result<(i32, i32), AmmError> {
+    let width = tick_upper - tick_lower;
+    let new_lower = current_tick - width / 2;
+    let new_upper = new_lower + width;
+    if new_lower < config.min_tick || new_upper > config.max_tick {
+        return Err(AmmError::RebalanceOutOfBounds);
+    }
+    Ok((new_lower, new_upper))
+}
@@ -7,10 +7,13 @@ use soroban_sdk::{
     token::StellarAssetClient,
     vec, Address, Env,
 };
-use crate::{AmmContract, AmmContractClient};
+use crate::{AmmContract, AmmContractClient, AmmError};
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
+/// Deadline far enough in the future that it never expires in these tests.
+const DEADLINE: u64 = u64::MAX;
+
 fn default_cb() -> CircuitBreakerConfig {
     CircuitBreakerConfig {
         max_volume_per_period: 1_000_000_000i128,
@@ -116,6 +119,7 @@ fn test_add_liquidity_in_range() {
     let r = client.add_liquidity(
         &lp, &ta, &tb, &-512i32, &512i32,
         &1_000_000i128, &1_000_000i128, &0i128, &0i128,
+        &DEADLINE, &None,
     );
     assert!(r.liquidity > 0);
     assert!(r.position_id >= 1);
@@ -132,6 +136,7 @@ fn test_invalid_tick_range_fails() {
     let result = client.try_add_liquidity(
         &lp, &ta, &tb, &512i32, &-512i32,
         &1_000_000i128, &1_000_000i128, &0i128, &0i128,
+        &DEADLINE, &None,
     );
     assert!(result.is_err());
 }
@@ -149,6 +154,7 @@ fn test_get_position_after_add() {
     let r = client.add_liquidity(
         &lp, &ta, &tb, &-512i32, &512i32,
         &1_000_000i128, &1_000_000i128, &0i128, &0i128,
+        &DEADLINE, &None,
     );
     let pos = client.get_position(&r.position_id).unwrap();
     assert_eq!(pos.tick_lower, -512);
@@ -166,8 +172,8 @@ fn test_get_lp_positions_by_owner() {
     let lp = Address::generate(&env);
     mint(&env, &ta, &tb, &lp, 10_000_000);
 
-    client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &500_000i128, &500_000i128, &0i128, &0i128);
-    client.add_liquidity(&lp, &ta, &tb, &-1024i32, &1024i32, &500_000i128, &500_000i128, &0i128, &0i128);
+    client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &500_000i128, &500_000i128, &0i128, &0i128, &DEADLINE, &None);
+    client.add_liquidity(&lp, &ta, &tb, &-1024i32, &1024i32, &500_000i128, &500_000i128, &0i128, &0i128, &DEADLINE, &None);
 
     assert_eq!(client.get_lp_positions(&lp).len(), 2);
 }
@@ -182,8 +188,8 @@ fn test_remove_liquidity_full() {
     let lp = Address::generate(&env);
     mint(&env, &ta, &tb, &lp, 10_000_000);
 
-    let r = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128);
-    client.remove_liquidity(&lp, &r.position_id, &r.liquidity, &0i128, &0i128);
+    let r = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128, &DEADLINE, &None);
+    client.remove_liquidity(&lp, &r.position_id, &r.liquidity, &0i128, &0i128, &DEADLINE, &None);
     assert!(client.get_position(&r.position_id).is_none());
 }
 
@@ -197,9 +203,9 @@ fn test_remove_liquidity_partial() {
     let lp = Address::generate(&env);
     mint(&env, &ta, &tb, &lp, 10_000_000);
 
-    let r = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128);
+    let r = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128, &DEADLINE, &None);
     let half = r.liquidity / 2;
-    client.remove_liquidity(&lp, &r.position_id, &half, &0i128, &0i128);
+    client.remove_liquidity(&lp, &r.position_id, &half, &0i128, &0i128, &DEADLINE, &None);
     let pos = client.get_position(&r.position_id).unwrap();
     assert_eq!(pos.liquidity, r.liquidity - half);
 }
@@ -215,8 +221,8 @@ fn test_remove_liquidity_wrong_owner_fails() {
     let intruder = Address::generate(&env);
     mint(&env, &ta, &tb, &lp, 10_000_000);
 
-    let r = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128);
-    let result = client.try_remove_liquidity(&intruder, &r.position_id, &r.liquidity, &0i128, &0i128);
+    let r = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128, &DEADLINE, &None);
+    let result = client.try_remove_liquidity(&intruder, &r.position_id, &r.liquidity, &0i128, &0i128, &DEADLINE, &None);
     assert!(result.is_err());
 }
 
@@ -230,7 +236,7 @@ fn test_estimate_il_at_entry_near_zero() {
     let lp = Address::generate(&env);
     mint(&env, &ta, &tb, &lp, 10_000_000);
 
-    let r = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128);
+    let r = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128, &DEADLINE, &None);
     let il = client.estimate_il(&r.position_id);
     assert!(il.abs() < 100, "IL at entry should be < 100 bps, got {}", il);
 }
@@ -245,7 +251,7 @@ fn test_capital_efficiency_returns_valid_range() {
     let lp = Address::generate(&env);
     mint(&env, &ta, &tb, &lp, 10_000_000);
 
-    client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128);
+    client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128, &DEADLINE, &None);
     let eff = client.get_capital_efficiency(&ta, &tb);
     assert!(eff >= 0 && eff <= 10_000);
 }
@@ -289,6 +295,7 @@ fn test_pause_blocks_add_liquidity() {
     let result = client.try_add_liquidity(
         &lp, &ta, &tb, &-512i32, &512i32,
         &1_000_000i128, &1_000_000i128, &0i128, &0i128,
+        &DEADLINE, &None,
     );
     assert!(result.is_err());
 }
@@ -307,6 +314,7 @@ fn test_unpause_restores_operations() {
     let result = client.try_add_liquidity(
         &lp, &ta, &tb, &-512i32, &512i32,
         &1_000_000i128, &1_000_000i128, &0i128, &0i128,
+        &DEADLINE, &None,
     );
     assert!(result.is_ok());
 }
@@ -323,8 +331,8 @@ fn test_multiple_positions_same_pool() {
     mint(&env, &ta, &tb, &lp1, 10_000_000);
     mint(&env, &ta, &tb, &lp2, 10_000_000);
 
-    let r1 = client.add_liquidity(&lp1, &ta, &tb, &-512i32, &512i32, &500_000i128, &500_000i128, &0i128, &0i128);
-    let r2 = client.add_liquidity(&lp2, &ta, &tb, &-1024i32, &1024i32, &500_000i128, &500_000i128, &0i128, &0i128);
+    let r1 = client.add_liquidity(&lp1, &ta, &tb, &-512i32, &512i32, &500_000i128, &500_000i128, &0i128, &0i128, &DEADLINE, &None);
+    let r2 = client.add_liquidity(&lp2, &ta, &tb, &-1024i32, &1024i32, &500_000i128, &500_000i128, &0i128, &0i128, &DEADLINE, &None);
 
     assert_ne!(r1.position_id, r2.position_id);
     assert_eq!(client.get_position(&r1.position_id).unwrap().tick_lower, -512);
@@ -370,3 +378,408 @@ fn test_all_three_fee_tiers_create_pools() {
         assert_eq!(pool.dynamic_fee_bps, tier);
     }
 }
+
+// ── Range orders ──────────────────────────────────────────────────────────────
+
+#[test]
+fn test_place_range_order_above_sells_token_a() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 10_000_000);
+
+    let r = client.place_range_order(&lp, &ta, &tb, &2i32, &4i32, &1_000_000i128, &DEADLINE);
+    assert!(r.liquidity > 0);
+    assert!(r.amount_a > 0);
+    assert_eq!(r.amount_b, 0);
+
+    let order = client.get_range_order(&r.position_id).unwrap();
+    assert!(order.sell_token_a);
+    assert_eq!(order.fill_tick, 4);
+    assert_eq!(client.get_range_orders_at_tick(&ta, &tb, &4i32, &true), vec![&env, r.position_id]);
+}
+
+#[test]
+fn test_place_range_order_straddling_current_tick_fails() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 10_000_000);
+
+    let result = client.try_place_range_order(&lp, &ta, &tb, &-4i32, &4i32, &1_000_000i128, &DEADLINE);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_range_order_fills_when_price_crosses() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let trader = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 1_000_000_000);
+    mint(&env, &ta, &tb, &maker, 10_000_000);
+    mint(&env, &ta, &tb, &trader, 1_000_000_000);
+
+    client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &100_000_000i128, &100_000_000i128, &0i128, &0i128, &DEADLINE, &None);
+    let order = client.place_range_order(&maker, &ta, &tb, &1i32, &2i32, &1_000_000i128, &DEADLINE);
+
+    let tb_client = soroban_sdk::token::Client::new(&env, &tb);
+    let maker_b_before = tb_client.balance(&maker);
+
+    // Buy token_a until the price moves through the order's range
+    for _ in 0..4 {
+        client.swap(&trader, &tb, &ta, &10_000_000i128, &0i128, &DEADLINE, &None);
+    }
+    assert!(client.get_pool(&ta, &tb).unwrap().current_tick >= 2);
+
+    assert!(client.get_position(&order.position_id).is_none());
+    assert!(client.get_range_order(&order.position_id).is_none());
+    assert_eq!(client.get_range_orders_at_tick(&ta, &tb, &2i32, &true).len(), 0);
+    assert!(tb_client.balance(&maker) > maker_b_before);
+}
+
+#[test]
+fn test_remove_range_order_closes_it() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let maker = Address::generate(&env);
+    mint(&env, &ta, &tb, &maker, 10_000_000);
+
+    let r = client.place_range_order(&maker, &ta, &tb, &-8i32, &-4i32, &1_000_000i128, &DEADLINE);
+    assert!(!client.get_range_order(&r.position_id).unwrap().sell_token_a);
+    client.remove_liquidity(&maker, &r.position_id, &r.liquidity, &0i128, &0i128, &DEADLINE, &None);
+    assert!(client.get_range_order(&r.position_id).is_none());
+    assert_eq!(client.get_range_orders_at_tick(&ta, &tb, &-8i32, &false).len(), 0);
+}
+
+#[test]
+fn test_swap_fill_cap_leaves_rest_to_keeper() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    let maker = Address::generate(&env);
+    let trader = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 1_000_000_000);
+    mint(&env, &ta, &tb, &maker, 100_000_000);
+    mint(&env, &ta, &tb, &trader, 1_000_000_000);
+
+    client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &100_000_000i128, &100_000_000i128, &0i128, &0i128, &DEADLINE, &None);
+
+    // Dust orders are refused
+    assert!(client.try_place_range_order(&maker, &ta, &tb, &1i32, &2i32, &999i128, &DEADLINE).is_err());
+
+    // One more order than a swap may fill, all on the same tick
+    for _ in 0..7 {
+        client.place_range_order(&maker, &ta, &tb, &1i32, &2i32, &1_000i128, &DEADLINE);
+    }
+    assert_eq!(client.get_range_orders_at_tick(&ta, &tb, &2i32, &true).len(), 7);
+
+    for _ in 0..4 {
+        client.swap(&trader, &tb, &ta, &10_000_000i128, &0i128, &DEADLINE, &None);
+    }
+    assert!(client.get_pool(&ta, &tb).unwrap().current_tick >= 2);
+    assert_eq!(client.get_range_orders_at_tick(&ta, &tb, &2i32, &true).len(), 1);
+
+    assert_eq!(client.settle_range_orders(&ta, &tb, &2i32, &true), 1);
+    assert_eq!(client.get_range_orders_at_tick(&ta, &tb, &2i32, &true).len(), 0);
+}
+
+// ── Rebalancing ───────────────────────────────────────────────────────────────
+
+#[test]
+fn test_rebalance_requires_opt_in() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 10_000_000);
+
+    let r = client.add_liquidity(&lp, &ta, &tb, &16i32, &32i32, &1_000_000i128, &0i128, &0i128, &0i128, &DEADLINE, &None);
+    assert!(client.try_rebalance_position(&keeper, &r.position_id).is_err());
+}
+
+#[test]
+fn test_rebalance_in_range_position_fails() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 10_000_000);
+
+    let r = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128, &DEADLINE, &None);
+    client.enable_rebalancing(&lp, &r.position_id, &-2048i32, &2048i32, &1i128, &i128::MAX);
+    assert!(client.try_rebalance_position(&keeper, &r.position_id).is_err());
+}
+
+#[test]
+fn test_rebalance_recenters_out_of_range_position() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 1_000_000_000);
+    mint(&env, &ta, &tb, &owner, 10_000_000);
+
+    client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &100_000_000i128, &100_000_000i128, &0i128, &0i128, &DEADLINE, &None);
+    let r = client.add_liquidity(&owner, &ta, &tb, &16i32, &32i32, &1_000_000i128, &0i128, &0i128, &0i128, &DEADLINE, &None);
+    let price = client.get_pool(&ta, &tb).unwrap().sqrt_price;
+    let band_min = price - price / 1_000;
+    client.enable_rebalancing(&owner, &r.position_id, &-64i32, &64i32, &band_min, &(price + price / 1_000));
+
+    let pos = client.rebalance_position(&keeper, &r.position_id);
+    let pool = client.get_pool(&ta, &tb).unwrap();
+    assert_eq!(pos.tick_upper - pos.tick_lower, 16);
+    assert!(pos.tick_lower <= pool.current_tick && pool.current_tick < pos.tick_upper);
+    assert!(pos.liquidity > 0);
+    // The keeper swap sold token A but never below the owner's band
+    assert!(pool.sqrt_price >= band_min && pool.sqrt_price <= price);
+}
+
+#[test]
+fn test_rebalance_refuses_a_sandwiched_price() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 1_000_000_000);
+    mint(&env, &ta, &tb, &owner, 10_000_000);
+    mint(&env, &ta, &tb, &keeper, 1_000_000_000);
+
+    client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &100_000_000i128, &100_000_000i128, &0i128, &0i128, &DEADLINE, &None);
+    let r = client.add_liquidity(&owner, &ta, &tb, &16i32, &32i32, &1_000_000i128, &0i128, &0i128, &0i128, &DEADLINE, &None);
+    let price = client.get_pool(&ta, &tb).unwrap().sqrt_price;
+    assert!(matches!(
+        client.try_enable_rebalancing(&owner, &r.position_id, &-64i32, &64i32, &price, &price),
+        Err(Ok(AmmError::InvalidTickRange))
+    ));
+    let band_min = price - price / 100_000;
+    client.enable_rebalancing(&owner, &r.position_id, &-64i32, &64i32, &band_min, &(price + price / 100_000));
+
+    // A keeper front-running its own rebalance with a large sell of token A
+    // pushes the price below the owner's band
+    client.swap(&keeper, &ta, &tb, &1_000_000i128, &0i128, &DEADLINE, &None);
+    assert!(client.get_pool(&ta, &tb).unwrap().sqrt_price < band_min);
+    assert!(matches!(
+        client.try_rebalance_position(&keeper, &r.position_id),
+        Err(Ok(AmmError::PriceLimitExceeded))
+    ));
+}
+
+#[test]
+fn test_rebalance_outside_owner_bounds_fails() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 1_000_000_000);
+    mint(&env, &ta, &tb, &owner, 10_000_000);
+
+    client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &100_000_000i128, &100_000_000i128, &0i128, &0i128, &DEADLINE, &None);
+    let r = client.add_liquidity(&owner, &ta, &tb, &16i32, &32i32, &1_000_000i128, &0i128, &0i128, &0i128, &DEADLINE, &None);
+    client.enable_rebalancing(&owner, &r.position_id, &10i32, &64i32, &1i128, &i128::MAX);
+    assert!(client.try_rebalance_position(&keeper, &r.position_id).is_err());
+
+    client.disable_rebalancing(&owner, &r.position_id);
+    assert!(client.get_rebalance_config(&r.position_id).is_none());
+}
+
+// ── Analytics ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_liquidity_distribution_by_tick_range() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 10_000_000);
+
+    let r1 = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &500_000i128, &500_000i128, &0i128, &0i128, &DEADLINE, &None);
+    let r2 = client.add_liquidity(&lp, &ta, &tb, &0i32, &1024i32, &500_000i128, &500_000i128, &0i128, &0i128, &DEADLINE, &None);
+
+    assert_eq!(client.get_pool_position_count(&ta, &tb), 2);
+    let dist = client.get_liquidity_distribution(&ta, &tb, &-1024i32, &1024i32, &512i32, &0u32, &10u32);
+    assert_eq!(dist.len(), 4);
+    assert_eq!(dist.get(0).unwrap().liquidity, 0);
+    assert_eq!(dist.get(1).unwrap().liquidity, r1.liquidity);
+    assert_eq!(dist.get(2).unwrap().liquidity, r1.liquidity + r2.liquidity);
+    assert_eq!(dist.get(3).unwrap().liquidity, r2.liquidity);
+
+    // One position per page; the pages add up to the whole
+    let first = client.get_liquidity_distribution(&ta, &tb, &-1024i32, &1024i32, &512i32, &0u32, &1u32);
+    let second = client.get_liquidity_distribution(&ta, &tb, &-1024i32, &1024i32, &512i32, &1u32, &1u32);
+    assert_eq!(first.get(1).unwrap().liquidity, r1.liquidity);
+    assert_eq!(second.get(1).unwrap().liquidity, 0);
+    assert_eq!(second.get(3).unwrap().liquidity, r2.liquidity);
+
+    // Burning the first position moves the last into its slot
+    client.remove_liquidity(&lp, &r1.position_id, &r1.liquidity, &0i128, &0i128, &DEADLINE, &None);
+    assert_eq!(client.get_pool_position_count(&ta, &tb), 1);
+    let dist = client.get_liquidity_distribution(&ta, &tb, &-1024i32, &1024i32, &512i32, &0u32, &1u32);
+    assert_eq!(dist.get(1).unwrap().liquidity, 0);
+    assert_eq!(dist.get(2).unwrap().liquidity, r2.liquidity);
+}
+
+#[test]
+fn test_liquidity_distribution_rejects_bad_spacing() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    assert!(client.try_get_liquidity_distribution(&ta, &tb, &-512i32, &512i32, &0i32, &0u32, &10u32).is_err());
+    assert!(client.try_get_liquidity_distribution(&ta, &tb, &-8192i32, &8192i32, &1i32, &0u32, &10u32).is_err());
+}
+
+#[test]
+fn test_swap_updates_fees_and_volume_buckets() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 100_000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    let trader = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 1_000_000_000);
+    mint(&env, &ta, &tb, &trader, 1_000_000_000);
+
+    client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &100_000_000i128, &100_000_000i128, &0i128, &0i128, &DEADLINE, &None);
+    assert_eq!(client.get_cumulative_fees(&ta, &tb), (0, 0));
+
+    let s1 = client.swap(&trader, &ta, &tb, &1_000_000i128, &0i128, &DEADLINE, &None);
+    env.ledger().with_mut(|l| l.timestamp = 100_000 + 3_600);
+    let s2 = client.swap(&trader, &tb, &ta, &1_000_000i128, &0i128, &DEADLINE, &None);
+
+    assert_eq!(client.get_cumulative_fees(&ta, &tb), (s1.fee_paid, s2.fee_paid));
+
+    let buckets = client.get_volume_24h(&ta, &tb);
+    assert_eq!(buckets.len(), 24);
+    let last = buckets.get(23).unwrap();
+    let prev = buckets.get(22).unwrap();
+    assert_eq!((prev.volume_a, prev.volume_b), (s1.amount_in, 0));
+    assert_eq!((last.volume_a, last.volume_b), (0, s2.amount_in));
+}
+
+#[test]
+fn test_uncollected_fees_matches_collect() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    let trader = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 1_000_000_000);
+    mint(&env, &ta, &tb, &trader, 1_000_000_000);
+
+    let r = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &100_000_000i128, &100_000_000i128, &0i128, &0i128, &DEADLINE, &None);
+    client.swap(&trader, &ta, &tb, &10_000_000i128, &0i128, &DEADLINE, &None);
+
+    let pending = client.get_uncollected_fees(&r.position_id);
+    assert!(pending.0 > 0);
+    // Querying does not collect
+    assert_eq!(client.get_uncollected_fees(&r.position_id), pending);
+    assert_eq!(client.collect_fees(&lp, &r.position_id), pending);
+    assert_eq!(client.get_uncollected_fees(&r.position_id), (0, 0));
+}
+
+// ── Deadlines and price limits ────────────────────────────────────────────────
+
+#[test]
+fn test_expired_deadline_rejects_calls() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 1_000_000_000);
+
+    // A deadline equal to the current timestamp is still valid
+    let r = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &100_000_000i128, &100_000_000i128, &0i128, &0i128, &1000u64, &None);
+
+    env.ledger().with_mut(|l| l.timestamp = 1001);
+    assert!(matches!(client.try_add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000i128, &1_000i128, &0i128, &0i128, &1000u64, &None), Err(Ok(AmmError::DeadlineExpired))));
+    assert!(matches!(client.try_swap(&lp, &ta, &tb, &1_000i128, &0i128, &1000u64, &None), Err(Ok(AmmError::DeadlineExpired))));
+    assert!(matches!(client.try_remove_liquidity(&lp, &r.position_id, &r.liquidity, &0i128, &0i128, &1000u64, &None), Err(Ok(AmmError::DeadlineExpired))));
+    assert!(matches!(client.try_place_range_order(&lp, &ta, &tb, &2i32, &4i32, &1_000i128, &1000u64), Err(Ok(AmmError::DeadlineExpired))));
+}
+
+#[test]
+fn test_swap_stops_at_sqrt_price_limit() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    let trader = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 1_000_000_000);
+    mint(&env, &ta, &tb, &trader, 1_000_000_000);
+
+    client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &100_000_000i128, &100_000_000i128, &0i128, &0i128, &DEADLINE, &None);
+    let start = client.get_pool(&ta, &tb).unwrap().sqrt_price;
+    let limit = start - 1_000_000_000_000;
+
+    // Selling token_a pushes the price down; it must not pass the limit
+    let r = client.swap(&trader, &ta, &tb, &10_000_000i128, &0i128, &DEADLINE, &Some(limit));
+    assert_eq!(r.new_sqrt_price, limit);
+
+    // Limit already reached: nothing left to trade
+    assert!(matches!(client.try_swap(&trader, &ta, &tb, &1_000i128, &0i128, &DEADLINE, &Some(limit)), Err(Ok(AmmError::PriceLimitExceeded))));
+}
+
+#[test]
+fn test_liquidity_calls_respect_price_band() {
+    let env = Env::default();
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let (ta, tb) = make_pool(&env, &client, &admin);
+    let lp = Address::generate(&env);
+    mint(&env, &ta, &tb, &lp, 1_000_000_000);
+
+    let price = client.get_pool(&ta, &tb).unwrap().sqrt_price;
+    // Price above the band
+    assert!(matches!(client.try_add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128, &DEADLINE, &Some((price - 10, price - 1))), Err(Ok(AmmError::PriceLimitExceeded))));
+    // Price below the band
+    assert!(matches!(client.try_add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128, &DEADLINE, &Some((price + 1, price + 10))), Err(Ok(AmmError::PriceLimitExceeded))));
+    let r = client.add_liquidity(&lp, &ta, &tb, &-512i32, &512i32, &1_000_000i128, &1_000_000i128, &0i128, &0i128, &DEADLINE, &Some((price, price)));
+    assert!(matches!(client.try_remove_liquidity(&lp, &r.position_id, &r.liquidity, &0i128, &0i128, &DEADLINE, &Some((price - 10, price - 1))), Err(Ok(AmmError::PriceLimitExceeded))));
+    assert!(matches!(client.try_remove_liquidity(&lp, &r.position_id, &r.liquidity, &0i128, &0i128, &DEADLINE, &Some((price + 1, price + 10))), Err(Ok(AmmError::PriceLimitExceeded))));
+    client.remove_liquidity(&lp, &r.position_id, &r.liquidity, &0i128, &0i128, &DEADLINE, &Some((price - 10, price + 10)));
+}
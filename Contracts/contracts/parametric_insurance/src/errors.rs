@@ -37,7 +37,7 @@ pub enum InsuranceError {
     // ── Risk pool ─────────────────────────────────────────────────────────────
     /// Pool does not hold enough unreserved liquidity for this coverage
     InsufficientPoolLiquidity = 6011,
-    /// LP is attempting to withdraw more than their recorded deposit
+    /// LP is attempting to redeem more shares than they hold
     InsufficientLPBalance = 6012,
     /// Withdrawal exceeds currently available (unreserved) pool liquidity
     WithdrawalExceedsAvailable = 6013,
@@ -53,4 +53,46 @@ pub enum InsuranceError {
     // ── Oracle ────────────────────────────────────────────────────────────────
     /// Oracle query failed (stale, insufficient sources, or call error)
     OracleFailure = 6017,
+
+    // ── Pricing ───────────────────────────────────────────────────────────────
+    /// Offered premium is below the quoted premium for this policy
+    PremiumTooLow = 6018,
+    /// Oracle value already satisfies the trigger; the risk is not insurable
+    TriggerAlreadyMet = 6019,
+    /// Pricing parameters are out of range
+    InvalidPricingConfig = 6020,
+
+    // ── Payouts ───────────────────────────────────────────────────────────────
+    /// Tiers or exhaustion point are inconsistent with the trigger condition
+    InvalidPayoutSchedule = 6021,
+    /// Composite trigger is empty, too large or requires no observations
+    InvalidTriggerExpression = 6022,
+
+    // ── Exposure / reinsurance ────────────────────────────────────────────────
+    /// Coverage would exceed the per-feed exposure limit
+    FeedExposureExceeded = 6023,
+    /// Coverage would exceed the per-policy-type exposure limit
+    TypeExposureExceeded = 6024,
+    /// Exposure limits or reinsurance terms are out of range
+    InvalidRiskLimits = 6025,
+
+    // ── Transfers ─────────────────────────────────────────────────────────────
+    /// Policy cannot be transferred to its current holder
+    InvalidRecipient = 6026,
+
+    // ── Refunds ───────────────────────────────────────────────────────────────
+    /// Refund fee above 100% or negative flat fee
+    InvalidRefundConfig = 6027,
+
+    // ── Withdrawal queue ──────────────────────────────────────────────────────
+    /// Instant withdrawals are disabled while a cooldown is configured
+    WithdrawalCooldownActive = 6028,
+    /// No queued withdrawal request with this ID
+    WithdrawalRequestNotFound = 6029,
+    /// Outstanding shares are backed by no liquidity; deposits are refused
+    PoolWipedOut = 6030,
+
+    // ── Claims ────────────────────────────────────────────────────────────────
+    /// The pool token refused the claim transfer to the policyholder
+    PayoutFailed = 6031,
 }
@@ -23,24 +23,121 @@
 //! | withdraw_liquidity  | contract → provider               |
 //!
 //! All amounts are denominated in `pool_token`.
+//!
+//! # LP shares
+//!
+//! Liquidity providers hold pool shares rather than nominal balances. Premiums
+//! raise `total_liquidity` and payouts lower it, so every provider's claim moves
+//! pro-rata with the pool:
+//!
+//! ```text
+//! shares_minted = amount × total_shares / total_liquidity
+//! redeemed      = shares × total_liquidity / total_shares
+//! ```
+//!
+//! The first deposit into an empty pool locks `DEAD_SHARES` of its shares
+//! with no owner, so a donation into a near-empty pool cannot make one share
+//! worth more than later deposits. Deposits are refused while outstanding
+//! shares are backed by nothing, and liquidity left in a pool with no shares
+//! moves to its `surplus` for the admin instead of going to the next
+//! depositor.
+//!
+//! # Reinsurance and exposure caps
+//!
+//! A second set of capital providers can back a reinsurance tranche. Once the
+//! admin sets a retention, coverage above it on each new policy is ceded to
+//! the tranche (as far as its free capital allows) in exchange for a share of
+//! that policy's premium. Payouts fill the retained layer first, then the
+//! ceded layer:
+//!
+//! ```text
+//! coverage  = retained (primary pool) + ceded (reinsurance tranche)
+//! ceded     = min(coverage − retention, tranche available)
+//! ```
+//!
+//! Outstanding coverage is also capped per oracle feed and per `PolicyType`
+//! as a fraction of total capital (primary + tranche).
+//!
+//! # Graduated payouts
+//!
+//! Besides all-or-nothing cover, policies may carry a tiered or linear
+//! `PayoutSchedule`. Each trigger check pays whatever the current oracle value
+//! entitles the holder to beyond previous payouts; the policy stays `Active`
+//! until the full coverage has been paid.
+//!
+//! # Composite triggers
+//!
+//! A policy may instead watch several feeds through a `CompositeTrigger`
+//! (AND / OR of feed conditions) that must hold for N consecutive fresh
+//! oracle observations before the full coverage is paid.
+//!
+//! # Policy transfers
+//!
+//! Active policies can be sold with `transfer_policy`, or moved by an
+//! approved spender with `transfer_policy_from`. Payouts and cancellation
+//! rights always follow the current `policyholder`.
+//!
+//! # Pricing
+//!
+//! Premiums are quoted by the `pricing` engine from coverage, duration,
+//! policy type, distance to the trigger and pool utilisation. `create_policy`
+//! rejects any premium below the current quote; see `quote_premium`.
+//!
+//! # Withdrawal queue
+//!
+//! Once the admin sets a withdrawal cooldown, LPs exit through
+//! `request_withdrawal`: the shares are locked immediately, keep bearing
+//! pool gains and losses through the cooldown, and are redeemed first-in
+//! first-out as coverage frees up, either when a policy expires or through
+//! `process_withdrawals`. Instant `withdraw_liquidity` is only available
+//! while the cooldown is zero.
+//!
+//! # Keepers
+//!
+//! After a market-wide event keepers call `check_triggers_for_feed`, which
+//! aggregates the feed once and settles every active policy watching it, and
+//! `sweep_expired` to reconcile lapsed policies. Both work in cursor-paged
+//! batches and pay a small per-action reward from the pool.
+//!
+//! # Refunds and renewal
+//!
+//! Cancelling refunds the unearned premium pro-rata, less the admin's
+//! `RefundConfig` fees (by default premiums are fully earned and nothing is
+//! refunded). `renew_policy` charges a fresh quote for the extension and
+//! pushes `end_time` out while the coverage stays reserved.
 
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, symbol_short, token, Address, Env, Symbol, Vec,
+    contract, contractimpl, symbol_short, token, Address, Env, Map, Symbol, Vec,
 };
 
-use shared::oracle::fetch_aggregate_price;
+use shared::oracle::{fetch_aggregate_price, AggregatePrice};
 
 mod errors;
+mod payout;
+mod pricing;
 mod storage;
+mod trigger;
 mod types;
 
 use errors::InsuranceError;
 use storage::InsuranceStorage;
-use types::{InsurancePolicy, OracleConfig, PolicyStatus, PolicyType, RiskPool, TriggerCondition};
+use types::{
+    ExposureLimits, InsurancePolicy, OracleConfig, PolicyStatus, PolicyType, PricingConfig,
+    RefundConfig, ReinsuranceConfig, RiskPool, TriggerCondition, WithdrawalRequest,
+    MAX_WITHDRAWALS_PER_EXPIRY,
+};
 
 // Re-export public types so tests and external callers can import from the crate root
+pub use types::{KeeperReport, DEAD_SHARES, MAX_KEEPER_BATCH, SHARE_PRECISION};
+pub use types::WithdrawalRequest as InsuranceWithdrawalRequest;
+pub use types::PricingConfig as InsurancePricingConfig;
+pub use types::RefundConfig as InsuranceRefundConfig;
+pub use types::{ExposureLimits as InsuranceExposureLimits, ReinsuranceConfig as InsuranceReinsuranceConfig};
+pub use types::{
+    CompositeTrigger, FeedCondition, PayoutSchedule, PayoutTier, TriggerExpression, TriggerKind,
+};
 pub use types::{InsurancePolicy as Policy, OracleConfig as InsuranceOracleConfig, PolicyStatus as Status, PolicyType as InsurancePolicyType, RiskPool as InsuranceRiskPool, TriggerCondition as InsuranceTriggerCondition};
 pub use errors::InsuranceError as Error;
 
@@ -126,6 +223,116 @@ impl ParametricInsuranceContract {
         Ok(())
     }
 
+    /// Replace the premium pricing parameters (admin only).
+    pub fn update_pricing_config(
+        env: Env,
+        admin: Address,
+        config: PricingConfig,
+    ) -> Result<(), InsuranceError> {
+        Self::require_admin_auth(&env, &admin)?;
+        pricing::validate_config(&config)?;
+
+        InsuranceStorage::set_pricing_config(&env, &config);
+
+        env.events()
+            .publish((symbol_short!("prc_upd"),), (admin, env.ledger().timestamp()));
+        Ok(())
+    }
+
+    /// Set the excess-of-loss terms for newly created policies (admin only).
+    ///
+    /// Existing policies keep the split they were created with.
+    pub fn set_reinsurance_config(
+        env: Env,
+        admin: Address,
+        config: ReinsuranceConfig,
+    ) -> Result<(), InsuranceError> {
+        Self::require_admin_auth(&env, &admin)?;
+        if config.retention < 0 || config.premium_share_bps as i128 > pricing::BPS {
+            return Err(InsuranceError::InvalidRiskLimits);
+        }
+
+        InsuranceStorage::set_reinsurance_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("re_cfg"),),
+            (admin, config.retention, config.premium_share_bps),
+        );
+        Ok(())
+    }
+
+    /// Replace the cancellation refund curve (admin only).
+    pub fn set_refund_config(
+        env: Env,
+        admin: Address,
+        config: RefundConfig,
+    ) -> Result<(), InsuranceError> {
+        Self::require_admin_auth(&env, &admin)?;
+        pricing::validate_refund_config(&config)?;
+
+        InsuranceStorage::set_refund_config(&env, &config);
+
+        env.events()
+            .publish((symbol_short!("rfd_upd"),), (admin, env.ledger().timestamp()));
+        Ok(())
+    }
+
+    /// Set how long LP withdrawal requests wait before fulfilment (admin only).
+    ///
+    /// A non-zero cooldown disables instant `withdraw_liquidity`.
+    pub fn set_withdrawal_cooldown(
+        env: Env,
+        admin: Address,
+        cooldown_secs: u64,
+    ) -> Result<(), InsuranceError> {
+        Self::require_admin_auth(&env, &admin)?;
+
+        InsuranceStorage::set_withdrawal_cooldown(&env, cooldown_secs);
+
+        env.events()
+            .publish((symbol_short!("wd_cool"),), (admin, cooldown_secs));
+        Ok(())
+    }
+
+    /// Set the reward paid to keepers per executed batch action (admin only).
+    pub fn set_keeper_reward(
+        env: Env,
+        admin: Address,
+        reward: i128,
+    ) -> Result<(), InsuranceError> {
+        Self::require_admin_auth(&env, &admin)?;
+        if reward < 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
+
+        InsuranceStorage::set_keeper_reward(&env, reward);
+
+        env.events()
+            .publish((symbol_short!("kpr_upd"),), (admin, reward));
+        Ok(())
+    }
+
+    /// Replace the per-feed / per-type exposure limits (admin only).
+    pub fn set_exposure_limits(
+        env: Env,
+        admin: Address,
+        limits: ExposureLimits,
+    ) -> Result<(), InsuranceError> {
+        Self::require_admin_auth(&env, &admin)?;
+        let valid = |bps: u32| bps > 0 && bps as i128 <= pricing::BPS;
+        if !valid(limits.max_feed_exposure_bps) || !valid(limits.max_type_exposure_bps) {
+            return Err(InsuranceError::InvalidRiskLimits);
+        }
+
+        InsuranceStorage::set_exposure_limits(&env, &limits);
+
+        env.events().publish(
+            (symbol_short!("exp_cfg"),),
+            (admin, limits.max_feed_exposure_bps, limits.max_type_exposure_bps),
+        );
+        Ok(())
+    }
+
     /// Pause all state-changing operations (admin only).
     pub fn pause(env: Env, admin: Address) -> Result<(), InsuranceError> {
         Self::require_admin_auth(&env, &admin)?;
@@ -148,15 +355,17 @@ impl ParametricInsuranceContract {
     // Liquidity pool management
     // =========================================================================
 
-    /// Deposit tokens into the shared risk pool.
+    /// Deposit tokens into the shared risk pool in exchange for pool shares.
     ///
     /// The caller must have approved the contract to transfer `amount` of
     /// `pool_token` before calling this function.
+    ///
+    /// Returns the number of shares minted.
     pub fn deposit_liquidity(
         env: Env,
         provider: Address,
         amount: i128,
-    ) -> Result<(), InsuranceError> {
+    ) -> Result<i128, InsuranceError> {
         Self::require_not_paused(&env)?;
         Self::require_initialized(&env)?;
 
@@ -169,52 +378,67 @@ impl ParametricInsuranceContract {
         let pool_token = InsuranceStorage::get_pool_token(&env)
             .ok_or(InsuranceError::NotInitialized)?;
 
+        let mut pool = InsuranceStorage::get_pool(&env);
+        if pool.is_wiped_out() {
+            return Err(InsuranceError::PoolWipedOut);
+        }
+
         // Transfer tokens from provider to this contract
         let token_client = token::Client::new(&env, &pool_token);
         token_client.transfer(&provider, &env.current_contract_address(), &amount);
 
-        // Update LP position
-        let current_balance = InsuranceStorage::get_lp_balance(&env, &provider);
-        InsuranceStorage::set_lp_balance(&env, &provider, current_balance + amount);
+        // Mint shares at the pre-deposit share value
+        pool.sweep_unowned();
+        let shares = pool.deposit(amount);
+        if shares <= 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
 
-        // Update pool accounting
-        let mut pool = InsuranceStorage::get_pool(&env);
-        pool.total_liquidity += amount;
+        let current_shares = InsuranceStorage::get_lp_shares(&env, &provider);
+        InsuranceStorage::set_lp_shares(&env, &provider, current_shares + shares);
         InsuranceStorage::set_pool(&env, &pool);
 
         env.events().publish(
             (symbol_short!("liq_dep"),),
-            (provider, amount, env.ledger().timestamp()),
+            (provider, amount, shares, env.ledger().timestamp()),
         );
 
-        Ok(())
+        Ok(shares)
     }
 
-    /// Withdraw previously deposited tokens from the risk pool.
+    /// Redeem `shares` for their current value in `pool_token`.
     ///
     /// Withdrawal is limited by:
-    ///   1. The caller's recorded deposit balance
+    ///   1. The caller's share balance
     ///   2. Currently unreserved pool liquidity (reserved = active policy coverage)
+    ///   3. No withdrawal cooldown being configured (use `request_withdrawal`)
+    ///
+    /// Returns the token amount paid out.
     pub fn withdraw_liquidity(
         env: Env,
         provider: Address,
-        amount: i128,
-    ) -> Result<(), InsuranceError> {
+        shares: i128,
+    ) -> Result<i128, InsuranceError> {
         Self::require_not_paused(&env)?;
         Self::require_initialized(&env)?;
 
-        if amount <= 0 {
+        if shares <= 0 {
             return Err(InsuranceError::InvalidAmount);
         }
 
         provider.require_auth();
 
-        let lp_balance = InsuranceStorage::get_lp_balance(&env, &provider);
-        if lp_balance < amount {
+        if InsuranceStorage::get_withdrawal_cooldown(&env) > 0 {
+            return Err(InsuranceError::WithdrawalCooldownActive);
+        }
+
+        let lp_shares = InsuranceStorage::get_lp_shares(&env, &provider);
+        if lp_shares < shares {
             return Err(InsuranceError::InsufficientLPBalance);
         }
 
-        let pool = InsuranceStorage::get_pool(&env);
+        let mut pool = InsuranceStorage::get_pool(&env);
+        let amount = pool.value_of_shares(shares);
         if pool.available_liquidity() < amount {
             return Err(InsuranceError::WithdrawalExceedsAvailable);
         }
@@ -223,268 +447,1217 @@ impl ParametricInsuranceContract {
             .ok_or(InsuranceError::NotInitialized)?;
 
         // Transfer tokens from this contract back to the provider
-        let token_client = token::Client::new(&env, &pool_token);
-        token_client.transfer(&env.current_contract_address(), &provider, &amount);
+        if amount > 0 {
+            let token_client = token::Client::new(&env, &pool_token);
+            token_client.transfer(&env.current_contract_address(), &provider, &amount);
+        }
 
-        // Update LP position
-        InsuranceStorage::set_lp_balance(&env, &provider, lp_balance - amount);
+        // Burn shares
+        InsuranceStorage::set_lp_shares(&env, &provider, lp_shares - shares);
 
         // Update pool accounting
-        let mut pool = InsuranceStorage::get_pool(&env);
         pool.total_liquidity -= amount;
+        pool.total_shares -= shares;
         InsuranceStorage::set_pool(&env, &pool);
 
         env.events().publish(
             (symbol_short!("liq_wdraw"),),
-            (provider, amount, env.ledger().timestamp()),
+            (provider, amount, shares, env.ledger().timestamp()),
         );
 
-        Ok(())
+        Ok(amount)
     }
 
-    // =========================================================================
-    // Policy management
-    // =========================================================================
-
-    /// Create a new parametric insurance policy.
-    ///
-    /// The caller pays `premium_amount` of `pool_token` upfront.
-    /// `coverage_amount` is reserved from the pool for the policy duration.
-    ///
-    /// # Parameters
-    /// - `policyholder`      – Address receiving the payout on trigger
-    /// - `policy_type`       – Risk category
-    /// - `oracle_feed`       – Symbol identifying the oracle data feed (≤32 chars)
-    /// - `trigger_threshold` – Threshold value compared against live oracle reading
-    /// - `trigger_condition` – Comparison operator (GreaterThan, LessThan, …)
-    /// - `coverage_amount`   – Payout amount if trigger fires (in pool_token)
-    /// - `premium_amount`    – Premium cost paid by the policyholder (in pool_token)
-    /// - `duration_secs`     – Coverage duration in seconds from `now`
+    /// Lock `shares` and queue them for redemption after the cooldown.
     ///
-    /// Returns the new policy ID.
-    pub fn create_policy(
+    /// The request is fulfilled first-in first-out at the share value in
+    /// force at fulfilment, once it has matured and enough coverage has been
+    /// released. Returns the request ID.
+    pub fn request_withdrawal(
         env: Env,
-        policyholder: Address,
-        policy_type: PolicyType,
-        oracle_feed: Symbol,
-        trigger_threshold: i128,
-        trigger_condition: TriggerCondition,
-        coverage_amount: i128,
-        premium_amount: i128,
-        duration_secs: u64,
+        provider: Address,
+        shares: i128,
     ) -> Result<u64, InsuranceError> {
         Self::require_not_paused(&env)?;
         Self::require_initialized(&env)?;
 
-        if coverage_amount <= 0 || premium_amount <= 0 {
+        if shares <= 0 {
             return Err(InsuranceError::InvalidAmount);
         }
-        if duration_secs == 0 {
-            return Err(InsuranceError::InvalidDuration);
-        }
 
-        policyholder.require_auth();
+        provider.require_auth();
 
-        // Verify the pool has enough unreserved liquidity
-        let pool = InsuranceStorage::get_pool(&env);
-        if pool.available_liquidity() < coverage_amount {
-            return Err(InsuranceError::InsufficientPoolLiquidity);
+        let lp_shares = InsuranceStorage::get_lp_shares(&env, &provider);
+        if lp_shares < shares {
+            return Err(InsuranceError::InsufficientLPBalance);
         }
+        InsuranceStorage::set_lp_shares(&env, &provider, lp_shares - shares);
 
-        let pool_token = InsuranceStorage::get_pool_token(&env)
-            .ok_or(InsuranceError::NotInitialized)?;
-
-        // Collect premium from policyholder
-        let token_client = token::Client::new(&env, &pool_token);
-        token_client.transfer(
-            &policyholder,
-            &env.current_contract_address(),
-            &premium_amount,
-        );
-
-        // Mint the policy record
-        let policy_id = InsuranceStorage::next_policy_id(&env);
         let now = env.ledger().timestamp();
-
-        let policy = InsurancePolicy {
-            id: policy_id,
-            policyholder: policyholder.clone(),
-            policy_type,
-            coverage_amount,
-            premium_amount,
-            oracle_feed: oracle_feed.clone(),
-            trigger_threshold,
-            trigger_condition,
-            start_time: now,
-            end_time: now + duration_secs,
-            status: PolicyStatus::Active,
-            created_at: now,
+        let request = WithdrawalRequest {
+            id: InsuranceStorage::next_withdrawal_id(&env),
+            provider: provider.clone(),
+            shares,
+            requested_at: now,
+            unlock_at: now + InsuranceStorage::get_withdrawal_cooldown(&env),
         };
-
-        InsuranceStorage::set_policy(&env, &policy);
-
-        // Update pool accounting
-        let mut pool = InsuranceStorage::get_pool(&env);
-        pool.total_liquidity += premium_amount;        // premium enters the pool
-        pool.reserved_liquidity += coverage_amount;    // coverage is locked
-        pool.total_premiums_collected += premium_amount;
-        pool.total_policies += 1;
-        pool.active_policies += 1;
-        InsuranceStorage::set_pool(&env, &pool);
+        InsuranceStorage::set_withdrawal_request(&env, &request);
 
         env.events().publish(
-            (symbol_short!("pol_crt"),),
-            (
-                policy_id,
-                policyholder,
-                coverage_amount,
-                premium_amount,
-                now + duration_secs,
-            ),
+            (symbol_short!("wd_req"),),
+            (request.id, provider, shares, request.unlock_at),
         );
 
-        Ok(policy_id)
+        // With no cooldown the request may be fulfilled straight away
+        Self::process_withdrawal_queue(&env, MAX_WITHDRAWALS_PER_EXPIRY);
+
+        Ok(request.id)
     }
 
-    /// Cancel an active policy before a trigger fires.
-    ///
-    /// The premium is non-refundable (it has already been earned by the pool).
-    /// The reserved coverage is released back to available liquidity.
-    pub fn cancel_policy(
+    /// Withdraw a queued request and return its locked shares.
+    pub fn cancel_withdrawal(
         env: Env,
-        policyholder: Address,
-        policy_id: u64,
+        provider: Address,
+        request_id: u64,
     ) -> Result<(), InsuranceError> {
-        Self::require_not_paused(&env)?;
-
-        policyholder.require_auth();
+        Self::require_initialized(&env)?;
 
-        let mut policy = InsuranceStorage::get_policy(&env, policy_id)
-            .ok_or(InsuranceError::PolicyNotFound)?;
+        provider.require_auth();
 
-        // Only the policyholder may cancel their own policy
-        if policy.policyholder != policyholder {
+        let request = InsuranceStorage::get_withdrawal_request(&env, request_id)
+            .ok_or(InsuranceError::WithdrawalRequestNotFound)?;
+        if request.provider != provider {
             return Err(InsuranceError::Unauthorized);
         }
 
-        match policy.status {
-            PolicyStatus::Active => {}
-            PolicyStatus::Cancelled => return Err(InsuranceError::PolicyAlreadyCancelled),
-            PolicyStatus::Claimed => return Err(InsuranceError::AlreadyClaimed),
-            PolicyStatus::Expired => return Err(InsuranceError::PolicyExpired),
-        }
-
-        // Release reserved coverage
-        let mut pool = InsuranceStorage::get_pool(&env);
-        pool.reserved_liquidity = pool.reserved_liquidity.saturating_sub(policy.coverage_amount);
-        pool.active_policies = pool.active_policies.saturating_sub(1);
-        InsuranceStorage::set_pool(&env, &pool);
-
-        policy.status = PolicyStatus::Cancelled;
-        InsuranceStorage::set_policy(&env, &policy);
+        InsuranceStorage::remove_withdrawal_request(&env, request_id);
+        let lp_shares = InsuranceStorage::get_lp_shares(&env, &provider);
+        InsuranceStorage::set_lp_shares(&env, &provider, lp_shares + request.shares);
 
         env.events().publish(
-            (symbol_short!("pol_cnl"),),
-            (policy_id, policyholder, env.ledger().timestamp()),
+            (symbol_short!("wd_cncl"),),
+            (request_id, provider, request.shares),
         );
 
         Ok(())
     }
 
-    // =========================================================================
-    // Oracle trigger / claims
-    // =========================================================================
-
-    /// Check whether the oracle condition for `policy_id` is satisfied and,
-    /// if so, automatically disburse the payout to the policyholder.
-    ///
-    /// This function is permissionless — anyone (oracle keeper, policyholder,
-    /// or an automated bot) can call it to finalise a triggered policy.
+    /// Fulfil matured withdrawal requests from unreserved liquidity, visiting
+    /// at most `limit` (capped at `MAX_KEEPER_BATCH`) queue entries.
     ///
-    /// Returns `true` if the trigger fired and the payout was executed;
-    /// `false` if the current oracle value does not satisfy the condition.
-    pub fn check_trigger(env: Env, policy_id: u64) -> Result<bool, InsuranceError> {
+    /// Permissionless; returns the number of requests fulfilled.
+    pub fn process_withdrawals(env: Env, limit: u32) -> Result<u32, InsuranceError> {
         Self::require_initialized(&env)?;
+        Ok(Self::process_withdrawal_queue(&env, limit.min(MAX_KEEPER_BATCH)))
+    }
 
-        let mut policy = InsuranceStorage::get_policy(&env, policy_id)
-            .ok_or(InsuranceError::PolicyNotFound)?;
+    /// Pay the surplus of both the risk pool and the reinsurance tranche to
+    /// `recipient` (admin only). Returns the amount paid.
+    pub fn withdraw_surplus(
+        env: Env,
+        admin: Address,
+        recipient: Address,
+    ) -> Result<i128, InsuranceError> {
+        Self::require_admin_auth(&env, &admin)?;
 
-        if policy.status != PolicyStatus::Active {
-            return Err(InsuranceError::PolicyNotActive);
+        let mut pool = InsuranceStorage::get_pool(&env);
+        let mut tranche = InsuranceStorage::get_reinsurance_pool(&env);
+        let amount = pool.surplus + tranche.surplus;
+        if amount <= 0 {
+            return Ok(0);
         }
+        pool.surplus = 0;
+        tranche.surplus = 0;
+        InsuranceStorage::set_pool(&env, &pool);
+        InsuranceStorage::set_reinsurance_pool(&env, &tranche);
 
-        let now = env.ledger().timestamp();
+        let pool_token = InsuranceStorage::get_pool_token(&env)
+            .ok_or(InsuranceError::NotInitialized)?;
+        token::Client::new(&env, &pool_token).transfer(
+            &env.current_contract_address(),
+            &recipient,
+            &amount,
+        );
 
-        // Auto-expire if the coverage window has elapsed
-        if now > policy.end_time {
+        env.events().publish(
+            (symbol_short!("surp_wdr"),),
+            (admin, recipient, amount, env.ledger().timestamp()),
+        );
+        Ok(amount)
+    }
+
+    /// Deposit capital into the reinsurance tranche in exchange for tranche
+    /// shares. Returns the number of shares minted.
+    pub fn deposit_reinsurance(
+        env: Env,
+        provider: Address,
+        amount: i128,
+    ) -> Result<i128, InsuranceError> {
+        Self::require_not_paused(&env)?;
+        Self::require_initialized(&env)?;
+
+        if amount <= 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
+
+        provider.require_auth();
+
+        let pool_token = InsuranceStorage::get_pool_token(&env)
+            .ok_or(InsuranceError::NotInitialized)?;
+
+        let mut tranche = InsuranceStorage::get_reinsurance_pool(&env);
+        if tranche.is_wiped_out() {
+            return Err(InsuranceError::PoolWipedOut);
+        }
+
+        let token_client = token::Client::new(&env, &pool_token);
+        token_client.transfer(&provider, &env.current_contract_address(), &amount);
+
+        tranche.sweep_unowned();
+        let shares = tranche.deposit(amount);
+        if shares <= 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
+
+        let current_shares = InsuranceStorage::get_reinsurance_shares(&env, &provider);
+        InsuranceStorage::set_reinsurance_shares(&env, &provider, current_shares + shares);
+        InsuranceStorage::set_reinsurance_pool(&env, &tranche);
+
+        env.events().publish(
+            (symbol_short!("re_dep"),),
+            (provider, amount, shares, env.ledger().timestamp()),
+        );
+
+        Ok(shares)
+    }
+
+    /// Redeem reinsurance tranche `shares`, limited by the tranche's
+    /// unreserved capital. Returns the token amount paid out.
+    pub fn withdraw_reinsurance(
+        env: Env,
+        provider: Address,
+        shares: i128,
+    ) -> Result<i128, InsuranceError> {
+        Self::require_not_paused(&env)?;
+        Self::require_initialized(&env)?;
+
+        if shares <= 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
+
+        provider.require_auth();
+
+        let held = InsuranceStorage::get_reinsurance_shares(&env, &provider);
+        if held < shares {
+            return Err(InsuranceError::InsufficientLPBalance);
+        }
+
+        let mut tranche = InsuranceStorage::get_reinsurance_pool(&env);
+        let amount = tranche.value_of_shares(shares);
+        if tranche.available_liquidity() < amount {
+            return Err(InsuranceError::WithdrawalExceedsAvailable);
+        }
+
+        let pool_token = InsuranceStorage::get_pool_token(&env)
+            .ok_or(InsuranceError::NotInitialized)?;
+
+        if amount > 0 {
+            let token_client = token::Client::new(&env, &pool_token);
+            token_client.transfer(&env.current_contract_address(), &provider, &amount);
+        }
+
+        InsuranceStorage::set_reinsurance_shares(&env, &provider, held - shares);
+
+        tranche.total_liquidity -= amount;
+        tranche.total_shares -= shares;
+        InsuranceStorage::set_reinsurance_pool(&env, &tranche);
+
+        env.events().publish(
+            (symbol_short!("re_wdraw"),),
+            (provider, amount, shares, env.ledger().timestamp()),
+        );
+
+        Ok(amount)
+    }
+
+    // =========================================================================
+    // Policy management
+    // =========================================================================
+
+    /// Create a new parametric insurance policy.
+    ///
+    /// The caller pays `premium_amount` of `pool_token` upfront, which must be
+    /// at least the current `quote_premium` for the same terms.
+    /// `coverage_amount` is reserved from the pool for the policy duration.
+    ///
+    /// # Parameters
+    /// - `policyholder`      – Address receiving the payout on trigger
+    /// - `policy_type`       – Risk category
+    /// - `oracle_feed`       – Symbol identifying the oracle data feed (≤32 chars)
+    /// - `trigger_threshold` – Threshold value compared against live oracle reading
+    /// - `trigger_condition` – Comparison operator (GreaterThan, LessThan, …)
+    /// - `coverage_amount`   – Payout amount if trigger fires (in pool_token)
+    /// - `premium_amount`    – Premium paid by the policyholder (in pool_token, ≥ quote)
+    /// - `duration_secs`     – Coverage duration in seconds from `now`
+    ///
+    /// Returns the new policy ID.
+    pub fn create_policy(
+        env: Env,
+        policyholder: Address,
+        policy_type: PolicyType,
+        oracle_feed: Symbol,
+        trigger_threshold: i128,
+        trigger_condition: TriggerCondition,
+        coverage_amount: i128,
+        premium_amount: i128,
+        duration_secs: u64,
+    ) -> Result<u64, InsuranceError> {
+        Self::open_policy(
+            &env,
+            policyholder,
+            policy_type,
+            oracle_feed,
+            trigger_threshold,
+            trigger_condition,
+            PayoutSchedule::Binary,
+            TriggerKind::SingleFeed,
+            coverage_amount,
+            premium_amount,
+            duration_secs,
+        )
+    }
+
+    /// Create a policy with a tiered or linear payout schedule.
+    ///
+    /// Identical to `create_policy` except that triggers pay out the share of
+    /// coverage given by `payout_schedule` at the current oracle value. Partial
+    /// claims leave the policy active for the remaining coverage.
+    ///
+    /// For `Tiered`, the first tier's threshold must equal `trigger_threshold`;
+    /// for `Linear`, `trigger_threshold` is the attachment point.
+    pub fn create_graduated_policy(
+        env: Env,
+        policyholder: Address,
+        policy_type: PolicyType,
+        oracle_feed: Symbol,
+        trigger_threshold: i128,
+        trigger_condition: TriggerCondition,
+        payout_schedule: PayoutSchedule,
+        coverage_amount: i128,
+        premium_amount: i128,
+        duration_secs: u64,
+    ) -> Result<u64, InsuranceError> {
+        Self::open_policy(
+            &env,
+            policyholder,
+            policy_type,
+            oracle_feed,
+            trigger_threshold,
+            trigger_condition,
+            payout_schedule,
+            TriggerKind::SingleFeed,
+            coverage_amount,
+            premium_amount,
+            duration_secs,
+        )
+    }
+
+    /// Create a policy triggered by a boolean expression over several feeds.
+    ///
+    /// The expression must hold for `trigger.required_observations`
+    /// consecutive fresh oracle observations; the full coverage is then paid.
+    /// The policy's `oracle_feed`, `trigger_threshold` and `trigger_condition`
+    /// mirror the expression's first condition.
+    pub fn create_composite_policy(
+        env: Env,
+        policyholder: Address,
+        policy_type: PolicyType,
+        trigger: CompositeTrigger,
+        coverage_amount: i128,
+        premium_amount: i128,
+        duration_secs: u64,
+    ) -> Result<u64, InsuranceError> {
+        trigger::validate(&env, &trigger)?;
+        let primary = trigger::conditions(&env, &trigger.expression).get_unchecked(0);
+
+        Self::open_policy(
+            &env,
+            policyholder,
+            policy_type,
+            primary.feed,
+            primary.threshold,
+            primary.condition,
+            PayoutSchedule::Binary,
+            TriggerKind::Composite(trigger),
+            coverage_amount,
+            premium_amount,
+            duration_secs,
+        )
+    }
+
+    /// Cancel an active policy before a trigger fires.
+    ///
+    /// The unearned premium is refunded pro-rata less the `RefundConfig` fees;
+    /// policies that have already paid a partial claim get no refund. The
+    /// reserved coverage is released back to available liquidity.
+    ///
+    /// Returns the amount refunded.
+    pub fn cancel_policy(
+        env: Env,
+        policyholder: Address,
+        policy_id: u64,
+    ) -> Result<i128, InsuranceError> {
+        Self::require_not_paused(&env)?;
+
+        policyholder.require_auth();
+
+        let mut policy = InsuranceStorage::get_policy(&env, policy_id)
+            .ok_or(InsuranceError::PolicyNotFound)?;
+
+        // Only the policyholder may cancel their own policy
+        if policy.policyholder != policyholder {
+            return Err(InsuranceError::Unauthorized);
+        }
+
+        match policy.status {
+            PolicyStatus::Active => {}
+            PolicyStatus::Cancelled => return Err(InsuranceError::PolicyAlreadyCancelled),
+            PolicyStatus::Claimed => return Err(InsuranceError::AlreadyClaimed),
+            PolicyStatus::Expired => return Err(InsuranceError::PolicyExpired),
+        }
+
+        // Release reserved coverage
+        Self::release_policy(&env, &policy);
+
+        let now = env.ledger().timestamp();
+        let refund = if policy.paid_out > 0 {
+            0
+        } else {
+            pricing::cancellation_refund(
+                &InsuranceStorage::get_refund_config(&env),
+                policy.premium_amount,
+                policy.start_time,
+                policy.end_time,
+                now,
+            )
+        };
+        if refund > 0 {
+            Self::pay_refund(&env, &policy, refund)?;
+        }
+
+        policy.status = PolicyStatus::Cancelled;
+        InsuranceStorage::set_policy(&env, &policy);
+
+        env.events().publish(
+            (symbol_short!("pol_cnl"),),
+            (policy_id, policyholder, refund, now),
+        );
+
+        Ok(refund)
+    }
+
+    /// Extend an active policy by `duration_secs`.
+    ///
+    /// The extension is priced at the current quote and charged to the
+    /// holder; coverage stays reserved throughout, so renewal cannot fail on
+    /// liquidity or exposure limits that tightened since the policy opened.
+    ///
+    /// Returns the premium charged.
+    pub fn renew_policy(
+        env: Env,
+        policy_id: u64,
+        duration_secs: u64,
+    ) -> Result<i128, InsuranceError> {
+        Self::require_not_paused(&env)?;
+        Self::require_initialized(&env)?;
+
+        let mut policy = Self::renewable_policy(&env, policy_id, duration_secs)?;
+        policy.policyholder.require_auth();
+
+        let premium = Self::quote_renewal_for(&env, &policy, duration_secs)?;
+
+        let pool_token = InsuranceStorage::get_pool_token(&env)
+            .ok_or(InsuranceError::NotInitialized)?;
+        token::Client::new(&env, &pool_token).transfer(
+            &policy.policyholder,
+            env.current_contract_address(),
+            &premium,
+        );
+
+        // Split the extension premium like the original one
+        let ceded_premium = match InsuranceStorage::get_reinsurance_config(&env) {
+            Some(cfg) if policy.ceded_coverage > 0 => {
+                premium * cfg.premium_share_bps as i128 / pricing::BPS
+            }
+            _ => 0,
+        };
+        if ceded_premium > 0 {
+            let mut tranche = InsuranceStorage::get_reinsurance_pool(&env);
+            tranche.total_liquidity += ceded_premium;
+            tranche.total_premiums_collected += ceded_premium;
+            InsuranceStorage::set_reinsurance_pool(&env, &tranche);
+        }
+
+        let mut pool = InsuranceStorage::get_pool(&env);
+        pool.total_liquidity += premium - ceded_premium;
+        pool.total_premiums_collected += premium - ceded_premium;
+        InsuranceStorage::set_pool(&env, &pool);
+
+        policy.premium_amount += premium;
+        policy.ceded_premium += ceded_premium;
+        policy.end_time += duration_secs;
+        InsuranceStorage::set_policy(&env, &policy);
+
+        env.events().publish(
+            (symbol_short!("pol_rnw"),),
+            (policy_id, premium, policy.end_time),
+        );
+
+        Ok(premium)
+    }
+
+    // =========================================================================
+    // Policy transfers
+    // =========================================================================
+
+    /// Approve `spender` to transfer `policy_id` on the holder's behalf.
+    ///
+    /// A policy has at most one approved spender; approving again replaces it.
+    /// The approval is cleared whenever the policy changes hands.
+    pub fn approve_policy_transfer(
+        env: Env,
+        holder: Address,
+        spender: Address,
+        policy_id: u64,
+    ) -> Result<(), InsuranceError> {
+        Self::require_not_paused(&env)?;
+
+        holder.require_auth();
+
+        let policy = Self::transferable_policy(&env, &holder, policy_id)?;
+        InsuranceStorage::set_policy_approval(&env, policy.id, &spender);
+
+        env.events().publish(
+            (symbol_short!("pol_appr"),),
+            (policy_id, holder, spender),
+        );
+
+        Ok(())
+    }
+
+    /// Withdraw any outstanding transfer approval on `policy_id`.
+    pub fn revoke_policy_approval(
+        env: Env,
+        holder: Address,
+        policy_id: u64,
+    ) -> Result<(), InsuranceError> {
+        Self::require_not_paused(&env)?;
+
+        holder.require_auth();
+
+        let policy = InsuranceStorage::get_policy(&env, policy_id)
+            .ok_or(InsuranceError::PolicyNotFound)?;
+        if policy.policyholder != holder {
+            return Err(InsuranceError::Unauthorized);
+        }
+        InsuranceStorage::clear_policy_approval(&env, policy_id);
+
+        env.events()
+            .publish((symbol_short!("pol_rvk"),), (policy_id, holder));
+
+        Ok(())
+    }
+
+    /// Transfer an active policy from its holder to `to`.
+    ///
+    /// All future payouts and the right to cancel follow the policy to the
+    /// new holder.
+    pub fn transfer_policy(
+        env: Env,
+        from: Address,
+        to: Address,
+        policy_id: u64,
+    ) -> Result<(), InsuranceError> {
+        Self::require_not_paused(&env)?;
+
+        from.require_auth();
+
+        Self::do_transfer_policy(&env, &from, &to, policy_id)
+    }
+
+    /// Transfer a policy on behalf of its holder using a prior approval.
+    pub fn transfer_policy_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        policy_id: u64,
+    ) -> Result<(), InsuranceError> {
+        Self::require_not_paused(&env)?;
+
+        spender.require_auth();
+
+        if InsuranceStorage::get_policy_approval(&env, policy_id) != Some(spender) {
+            return Err(InsuranceError::Unauthorized);
+        }
+
+        Self::do_transfer_policy(&env, &from, &to, policy_id)
+    }
+
+    // =========================================================================
+    // Oracle trigger / claims
+    // =========================================================================
+
+    /// Check whether the oracle condition for `policy_id` is satisfied and,
+    /// if so, automatically disburse the payout to the policyholder.
+    ///
+    /// Graduated policies are paid the share of coverage their schedule grants
+    /// at the current value, minus anything already paid; they become
+    /// `Claimed` only once the full coverage is exhausted. Composite policies
+    /// count one observation per fresh oracle reading and pay the remaining
+    /// coverage once their expression has held for the required number.
+    ///
+    /// This function is permissionless — anyone (oracle keeper, policyholder,
+    /// or an automated bot) can call it to finalise a triggered policy.
+    ///
+    /// Returns `true` if a payout was executed; `false` if the current oracle
+    /// value entitles the holder to nothing beyond previous payouts.
+    pub fn check_trigger(env: Env, policy_id: u64) -> Result<bool, InsuranceError> {
+        Self::require_initialized(&env)?;
+
+        let mut policy = InsuranceStorage::get_policy(&env, policy_id)
+            .ok_or(InsuranceError::PolicyNotFound)?;
+
+        if policy.status != PolicyStatus::Active {
+            return Err(InsuranceError::PolicyNotActive);
+        }
+
+        let now = env.ledger().timestamp();
+
+        // Auto-expire if the coverage window has elapsed
+        if now > policy.end_time {
             Self::do_expire_policy(&env, &mut policy);
             return Err(InsuranceError::PolicyExpired);
         }
 
-        // Query oracle sources for the policy's data feed
-        let oracle_config = InsuranceStorage::get_oracle_config(&env)
-            .ok_or(InsuranceError::NotInitialized)?;
+        if let TriggerKind::Composite(trigger) = policy.trigger_kind.clone() {
+            return Self::check_composite_trigger(&env, &mut policy, &trigger, now);
+        }
+
+        // Query oracle sources for the policy's data feed
+        let oracle_value = Self::read_feed(&env, &policy.oracle_feed)?;
+
+        Self::settle_single_feed(&env, &mut policy, oracle_value, now)
+    }
+
+    /// Evaluate every active policy watching `feed`, aggregating the feed once.
+    ///
+    /// Visits up to `limit` (capped at `MAX_KEEPER_BATCH`) entries of the
+    /// feed's active-policy index starting at `cursor`, paying triggered
+    /// claims and expiring lapsed policies. Settled policies leave the index,
+    /// so callers should continue from the returned `next_cursor`. A policy
+    /// whose oracle read or payout fails is skipped, counted in `failed` and
+    /// reported in a `trig_fail` event; the batch carries on past it.
+    pub fn check_triggers_for_feed(
+        env: Env,
+        keeper: Address,
+        feed: Symbol,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<KeeperReport, InsuranceError> {
+        Self::require_initialized(&env)?;
+
+        let ids = InsuranceStorage::get_active_policy_ids_by_feed(&env, &feed);
+        let start = cursor.min(ids.len() as u64) as u32;
+        let end = start.saturating_add(limit.min(MAX_KEEPER_BATCH)).min(ids.len());
+        let now = env.ledger().timestamp();
+
+        // One aggregate read serves every single-feed policy in the batch
+        let oracle_value = if start < end {
+            Self::read_feed(&env, &feed)
+        } else {
+            Err(InsuranceError::OracleFailure)
+        };
+
+        let mut executed = 0u32;
+        let mut failed = 0u32;
+        let mut settled = 0u32;
+        for idx in start..end {
+            let Some(mut policy) = InsuranceStorage::get_policy(&env, ids.get_unchecked(idx)) else {
+                continue;
+            };
+            if policy.status != PolicyStatus::Active {
+                continue;
+            }
+
+            let acted = if now > policy.end_time {
+                Self::do_expire_policy(&env, &mut policy);
+                true
+            } else {
+                let result = match &policy.trigger_kind.clone() {
+                    TriggerKind::Composite(trigger) => {
+                        Self::check_composite_trigger(&env, &mut policy, trigger, now)
+                    }
+                    TriggerKind::SingleFeed => oracle_value.and_then(|value| {
+                        Self::settle_single_feed(&env, &mut policy, value, now)
+                    }),
+                };
+                // A failure only skips this policy; both paths fail before
+                // writing anything, including when the token refuses a payout
+                result.unwrap_or_else(|err| {
+                    failed += 1;
+                    env.events().publish(
+                        (symbol_short!("trig_fail"), feed.clone()),
+                        (policy.id, err as u32),
+                    );
+                    false
+                })
+            };
+
+            if acted {
+                executed += 1;
+            }
+            if policy.status != PolicyStatus::Active {
+                settled += 1;
+            }
+        }
+
+        let next_cursor = (end - settled) as u64;
+        let remaining = InsuranceStorage::get_active_policy_ids_by_feed(&env, &feed).len() as u64;
+        let reward = Self::pay_keeper_reward(&env, &keeper, executed)?;
+
+        env.events().publish(
+            (symbol_short!("kpr_trig"), feed),
+            (keeper, end - start, executed, reward),
+        );
+
+        Ok(KeeperReport {
+            processed: end - start,
+            executed,
+            failed,
+            next_cursor,
+            done: next_cursor >= remaining,
+            reward,
+        })
+    }
+
+    /// Expire every lapsed active policy among IDs `cursor..cursor + limit`.
+    ///
+    /// `limit` is capped at `MAX_KEEPER_BATCH`; pass the returned
+    /// `next_cursor` to continue until `done`.
+    pub fn sweep_expired(
+        env: Env,
+        keeper: Address,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<KeeperReport, InsuranceError> {
+        Self::require_initialized(&env)?;
+
+        let count = InsuranceStorage::current_policy_id(&env);
+        let start = cursor.max(1);
+        let end = start
+            .saturating_add(limit.min(MAX_KEEPER_BATCH) as u64)
+            .min(count + 1)
+            .max(start);
+        let now = env.ledger().timestamp();
+
+        let mut executed = 0u32;
+        for id in start..end {
+            if let Some(mut policy) = InsuranceStorage::get_policy(&env, id) {
+                if policy.status == PolicyStatus::Active && now > policy.end_time {
+                    Self::do_expire_policy(&env, &mut policy);
+                    executed += 1;
+                }
+            }
+        }
+
+        let processed = (end - start) as u32;
+        let reward = Self::pay_keeper_reward(&env, &keeper, executed)?;
+
+        env.events().publish(
+            (symbol_short!("kpr_swp"),),
+            (keeper, processed, executed, reward),
+        );
+
+        Ok(KeeperReport {
+            processed,
+            executed,
+            failed: 0,
+            next_cursor: end,
+            done: end > count,
+            reward,
+        })
+    }
+
+    /// Mark an expired policy as `Expired` and release its reserved coverage.
+    ///
+    /// This is permissionless so keepers and integrators can call it to
+    /// reconcile the pool after a policy's coverage window lapses.
+    pub fn expire_policy(env: Env, policy_id: u64) -> Result<(), InsuranceError> {
+        Self::require_initialized(&env)?;
+
+        let mut policy = InsuranceStorage::get_policy(&env, policy_id)
+            .ok_or(InsuranceError::PolicyNotFound)?;
+
+        if policy.status != PolicyStatus::Active {
+            return Err(InsuranceError::PolicyNotActive);
+        }
+
+        let now = env.ledger().timestamp();
+        if now <= policy.end_time {
+            // Policy hasn't expired yet
+            return Err(InsuranceError::PolicyNotActive);
+        }
+
+        Self::do_expire_policy(&env, &mut policy);
+        Ok(())
+    }
+
+    // =========================================================================
+    // View functions
+    // =========================================================================
+
+    /// Premium `create_policy` would currently require for these terms.
+    ///
+    /// Reads the live oracle value for `oracle_feed` and the current pool
+    /// utilisation, so the quote moves with both.
+    pub fn quote_premium(
+        env: Env,
+        policy_type: PolicyType,
+        oracle_feed: Symbol,
+        trigger_threshold: i128,
+        trigger_condition: TriggerCondition,
+        coverage_amount: i128,
+        duration_secs: u64,
+    ) -> Result<i128, InsuranceError> {
+        Self::require_initialized(&env)?;
+        let pool = InsuranceStorage::get_pool(&env);
+        Self::quote(
+            &env,
+            &pool,
+            &policy_type,
+            &oracle_feed,
+            trigger_threshold,
+            &trigger_condition,
+            coverage_amount,
+            duration_secs,
+        )
+    }
+
+    /// Premium `create_composite_policy` would currently require.
+    pub fn quote_composite_premium(
+        env: Env,
+        policy_type: PolicyType,
+        expression: TriggerExpression,
+        coverage_amount: i128,
+        duration_secs: u64,
+    ) -> Result<i128, InsuranceError> {
+        Self::require_initialized(&env)?;
+        let pool = InsuranceStorage::get_pool(&env);
+        Self::quote_composite(
+            &env,
+            &pool,
+            &policy_type,
+            &expression,
+            coverage_amount,
+            duration_secs,
+        )
+    }
+
+    /// Premium `renew_policy` would currently charge to extend `policy_id`.
+    pub fn quote_renewal(
+        env: Env,
+        policy_id: u64,
+        duration_secs: u64,
+    ) -> Result<i128, InsuranceError> {
+        Self::require_initialized(&env)?;
+        let policy = Self::renewable_policy(&env, policy_id, duration_secs)?;
+        Self::quote_renewal_for(&env, &policy, duration_secs)
+    }
+
+    pub fn get_withdrawal_cooldown(env: Env) -> u64 {
+        InsuranceStorage::get_withdrawal_cooldown(&env)
+    }
+
+    pub fn get_withdrawal_request(env: Env, request_id: u64) -> Option<WithdrawalRequest> {
+        InsuranceStorage::get_withdrawal_request(&env, request_id)
+    }
+
+    /// ID of the oldest withdrawal request not yet processed.
+    pub fn get_withdrawal_queue_head(env: Env) -> u64 {
+        InsuranceStorage::get_withdrawal_queue_head(&env)
+    }
+
+    pub fn get_keeper_reward(env: Env) -> i128 {
+        InsuranceStorage::get_keeper_reward(&env)
+    }
+
+    /// Active policies watching `feed`, in keeper cursor order.
+    pub fn get_active_policies_for_feed(env: Env, feed: Symbol) -> Vec<u64> {
+        InsuranceStorage::get_active_policy_ids_by_feed(&env, &feed)
+    }
+
+    pub fn get_refund_config(env: Env) -> RefundConfig {
+        InsuranceStorage::get_refund_config(&env)
+    }
+
+    pub fn get_pricing_config(env: Env) -> PricingConfig {
+        InsuranceStorage::get_pricing_config(&env)
+    }
+
+    pub fn get_policy(env: Env, policy_id: u64) -> Option<InsurancePolicy> {
+        InsuranceStorage::get_policy(&env, policy_id)
+    }
+
+    pub fn get_policies_by_holder(env: Env, holder: Address) -> Vec<InsurancePolicy> {
+        InsuranceStorage::get_policies_by_holder(&env, &holder)
+    }
+
+    pub fn get_policy_approval(env: Env, policy_id: u64) -> Option<Address> {
+        InsuranceStorage::get_policy_approval(&env, policy_id)
+    }
+
+    pub fn get_pool_stats(env: Env) -> RiskPool {
+        InsuranceStorage::get_pool(&env)
+    }
+
+    pub fn get_available_liquidity(env: Env) -> i128 {
+        InsuranceStorage::get_pool(&env).available_liquidity()
+    }
+
+    /// Current token value of `provider`'s shares.
+    pub fn get_liquidity_position(env: Env, provider: Address) -> i128 {
+        let shares = InsuranceStorage::get_lp_shares(&env, &provider);
+        InsuranceStorage::get_pool(&env).value_of_shares(shares)
+    }
+
+    pub fn get_lp_shares(env: Env, provider: Address) -> i128 {
+        InsuranceStorage::get_lp_shares(&env, &provider)
+    }
+
+    /// Value of one pool share in `pool_token`, scaled by `SHARE_PRECISION`.
+    pub fn get_share_value(env: Env) -> i128 {
+        InsuranceStorage::get_pool(&env).share_value()
+    }
+
+    /// Tokens that redeeming `shares` would pay out right now. Does not check
+    /// the caller's balance or available liquidity.
+    pub fn preview_withdraw(env: Env, shares: i128) -> i128 {
+        InsuranceStorage::get_pool(&env).value_of_shares(shares)
+    }
+
+    pub fn get_reinsurance_config(env: Env) -> Option<ReinsuranceConfig> {
+        InsuranceStorage::get_reinsurance_config(&env)
+    }
+
+    pub fn get_reinsurance_pool(env: Env) -> RiskPool {
+        InsuranceStorage::get_reinsurance_pool(&env)
+    }
+
+    pub fn get_reinsurance_shares(env: Env, provider: Address) -> i128 {
+        InsuranceStorage::get_reinsurance_shares(&env, &provider)
+    }
+
+    /// Current token value of `provider`'s reinsurance tranche shares.
+    pub fn get_reinsurance_position(env: Env, provider: Address) -> i128 {
+        let shares = InsuranceStorage::get_reinsurance_shares(&env, &provider);
+        InsuranceStorage::get_reinsurance_pool(&env).value_of_shares(shares)
+    }
+
+    pub fn get_exposure_limits(env: Env) -> ExposureLimits {
+        InsuranceStorage::get_exposure_limits(&env)
+    }
+
+    /// Outstanding (unpaid) coverage on policies watching `feed`.
+    pub fn get_feed_exposure(env: Env, feed: Symbol) -> i128 {
+        InsuranceStorage::get_feed_exposure(&env, &feed)
+    }
+
+    /// Outstanding (unpaid) coverage on policies of `policy_type`.
+    pub fn get_type_exposure(env: Env, policy_type: PolicyType) -> i128 {
+        InsuranceStorage::get_type_exposure(&env, &policy_type)
+    }
+
+    pub fn get_oracle_config(env: Env) -> Option<OracleConfig> {
+        InsuranceStorage::get_oracle_config(&env)
+    }
+
+    pub fn is_initialized(env: Env) -> bool {
+        InsuranceStorage::is_initialized(&env)
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        InsuranceStorage::is_paused(&env)
+    }
+
+    pub fn get_policy_count(env: Env) -> u64 {
+        InsuranceStorage::current_policy_id(&env)
+    }
+
+    // =========================================================================
+    // Private helpers
+    // =========================================================================
+
+    fn require_initialized(env: &Env) -> Result<(), InsuranceError> {
+        if !InsuranceStorage::is_initialized(env) {
+            return Err(InsuranceError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    fn require_not_paused(env: &Env) -> Result<(), InsuranceError> {
+        if InsuranceStorage::is_paused(env) {
+            return Err(InsuranceError::ContractPaused);
+        }
+        Ok(())
+    }
 
-        let aggregate = fetch_aggregate_price(
-            &env,
-            &oracle_config.sources,
-            &policy.oracle_feed,
-            oracle_config.max_staleness,
-            oracle_config.min_sources,
-        )
-        .map_err(|_| InsuranceError::OracleFailure)?;
+    fn require_admin_auth(env: &Env, admin: &Address) -> Result<(), InsuranceError> {
+        Self::require_initialized(env)?;
+        if !InsuranceStorage::is_admin(env, admin) {
+            return Err(InsuranceError::Unauthorized);
+        }
+        admin.require_auth();
+        Ok(())
+    }
+
+    /// Validate, price and persist a new policy.
+    #[allow(clippy::too_many_arguments)]
+    fn open_policy(
+        env: &Env,
+        policyholder: Address,
+        policy_type: PolicyType,
+        oracle_feed: Symbol,
+        trigger_threshold: i128,
+        trigger_condition: TriggerCondition,
+        payout_schedule: PayoutSchedule,
+        trigger_kind: TriggerKind,
+        coverage_amount: i128,
+        premium_amount: i128,
+        duration_secs: u64,
+    ) -> Result<u64, InsuranceError> {
+        Self::require_not_paused(env)?;
+        Self::require_initialized(env)?;
+
+        if coverage_amount <= 0 || premium_amount <= 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
+        if duration_secs == 0 {
+            return Err(InsuranceError::InvalidDuration);
+        }
+
+        payout::validate_schedule(&payout_schedule, trigger_threshold, &trigger_condition)?;
 
-        let oracle_value = aggregate.median_price;
+        policyholder.require_auth();
 
-        // Evaluate trigger condition
-        let triggered = match policy.trigger_condition {
-            TriggerCondition::GreaterThan => oracle_value > policy.trigger_threshold,
-            TriggerCondition::LessThan => oracle_value < policy.trigger_threshold,
-            TriggerCondition::GreaterOrEqual => oracle_value >= policy.trigger_threshold,
-            TriggerCondition::LessOrEqual => oracle_value <= policy.trigger_threshold,
-            TriggerCondition::EqualTo => oracle_value == policy.trigger_threshold,
+        // Cede coverage above the retention to the reinsurance tranche
+        let pool = InsuranceStorage::get_pool(env);
+        let tranche = InsuranceStorage::get_reinsurance_pool(env);
+        let reinsurance = InsuranceStorage::get_reinsurance_config(env);
+        let ceded_coverage = match &reinsurance {
+            Some(cfg) => (coverage_amount - cfg.retention)
+                .max(0)
+                .min(tranche.available_liquidity()),
+            None => 0,
         };
 
-        if !triggered {
-            env.events().publish(
-                (symbol_short!("trig_miss"),),
-                (policy_id, oracle_value, policy.trigger_threshold),
-            );
-            return Ok(false);
+        // Verify the pool has enough unreserved liquidity for the retained layer
+        if pool.available_liquidity() < coverage_amount - ceded_coverage {
+            return Err(InsuranceError::InsufficientPoolLiquidity);
         }
 
-        // ── Trigger fired: execute automatic payout ──────────────────────────
+        // Enforce concentration limits against total capital
+        let feeds = Self::trigger_feeds(env, &trigger_kind, &oracle_feed);
+        let limits = InsuranceStorage::get_exposure_limits(env);
+        let capital = pool.total_liquidity + tranche.total_liquidity;
+        let feed_cap = capital * limits.max_feed_exposure_bps as i128 / pricing::BPS;
+        for feed in feeds.iter() {
+            if InsuranceStorage::get_feed_exposure(env, &feed) + coverage_amount > feed_cap {
+                return Err(InsuranceError::FeedExposureExceeded);
+            }
+        }
+        let type_cap = capital * limits.max_type_exposure_bps as i128 / pricing::BPS;
+        if InsuranceStorage::get_type_exposure(env, &policy_type) + coverage_amount > type_cap {
+            return Err(InsuranceError::TypeExposureExceeded);
+        }
 
-        let pool_token = InsuranceStorage::get_pool_token(&env)
+        // Price the risk and reject under-priced offers
+        let quoted = match &trigger_kind {
+            TriggerKind::Composite(trigger) => Self::quote_composite(
+                env,
+                &pool,
+                &policy_type,
+                &trigger.expression,
+                coverage_amount,
+                duration_secs,
+            )?,
+            TriggerKind::SingleFeed => Self::quote(
+                env,
+                &pool,
+                &policy_type,
+                &oracle_feed,
+                trigger_threshold,
+                &trigger_condition,
+                coverage_amount,
+                duration_secs,
+            )?,
+        };
+        if premium_amount < quoted {
+            return Err(InsuranceError::PremiumTooLow);
+        }
+
+        let pool_token = InsuranceStorage::get_pool_token(env)
             .ok_or(InsuranceError::NotInitialized)?;
 
-        let token_client = token::Client::new(&env, &pool_token);
+        // Collect premium from policyholder
+        let token_client = token::Client::new(env, &pool_token);
         token_client.transfer(
-            &env.current_contract_address(),
-            &policy.policyholder,
-            &policy.coverage_amount,
+            &policyholder,
+            env.current_contract_address(),
+            &premium_amount,
         );
 
+        // Reinsured policies pay the tranche its share of the premium
+        let ceded_premium = match &reinsurance {
+            Some(cfg) if ceded_coverage > 0 => {
+                premium_amount * cfg.premium_share_bps as i128 / pricing::BPS
+            }
+            _ => 0,
+        };
+
+        // Mint the policy record
+        let policy_id = InsuranceStorage::next_policy_id(env);
+        let now = env.ledger().timestamp();
+
+        let policy = InsurancePolicy {
+            id: policy_id,
+            policyholder: policyholder.clone(),
+            policy_type,
+            coverage_amount,
+            premium_amount,
+            oracle_feed: oracle_feed.clone(),
+            trigger_threshold,
+            trigger_condition,
+            payout_schedule,
+            paid_out: 0,
+            trigger_kind,
+            consecutive_hits: 0,
+            last_observation: 0,
+            ceded_coverage,
+            ceded_premium,
+            start_time: now,
+            end_time: now + duration_secs,
+            status: PolicyStatus::Active,
+            created_at: now,
+        };
+
+        InsuranceStorage::set_policy(env, &policy);
+
+        if ceded_coverage > 0 {
+            let mut tranche = InsuranceStorage::get_reinsurance_pool(env);
+            tranche.total_liquidity += ceded_premium;
+            tranche.reserved_liquidity += ceded_coverage;
+            tranche.total_premiums_collected += ceded_premium;
+            tranche.total_policies += 1;
+            tranche.active_policies += 1;
+            InsuranceStorage::set_reinsurance_pool(env, &tranche);
+        }
+
         // Update pool accounting
-        let mut pool = InsuranceStorage::get_pool(&env);
-        pool.total_liquidity = pool.total_liquidity.saturating_sub(policy.coverage_amount);
-        pool.reserved_liquidity = pool.reserved_liquidity.saturating_sub(policy.coverage_amount);
-        pool.total_payouts += policy.coverage_amount;
-        pool.active_policies = pool.active_policies.saturating_sub(1);
-        InsuranceStorage::set_pool(&env, &pool);
+        let retained_premium = premium_amount - ceded_premium;
+        let mut pool = InsuranceStorage::get_pool(env);
+        pool.total_liquidity += retained_premium;      // premium enters the pool
+        pool.reserved_liquidity += coverage_amount - ceded_coverage; // coverage is locked
+        pool.total_premiums_collected += retained_premium;
+        pool.total_policies += 1;
+        pool.active_policies += 1;
+        InsuranceStorage::set_pool(env, &pool);
 
-        // Mark policy as claimed
-        policy.status = PolicyStatus::Claimed;
-        InsuranceStorage::set_policy(&env, &policy);
+        Self::adjust_exposure(env, &policy, coverage_amount);
+        Self::index_policy(env, &policy, true);
 
         env.events().publish(
-            (symbol_short!("trig_act"),),
+            (symbol_short!("pol_crt"),),
             (
                 policy_id,
+                policyholder,
+                coverage_amount,
+                premium_amount,
+                now + duration_secs,
+            ),
+        );
+
+        Ok(policy_id)
+    }
+
+    /// Transfer `payout` to the policyholder and book it against the pool,
+    /// marking the policy `Claimed` once its coverage is exhausted. Fails with
+    /// `PayoutFailed`, having written nothing, if the token refuses the transfer.
+    fn pay_claim(
+        env: &Env,
+        policy: &mut InsurancePolicy,
+        payout: i128,
+        oracle_value: i128,
+        now: u64,
+    ) -> Result<(), InsuranceError> {
+        let pool_token = InsuranceStorage::get_pool_token(env)
+            .ok_or(InsuranceError::NotInitialized)?;
+
+        let token_client = token::Client::new(env, &pool_token);
+        if !matches!(
+            token_client.try_transfer(
+                &env.current_contract_address(),
+                &policy.policyholder,
+                &payout,
+            ),
+            Ok(Ok(()))
+        ) {
+            return Err(InsuranceError::PayoutFailed);
+        }
+
+        let (from_pool, from_tranche) = policy.split_payout(payout);
+        policy.paid_out += payout;
+        let fully_claimed = policy.remaining_coverage() == 0;
+
+        // Update pool accounting
+        let mut pool = InsuranceStorage::get_pool(env);
+        pool.total_liquidity = pool.total_liquidity.saturating_sub(from_pool);
+        pool.reserved_liquidity = pool.reserved_liquidity.saturating_sub(from_pool);
+        pool.total_payouts += from_pool;
+        if fully_claimed {
+            pool.active_policies = pool.active_policies.saturating_sub(1);
+        }
+        InsuranceStorage::set_pool(env, &pool);
+
+        // Losses above the retention are recovered from the tranche
+        if policy.ceded_coverage > 0 {
+            let mut tranche = InsuranceStorage::get_reinsurance_pool(env);
+            tranche.total_liquidity = tranche.total_liquidity.saturating_sub(from_tranche);
+            tranche.reserved_liquidity = tranche.reserved_liquidity.saturating_sub(from_tranche);
+            tranche.total_payouts += from_tranche;
+            if fully_claimed {
+                tranche.active_policies = tranche.active_policies.saturating_sub(1);
+            }
+            InsuranceStorage::set_reinsurance_pool(env, &tranche);
+        }
+
+        Self::adjust_exposure(env, policy, -payout);
+
+        // Partial claims keep the policy active for the remaining coverage
+        if fully_claimed {
+            policy.status = PolicyStatus::Claimed;
+            Self::index_policy(env, policy, false);
+        }
+        InsuranceStorage::set_policy(env, policy);
+
+        env.events().publish(
+            (symbol_short!("trig_act"),),
+            (
+                policy.id,
                 policy.policyholder.clone(),
                 oracle_value,
                 policy.trigger_threshold,
-                policy.coverage_amount,
+                payout,
                 now,
             ),
         );
@@ -492,114 +1665,391 @@ impl ParametricInsuranceContract {
         env.events().publish(
             (symbol_short!("clm_paid"),),
             (
-                policy_id,
-                policy.policyholder,
-                policy.coverage_amount,
+                policy.id,
+                policy.policyholder.clone(),
+                payout,
+                policy.paid_out,
                 now,
             ),
         );
 
-        Ok(true)
+        Ok(())
+    }
+
+    /// Record one observation of a composite trigger and pay the remaining
+    /// coverage once it has held for `required_observations` fresh readings.
+    fn check_composite_trigger(
+        env: &Env,
+        policy: &mut InsurancePolicy,
+        trigger: &CompositeTrigger,
+        now: u64,
+    ) -> Result<bool, InsuranceError> {
+        let (values, observed_at) = Self::read_feeds(env, &trigger.expression)?;
+        let held = trigger::evaluate(&trigger.expression, &values);
+        let primary_value = values.get(policy.oracle_feed.clone()).unwrap_or(0);
+
+        if !held {
+            policy.consecutive_hits = 0;
+            policy.last_observation = observed_at;
+            InsuranceStorage::set_policy(env, policy);
+            env.events().publish(
+                (symbol_short!("trig_miss"),),
+                (policy.id, primary_value, policy.trigger_threshold),
+            );
+            return Ok(false);
+        }
+
+        // Only fresh oracle data counts towards the consecutive requirement
+        if policy.consecutive_hits == 0 || observed_at > policy.last_observation {
+            policy.consecutive_hits += 1;
+            policy.last_observation = observed_at;
+        }
+
+        if policy.consecutive_hits < trigger.required_observations {
+            InsuranceStorage::set_policy(env, policy);
+            env.events().publish(
+                (symbol_short!("trig_obs"),),
+                (policy.id, policy.consecutive_hits, trigger.required_observations),
+            );
+            return Ok(false);
+        }
+
+        let payout = policy.remaining_coverage();
+        Self::pay_claim(env, policy, payout, primary_value, now)?;
+        Ok(true)
+    }
+
+    /// Aggregate `feed` under the configured source rules.
+    fn read_aggregate(env: &Env, feed: &Symbol) -> Result<AggregatePrice, InsuranceError> {
+        let oracle_config = InsuranceStorage::get_oracle_config(env)
+            .ok_or(InsuranceError::NotInitialized)?;
+
+        fetch_aggregate_price(
+            env,
+            &oracle_config.sources,
+            feed,
+            oracle_config.max_staleness,
+            oracle_config.min_sources,
+        )
+        .map_err(|_| InsuranceError::OracleFailure)
+    }
+
+    /// Aggregated oracle value for `feed` under the configured source rules.
+    fn read_feed(env: &Env, feed: &Symbol) -> Result<i128, InsuranceError> {
+        Ok(Self::read_aggregate(env, feed)?.median_price)
+    }
+
+    /// Aggregated value of every feed in `expression` that can be read, plus
+    /// the timestamp of the oldest oracle answer used. A feed that fails is
+    /// left out and its conditions count as not met, so only `AllOf` fails
+    /// on it; any expression fails when no feed can be read.
+    fn read_feeds(
+        env: &Env,
+        expression: &TriggerExpression,
+    ) -> Result<(Map<Symbol, i128>, u64), InsuranceError> {
+        let mut values = Map::new(env);
+        let mut oldest = u64::MAX;
+        let mut failure = None;
+        for feed in trigger::feeds(env, expression).iter() {
+            match Self::read_aggregate(env, &feed) {
+                Ok(aggregate) => {
+                    oldest = oldest.min(aggregate.oldest_timestamp);
+                    values.set(feed, aggregate.median_price);
+                }
+                Err(e) if matches!(expression, TriggerExpression::AllOf(_)) => return Err(e),
+                Err(e) => failure = failure.or(Some(e)),
+            }
+        }
+        if values.is_empty() {
+            return Err(failure.unwrap_or(InsuranceError::OracleFailure));
+        }
+        Ok((values, oldest))
+    }
+
+    /// Price a policy against the live oracle value and `pool`.
+    #[allow(clippy::too_many_arguments)]
+    fn quote(
+        env: &Env,
+        pool: &RiskPool,
+        policy_type: &PolicyType,
+        oracle_feed: &Symbol,
+        trigger_threshold: i128,
+        trigger_condition: &TriggerCondition,
+        coverage_amount: i128,
+        duration_secs: u64,
+    ) -> Result<i128, InsuranceError> {
+        let current_value = Self::read_feed(env, oracle_feed)?;
+        pricing::quote(
+            &InsuranceStorage::get_pricing_config(env),
+            pool,
+            policy_type,
+            trigger_threshold,
+            trigger_condition,
+            coverage_amount,
+            duration_secs,
+            current_value,
+        )
+    }
+
+    /// Price a composite trigger against the live value of each of its feeds,
+    /// refusing expressions that already hold.
+    fn quote_composite(
+        env: &Env,
+        pool: &RiskPool,
+        policy_type: &PolicyType,
+        expression: &TriggerExpression,
+        coverage_amount: i128,
+        duration_secs: u64,
+    ) -> Result<i128, InsuranceError> {
+        let (values, _) = Self::read_feeds(env, expression)?;
+        if trigger::evaluate(expression, &values) {
+            return Err(InsuranceError::TriggerAlreadyMet);
+        }
+        pricing::quote_expression(
+            &InsuranceStorage::get_pricing_config(env),
+            pool,
+            policy_type,
+            expression,
+            coverage_amount,
+            duration_secs,
+            &values,
+        )
+    }
+
+    /// Feeds a policy's outstanding coverage counts against.
+    fn trigger_feeds(env: &Env, trigger_kind: &TriggerKind, oracle_feed: &Symbol) -> Vec<Symbol> {
+        match trigger_kind {
+            TriggerKind::Composite(trigger) => trigger::feeds(env, &trigger.expression),
+            TriggerKind::SingleFeed => Vec::from_array(env, [oracle_feed.clone()]),
+        }
     }
 
-    /// Mark an expired policy as `Expired` and release its reserved coverage.
-    ///
-    /// This is permissionless so keepers and integrators can call it to
-    /// reconcile the pool after a policy's coverage window lapses.
-    pub fn expire_policy(env: Env, policy_id: u64) -> Result<(), InsuranceError> {
-        Self::require_initialized(&env)?;
+    /// Add `delta` to the outstanding exposure of every feed the policy
+    /// watches and of its policy type.
+    fn adjust_exposure(env: &Env, policy: &InsurancePolicy, delta: i128) {
+        for feed in Self::trigger_feeds(env, &policy.trigger_kind, &policy.oracle_feed).iter() {
+            let current = InsuranceStorage::get_feed_exposure(env, &feed);
+            InsuranceStorage::set_feed_exposure(env, &feed, (current + delta).max(0));
+        }
+        let current = InsuranceStorage::get_type_exposure(env, &policy.policy_type);
+        InsuranceStorage::set_type_exposure(env, &policy.policy_type, (current + delta).max(0));
+    }
 
-        let mut policy = InsuranceStorage::get_policy(&env, policy_id)
-            .ok_or(InsuranceError::PolicyNotFound)?;
+    /// Release whatever coverage an ending policy still reserves in the pool
+    /// and the reinsurance tranche, and drop it from exposure tracking.
+    fn release_policy(env: &Env, policy: &InsurancePolicy) {
+        let (retained, ceded) = policy.remaining_split();
 
-        if policy.status != PolicyStatus::Active {
-            return Err(InsuranceError::PolicyNotActive);
-        }
+        let mut pool = InsuranceStorage::get_pool(env);
+        pool.reserved_liquidity = pool.reserved_liquidity.saturating_sub(retained);
+        pool.active_policies = pool.active_policies.saturating_sub(1);
+        InsuranceStorage::set_pool(env, &pool);
 
-        let now = env.ledger().timestamp();
-        if now <= policy.end_time {
-            // Policy hasn't expired yet
-            return Err(InsuranceError::PolicyNotActive);
+        if policy.ceded_coverage > 0 {
+            let mut tranche = InsuranceStorage::get_reinsurance_pool(env);
+            tranche.reserved_liquidity = tranche.reserved_liquidity.saturating_sub(ceded);
+            tranche.active_policies = tranche.active_policies.saturating_sub(1);
+            InsuranceStorage::set_reinsurance_pool(env, &tranche);
         }
 
-        Self::do_expire_policy(&env, &mut policy);
-        Ok(())
+        Self::adjust_exposure(env, policy, -policy.remaining_coverage());
+        Self::index_policy(env, policy, false);
     }
 
-    // =========================================================================
-    // View functions
-    // =========================================================================
-
-    pub fn get_policy(env: Env, policy_id: u64) -> Option<InsurancePolicy> {
-        InsuranceStorage::get_policy(&env, policy_id)
+    /// Add or remove `policy` from the active index of every feed it watches.
+    fn index_policy(env: &Env, policy: &InsurancePolicy, active: bool) {
+        for feed in Self::trigger_feeds(env, &policy.trigger_kind, &policy.oracle_feed).iter() {
+            if active {
+                InsuranceStorage::add_active_policy_for_feed(env, &feed, policy.id);
+            } else {
+                InsuranceStorage::remove_active_policy_for_feed(env, &feed, policy.id);
+            }
+        }
     }
 
-    pub fn get_policies_by_holder(env: Env, holder: Address) -> Vec<InsurancePolicy> {
-        InsuranceStorage::get_policies_by_holder(&env, &holder)
-    }
+    /// Pay the outstanding entitlement of a single-feed policy at
+    /// `oracle_value`; returns whether anything was paid.
+    fn settle_single_feed(
+        env: &Env,
+        policy: &mut InsurancePolicy,
+        oracle_value: i128,
+        now: u64,
+    ) -> Result<bool, InsuranceError> {
+        // Evaluate the payout schedule against the current reading
+        let payout = payout::outstanding_payout(policy, oracle_value);
+
+        if payout <= 0 {
+            env.events().publish(
+                (symbol_short!("trig_miss"),),
+                (policy.id, oracle_value, policy.trigger_threshold),
+            );
+            return Ok(false);
+        }
 
-    pub fn get_pool_stats(env: Env) -> RiskPool {
-        InsuranceStorage::get_pool(&env)
+        Self::pay_claim(env, policy, payout, oracle_value, now)?;
+        Ok(true)
     }
 
-    pub fn get_available_liquidity(env: Env) -> i128 {
-        InsuranceStorage::get_pool(&env).available_liquidity()
-    }
+    /// Pay `keeper` the configured reward for `actions` executed actions,
+    /// capped at the pool's unreserved liquidity.
+    fn pay_keeper_reward(env: &Env, keeper: &Address, actions: u32) -> Result<i128, InsuranceError> {
+        let mut pool = InsuranceStorage::get_pool(env);
+        let reward = (InsuranceStorage::get_keeper_reward(env) * actions as i128)
+            .min(pool.available_liquidity())
+            .max(0);
+        if reward == 0 {
+            return Ok(0);
+        }
 
-    pub fn get_liquidity_position(env: Env, provider: Address) -> i128 {
-        InsuranceStorage::get_lp_balance(&env, &provider)
-    }
+        pool.total_liquidity -= reward;
+        InsuranceStorage::set_pool(env, &pool);
 
-    pub fn get_oracle_config(env: Env) -> Option<OracleConfig> {
-        InsuranceStorage::get_oracle_config(&env)
+        let pool_token = InsuranceStorage::get_pool_token(env)
+            .ok_or(InsuranceError::NotInitialized)?;
+        token::Client::new(env, &pool_token).transfer(
+            &env.current_contract_address(),
+            keeper,
+            &reward,
+        );
+        Ok(reward)
     }
 
-    pub fn is_initialized(env: Env) -> bool {
-        InsuranceStorage::is_initialized(&env)
-    }
+    /// Load an active, unexpired policy that can be extended.
+    fn renewable_policy(
+        env: &Env,
+        policy_id: u64,
+        duration_secs: u64,
+    ) -> Result<InsurancePolicy, InsuranceError> {
+        if duration_secs == 0 {
+            return Err(InsuranceError::InvalidDuration);
+        }
+        let policy = InsuranceStorage::get_policy(env, policy_id)
+            .ok_or(InsuranceError::PolicyNotFound)?;
 
-    pub fn is_paused(env: Env) -> bool {
-        InsuranceStorage::is_paused(&env)
+        if policy.status != PolicyStatus::Active {
+            return Err(InsuranceError::PolicyNotActive);
+        }
+        if env.ledger().timestamp() > policy.end_time {
+            return Err(InsuranceError::PolicyExpired);
+        }
+        Ok(policy)
     }
 
-    pub fn get_policy_count(env: Env) -> u64 {
-        InsuranceStorage::current_policy_id(&env)
+    /// Price an extension of the coverage `policy` has left. Its own
+    /// reservation is taken out of the pool first so utilisation is not
+    /// loaded twice for the same coverage.
+    fn quote_renewal_for(
+        env: &Env,
+        policy: &InsurancePolicy,
+        duration_secs: u64,
+    ) -> Result<i128, InsuranceError> {
+        let remaining = policy.remaining_coverage();
+        let (retained, _) = policy.remaining_split();
+        let mut pool = InsuranceStorage::get_pool(env);
+        pool.reserved_liquidity = pool.reserved_liquidity.saturating_sub(retained);
+
+        match &policy.trigger_kind {
+            TriggerKind::Composite(trigger) => Self::quote_composite(
+                env,
+                &pool,
+                &policy.policy_type,
+                &trigger.expression,
+                remaining,
+                duration_secs,
+            ),
+            TriggerKind::SingleFeed => Self::quote(
+                env,
+                &pool,
+                &policy.policy_type,
+                &policy.oracle_feed,
+                policy.trigger_threshold,
+                &policy.trigger_condition,
+                remaining,
+                duration_secs,
+            ),
+        }
     }
 
-    // =========================================================================
-    // Private helpers
-    // =========================================================================
+    /// Return `refund` of a cancelled policy's premium, drawing on the primary
+    /// pool and the reinsurance tranche in the proportion they were paid.
+    fn pay_refund(env: &Env, policy: &InsurancePolicy, refund: i128) -> Result<(), InsuranceError> {
+        let ceded = refund * policy.ceded_premium / policy.premium_amount.max(1);
+        let retained = refund - ceded;
 
-    fn require_initialized(env: &Env) -> Result<(), InsuranceError> {
-        if !InsuranceStorage::is_initialized(env) {
-            return Err(InsuranceError::NotInitialized);
+        let mut pool = InsuranceStorage::get_pool(env);
+        if pool.available_liquidity() < retained {
+            return Err(InsuranceError::InsufficientPoolLiquidity);
         }
-        Ok(())
-    }
+        pool.total_liquidity -= retained;
+        pool.total_premiums_collected -= retained;
+        InsuranceStorage::set_pool(env, &pool);
 
-    fn require_not_paused(env: &Env) -> Result<(), InsuranceError> {
-        if InsuranceStorage::is_paused(env) {
-            return Err(InsuranceError::ContractPaused);
+        if ceded > 0 {
+            let mut tranche = InsuranceStorage::get_reinsurance_pool(env);
+            if tranche.available_liquidity() < ceded {
+                return Err(InsuranceError::InsufficientPoolLiquidity);
+            }
+            tranche.total_liquidity -= ceded;
+            tranche.total_premiums_collected -= ceded;
+            InsuranceStorage::set_reinsurance_pool(env, &tranche);
         }
+
+        let pool_token = InsuranceStorage::get_pool_token(env)
+            .ok_or(InsuranceError::NotInitialized)?;
+        token::Client::new(env, &pool_token).transfer(
+            &env.current_contract_address(),
+            &policy.policyholder,
+            &refund,
+        );
         Ok(())
     }
 
-    fn require_admin_auth(env: &Env, admin: &Address) -> Result<(), InsuranceError> {
-        Self::require_initialized(env)?;
-        if !InsuranceStorage::is_admin(env, admin) {
+    /// Load a policy owned by `holder` that can still change hands.
+    fn transferable_policy(
+        env: &Env,
+        holder: &Address,
+        policy_id: u64,
+    ) -> Result<InsurancePolicy, InsuranceError> {
+        let policy = InsuranceStorage::get_policy(env, policy_id)
+            .ok_or(InsuranceError::PolicyNotFound)?;
+
+        if policy.policyholder != *holder {
             return Err(InsuranceError::Unauthorized);
         }
-        admin.require_auth();
+        if policy.status != PolicyStatus::Active {
+            return Err(InsuranceError::PolicyNotActive);
+        }
+        if env.ledger().timestamp() > policy.end_time {
+            return Err(InsuranceError::PolicyExpired);
+        }
+        Ok(policy)
+    }
+
+    fn do_transfer_policy(
+        env: &Env,
+        from: &Address,
+        to: &Address,
+        policy_id: u64,
+    ) -> Result<(), InsuranceError> {
+        if from == to {
+            return Err(InsuranceError::InvalidRecipient);
+        }
+        let mut policy = Self::transferable_policy(env, from, policy_id)?;
+
+        InsuranceStorage::clear_policy_approval(env, policy_id);
+        InsuranceStorage::transfer_policy(env, &mut policy, to);
+
+        env.events().publish(
+            (symbol_short!("pol_xfer"),),
+            (policy_id, from.clone(), to.clone(), env.ledger().timestamp()),
+        );
+
         Ok(())
     }
 
     /// Shared logic for expiring a policy: releases reservation and persists status.
     fn do_expire_policy(env: &Env, policy: &mut InsurancePolicy) {
-        let mut pool = InsuranceStorage::get_pool(env);
-        pool.reserved_liquidity =
-            pool.reserved_liquidity.saturating_sub(policy.coverage_amount);
-        pool.active_policies = pool.active_policies.saturating_sub(1);
-        InsuranceStorage::set_pool(env, &pool);
+        Self::release_policy(env, policy);
 
         policy.status = PolicyStatus::Expired;
         InsuranceStorage::set_policy(env, policy);
@@ -608,6 +2058,66 @@ impl ParametricInsuranceContract {
             (symbol_short!("pol_exp"),),
             (policy.id, policy.policyholder.clone(), env.ledger().timestamp()),
         );
+
+        // Released coverage goes to queued LP withdrawals first
+        Self::process_withdrawal_queue(env, MAX_WITHDRAWALS_PER_EXPIRY);
+    }
+
+    /// Fulfil queued withdrawals in FIFO order, visiting at most `max`
+    /// entries. Stops at the first request that has not matured or cannot be
+    /// covered by unreserved liquidity, so later requests never jump the queue.
+    fn process_withdrawal_queue(env: &Env, max: u32) -> u32 {
+        let Some(pool_token) = InsuranceStorage::get_pool_token(env) else {
+            return 0;
+        };
+        let tail = InsuranceStorage::get_withdrawal_queue_tail(env);
+        let mut head = InsuranceStorage::get_withdrawal_queue_head(env);
+        let now = env.ledger().timestamp();
+        let mut pool = InsuranceStorage::get_pool(env);
+
+        let mut visited = 0u32;
+        let mut fulfilled = 0u32;
+        while head <= tail && visited < max {
+            visited += 1;
+
+            // Cancelled requests leave a gap in the queue
+            let Some(request) = InsuranceStorage::get_withdrawal_request(env, head) else {
+                head += 1;
+                continue;
+            };
+            if now < request.unlock_at {
+                break;
+            }
+            let amount = pool.value_of_shares(request.shares);
+            if pool.available_liquidity() < amount {
+                break;
+            }
+
+            if amount > 0 {
+                token::Client::new(env, &pool_token).transfer(
+                    &env.current_contract_address(),
+                    &request.provider,
+                    &amount,
+                );
+            }
+            pool.total_liquidity -= amount;
+            pool.total_shares -= request.shares;
+            InsuranceStorage::remove_withdrawal_request(env, head);
+
+            env.events().publish(
+                (symbol_short!("wd_done"),),
+                (request.id, request.provider, amount, request.shares),
+            );
+
+            head += 1;
+            fulfilled += 1;
+        }
+
+        if fulfilled > 0 {
+            InsuranceStorage::set_pool(env, &pool);
+        }
+        InsuranceStorage::set_withdrawal_queue_head(env, head);
+        fulfilled
     }
 }
 
@@ -713,9 +2223,7 @@ mod test {
         sources.push_back(oracle.clone());
 
         let client = ParametricInsuranceContractClient::new(&env, &contract);
-        client
-            .init(&admin, &pool_token, &sources, &3600u64, &1u32)
-            .unwrap();
+        client.init(&admin, &pool_token, &sources, &3600u64, &1u32);
 
         TestCtx {
             env,
@@ -760,14 +2268,14 @@ mod test {
         let ctx = setup();
         let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-        client.pause(&ctx.admin).unwrap();
+        client.pause(&ctx.admin);
         assert!(client.is_paused());
 
         // Deposit should fail while paused
         let result = client.try_deposit_liquidity(&ctx.lp, &1000i128);
         assert!(result.is_err());
 
-        client.unpause(&ctx.admin).unwrap();
+        client.unpause(&ctx.admin);
         assert!(!client.is_paused());
     }
 
@@ -776,37 +2284,140 @@ mod test {
         let ctx = setup();
         let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-        client.deposit_liquidity(&ctx.lp, &50_000i128).unwrap();
+        // The first deposit locks DEAD_SHARES with no owner
+        assert_eq!(client.deposit_liquidity(&ctx.lp, &50_000i128), 50_000 - DEAD_SHARES);
 
-        assert_eq!(client.get_liquidity_position(&ctx.lp), 50_000);
+        assert_eq!(client.get_liquidity_position(&ctx.lp), 49_000);
+        assert_eq!(client.get_pool_stats().total_shares, 50_000);
         assert_eq!(client.get_pool_stats().total_liquidity, 50_000);
         assert_eq!(client.get_available_liquidity(), 50_000);
         assert_eq!(token_balance(&ctx.env, &ctx.pool_token, &ctx.contract), 50_000);
 
-        client.withdraw_liquidity(&ctx.lp, &20_000i128).unwrap();
+        client.withdraw_liquidity(&ctx.lp, &20_000i128);
 
-        assert_eq!(client.get_liquidity_position(&ctx.lp), 30_000);
+        assert_eq!(client.get_liquidity_position(&ctx.lp), 29_000);
         assert_eq!(client.get_pool_stats().total_liquidity, 30_000);
         assert_eq!(token_balance(&ctx.env, &ctx.pool_token, &ctx.lp), 70_000);
     }
 
+    #[test]
+    fn test_shares_minted_pro_rata_after_premium() {
+        let ctx = setup();
+        let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+        let lp2 = Address::generate(&ctx.env);
+        mint_token(&ctx.env, &ctx.pool_token, &lp2, 100_000);
+
+        assert_eq!(client.deposit_liquidity(&ctx.lp, &10_000i128), 9_000);
+        assert_eq!(client.get_share_value(), SHARE_PRECISION);
+
+        // Premium of 1_000 raises the pool to 11_000 backing 10_000 shares
+        let feed = Symbol::new(&ctx.env, "RAINFALL");
+        client.create_policy(
+            &ctx.policyholder,
+            &PolicyType::Weather,
+            &feed,
+            &200i128,
+            &TriggerCondition::GreaterOrEqual,
+            &2_000i128,
+            &1_000i128,
+            &86_400u64,
+        );
+        assert_eq!(client.get_share_value(), SHARE_PRECISION * 11 / 10);
+        assert_eq!(client.get_liquidity_position(&ctx.lp), 9_900);
+
+        // A later depositor pays the higher share price
+        assert_eq!(client.deposit_liquidity(&lp2, &11_000i128), 10_000);
+        assert_eq!(client.get_lp_shares(&lp2), 10_000);
+        assert_eq!(client.preview_withdraw(&10_000i128), 11_000);
+    }
+
+    #[test]
+    fn test_payout_is_socialised_across_lps() {
+        let ctx = setup();
+        let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+        let lp2 = Address::generate(&ctx.env);
+        mint_token(&ctx.env, &ctx.pool_token, &lp2, 100_000);
+
+        client.deposit_liquidity(&ctx.lp, &30_000i128);
+        client.deposit_liquidity(&lp2, &10_000i128);
+
+        let feed = Symbol::new(&ctx.env, "HURR_MIA");
+        let policy_id = client.create_policy(
+            &ctx.policyholder,
+            &PolicyType::NaturalDisaster,
+            &feed,
+            &100i128,
+            &TriggerCondition::GreaterOrEqual,
+            &8_000i128,
+            &4_000i128,
+            &86_400u64,
+        );
+
+        MockOracleClient::new(&ctx.env, &ctx.oracle)
+            .set_price(&feed, &120i128, &ctx.env.ledger().timestamp());
+        assert!(client.check_trigger(&policy_id));
+
+        // Pool: 40_000 + 4_000 premium − 8_000 payout = 36_000 over 40_000
+        // shares, 1_000 of them dead
+        assert_eq!(client.get_liquidity_position(&ctx.lp), 26_100);
+        assert_eq!(client.get_liquidity_position(&lp2), 9_000);
+
+        // Both LPs can exit in full; nobody is left holding the loss
+        assert_eq!(client.withdraw_liquidity(&lp2, &10_000i128), 9_000);
+        assert_eq!(client.withdraw_liquidity(&ctx.lp, &29_000i128), 26_100);
+        assert_eq!(client.get_pool_stats().total_shares, DEAD_SHARES);
+        assert_eq!(token_balance(&ctx.env, &ctx.pool_token, &ctx.contract), 900);
+    }
+
     #[test]
     fn test_withdraw_exceeds_lp_balance_fails() {
         let ctx = setup();
         let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-        client.deposit_liquidity(&ctx.lp, &1_000i128).unwrap();
+        client.deposit_liquidity(&ctx.lp, &2_000i128);
         let result = client.try_withdraw_liquidity(&ctx.lp, &2_000i128);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_deposit_skips_unowned_liquidity_and_refuses_wiped_out_pool() {
+        let ctx = setup();
+        let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+
+        // 700 left in a pool with no shares, 200 of it reserved
+        mint_token(&ctx.env, &ctx.pool_token, &ctx.contract, 700);
+        let mut pool = RiskPool::new();
+        pool.total_liquidity = 700;
+        pool.reserved_liquidity = 200;
+        ctx.env.as_contract(&ctx.contract, || InsuranceStorage::set_pool(&ctx.env, &pool));
+
+        // The next LP gets shares for its own deposit only; the reserved 200
+        // stays with the pool, split with the dead shares
+        assert_eq!(client.deposit_liquidity(&ctx.lp, &2_000i128), 1_000);
+        let pool = client.get_pool_stats();
+        assert_eq!(pool.surplus, 500);
+        assert_eq!(pool.total_liquidity, 2_200);
+        assert_eq!(client.get_liquidity_position(&ctx.lp), 1_100);
+
+        assert_eq!(client.withdraw_surplus(&ctx.admin, &ctx.admin), 500);
+        assert_eq!(token_balance(&ctx.env, &ctx.pool_token, &ctx.admin), 500);
+        assert_eq!(client.get_pool_stats().surplus, 0);
+
+        // Shares backed by nothing take no new deposits
+        let mut pool = client.get_pool_stats();
+        pool.total_liquidity = 0;
+        ctx.env.as_contract(&ctx.contract, || InsuranceStorage::set_pool(&ctx.env, &pool));
+        let result = client.try_deposit_liquidity(&ctx.lp, &1_000i128);
+        assert!(matches!(result, Err(Ok(InsuranceError::PoolWipedOut))));
+    }
+
     #[test]
     fn test_create_policy_and_pool_accounting() {
         let ctx = setup();
         let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
         // LP provides capital
-        client.deposit_liquidity(&ctx.lp, &10_000i128).unwrap();
+        client.deposit_liquidity(&ctx.lp, &10_000i128);
 
         let feed = Symbol::new(&ctx.env, "RAINFALL");
         let policy_id = client
@@ -819,8 +2430,7 @@ mod test {
                 &5_000i128,         // coverage
                 &500i128,           // premium
                 &86_400u64,         // 1 day
-            )
-            .unwrap();
+            );
 
         assert_eq!(policy_id, 1);
 
@@ -862,7 +2472,7 @@ mod test {
         let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
         // Fund pool
-        client.deposit_liquidity(&ctx.lp, &10_000i128).unwrap();
+        client.deposit_liquidity(&ctx.lp, &10_000i128);
 
         let feed = Symbol::new(&ctx.env, "TEMP_NYC");
         let policy_id = client
@@ -875,8 +2485,7 @@ mod test {
                 &4_000i128,
                 &400i128,
                 &86_400u64,
-            )
-            .unwrap();
+            );
 
         let holder_balance_before =
             token_balance(&ctx.env, &ctx.pool_token, &ctx.policyholder);
@@ -885,7 +2494,7 @@ mod test {
         let oracle_client = MockOracleClient::new(&ctx.env, &ctx.oracle);
         oracle_client.set_price(&feed, &(-150i128), &ctx.env.ledger().timestamp());
 
-        let triggered = client.check_trigger(&policy_id).unwrap();
+        let triggered = client.check_trigger(&policy_id);
         assert!(triggered);
 
         let policy = client.get_policy(&policy_id).unwrap();
@@ -906,7 +2515,7 @@ mod test {
         let ctx = setup();
         let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-        client.deposit_liquidity(&ctx.lp, &10_000i128).unwrap();
+        client.deposit_liquidity(&ctx.lp, &10_000i128);
 
         let feed = Symbol::new(&ctx.env, "WIND_CHI");
         let policy_id = client
@@ -919,14 +2528,13 @@ mod test {
                 &3_000i128,
                 &300i128,
                 &86_400u64,
-            )
-            .unwrap();
+            );
 
         // Oracle reports 100 km/h — below threshold
         let oracle_client = MockOracleClient::new(&ctx.env, &ctx.oracle);
         oracle_client.set_price(&feed, &100i128, &ctx.env.ledger().timestamp());
 
-        let triggered = client.check_trigger(&policy_id).unwrap();
+        let triggered = client.check_trigger(&policy_id);
         assert!(!triggered);
 
         let policy = client.get_policy(&policy_id).unwrap();
@@ -938,7 +2546,7 @@ mod test {
         let ctx = setup();
         let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-        client.deposit_liquidity(&ctx.lp, &10_000i128).unwrap();
+        client.deposit_liquidity(&ctx.lp, &10_000i128);
 
         let feed = Symbol::new(&ctx.env, "QUAKE_SF");
         let policy_id = client
@@ -951,13 +2559,12 @@ mod test {
                 &6_000i128,
                 &600i128,
                 &86_400u64,
-            )
-            .unwrap();
+            );
 
         // Reservation should be active
         assert_eq!(client.get_pool_stats().reserved_liquidity, 6_000);
 
-        client.cancel_policy(&ctx.policyholder, &policy_id).unwrap();
+        client.cancel_policy(&ctx.policyholder, &policy_id);
 
         let policy = client.get_policy(&policy_id).unwrap();
         assert_eq!(policy.status, PolicyStatus::Cancelled);
@@ -971,7 +2578,7 @@ mod test {
         let ctx = setup();
         let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-        client.deposit_liquidity(&ctx.lp, &10_000i128).unwrap();
+        client.deposit_liquidity(&ctx.lp, &10_000i128);
 
         let feed = Symbol::new(&ctx.env, "FLOOD_MIA");
         let policy_id = client
@@ -984,13 +2591,12 @@ mod test {
                 &4_000i128,
                 &400i128,
                 &3_600u64,   // 1-hour policy
-            )
-            .unwrap();
+            );
 
         // Advance time past expiry
         advance_time(&ctx.env, 7_200);
 
-        client.expire_policy(&policy_id).unwrap();
+        client.expire_policy(&policy_id);
 
         let policy = client.get_policy(&policy_id).unwrap();
         assert_eq!(policy.status, PolicyStatus::Expired);
@@ -1003,7 +2609,7 @@ mod test {
         let ctx = setup();
         let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-        client.deposit_liquidity(&ctx.lp, &50_000i128).unwrap();
+        client.deposit_liquidity(&ctx.lp, &50_000i128);
 
         let f1 = Symbol::new(&ctx.env, "RAIN_LON");
         let f2 = Symbol::new(&ctx.env, "DELAY_BA");
@@ -1018,8 +2624,7 @@ mod test {
                 &2_000i128,
                 &200i128,
                 &86_400u64,
-            )
-            .unwrap();
+            );
 
         client
             .create_policy(
@@ -1031,8 +2636,7 @@ mod test {
                 &1_500i128,
                 &150i128,
                 &172_800u64,
-            )
-            .unwrap();
+            );
 
         let policies = client.get_policies_by_holder(&ctx.policyholder);
         assert_eq!(policies.len(), 2);
@@ -1043,9 +2647,11 @@ mod test {
         let ctx = setup();
         let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-        client.deposit_liquidity(&ctx.lp, &10_000i128).unwrap();
+        client.deposit_liquidity(&ctx.lp, &10_000i128);
 
         let feed = Symbol::new(&ctx.env, "CROP_KAN");
+        MockOracleClient::new(&ctx.env, &ctx.oracle)
+            .set_price(&feed, &80i128, &ctx.env.ledger().timestamp());
         let policy_id = client
             .create_policy(
                 &ctx.policyholder,
@@ -1056,11 +2662,121 @@ mod test {
                 &3_000i128,
                 &300i128,
                 &86_400u64,
-            )
-            .unwrap();
+            );
 
         // LP tries to cancel policyholder's policy — must fail
         let result = client.try_cancel_policy(&ctx.lp, &policy_id);
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_underpriced_premium_is_rejected() {
+        let ctx = setup();
+        let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+
+        client.deposit_liquidity(&ctx.lp, &10_000i128);
+
+        let feed = Symbol::new(&ctx.env, "RAINFALL");
+        let result = client.try_create_policy(
+            &ctx.policyholder,
+            &PolicyType::Weather,
+            &feed,
+            &200i128,
+            &TriggerCondition::GreaterOrEqual,
+            &5_000i128,
+            &1i128,            // 1-stroop premium for full coverage
+            &86_400u64,
+        );
+        assert!(matches!(result, Err(Ok(InsuranceError::PremiumTooLow))));
+        assert_eq!(client.get_pool_stats().reserved_liquidity, 0);
+    }
+
+    #[test]
+    fn test_quote_reflects_proximity_and_utilisation() {
+        let ctx = setup();
+        let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+        let oracle = MockOracleClient::new(&ctx.env, &ctx.oracle);
+
+        client.deposit_liquidity(&ctx.lp, &100_000i128);
+
+        let feed = Symbol::new(&ctx.env, "RAINFALL");
+        let quote = |coverage: i128| {
+            client.quote_premium(
+                &PolicyType::Weather,
+                &feed,
+                &200i128,
+                &TriggerCondition::GreaterOrEqual,
+                &coverage,
+                &31_536_000u64,
+            )
+        };
+
+        // Far from the trigger: 10% base × 1.1 utilisation loading
+        oracle.set_price(&feed, &0i128, &ctx.env.ledger().timestamp());
+        assert_eq!(quote(10_000), 1_100);
+
+        // 5% away from the trigger adds a 1.8× proximity loading
+        oracle.set_price(&feed, &190i128, &ctx.env.ledger().timestamp());
+        assert_eq!(quote(10_000), 3_080);
+
+        // Higher post-policy utilisation costs more per unit of coverage
+        oracle.set_price(&feed, &0i128, &ctx.env.ledger().timestamp());
+        assert_eq!(quote(90_000), 17_100);
+    }
+
+    #[test]
+    fn test_trigger_already_met_is_not_insurable() {
+        let ctx = setup();
+        let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+
+        client.deposit_liquidity(&ctx.lp, &10_000i128);
+
+        let feed = Symbol::new(&ctx.env, "WIND_CHI");
+        MockOracleClient::new(&ctx.env, &ctx.oracle)
+            .set_price(&feed, &180i128, &ctx.env.ledger().timestamp());
+
+        let result = client.try_create_policy(
+            &ctx.policyholder,
+            &PolicyType::Weather,
+            &feed,
+            &150i128,
+            &TriggerCondition::GreaterThan,
+            &3_000i128,
+            &3_000i128,
+            &86_400u64,
+        );
+        assert!(matches!(result, Err(Ok(InsuranceError::TriggerAlreadyMet))));
+    }
+
+    #[test]
+    fn test_update_pricing_config() {
+        let ctx = setup();
+        let client = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+
+        let mut config = client.get_pricing_config();
+        assert_eq!(config, PricingConfig::default());
+
+        config.min_premium_bps = 20_000;
+        let result = client.try_update_pricing_config(&ctx.admin, &config);
+        assert!(matches!(result, Err(Ok(InsuranceError::InvalidPricingConfig))));
+
+        config.min_premium_bps = 1_000;
+        assert!(client.try_update_pricing_config(&ctx.lp, &config).is_err());
+
+        client.update_pricing_config(&ctx.admin, &config);
+        assert_eq!(client.get_pricing_config().min_premium_bps, 1_000);
+
+        // 10% floor now dominates a one-day quote
+        client.deposit_liquidity(&ctx.lp, &10_000i128);
+        let feed = Symbol::new(&ctx.env, "RAINFALL");
+        let quoted = client.quote_premium(
+            &PolicyType::Weather,
+            &feed,
+            &200i128,
+            &TriggerCondition::GreaterOrEqual,
+            &5_000i128,
+            &86_400u64,
+        );
+        assert_eq!(quoted, 500);
+    }
+}
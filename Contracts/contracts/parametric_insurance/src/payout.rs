@@ -0,0 +1,121 @@
+//! Graduated payout evaluation for the Parametric Insurance Protocol
+//!
+//! A policy's `payout_schedule` maps the live oracle value to the share of
+//! coverage the policyholder is entitled to in total:
+//!
+//! ```text
+//! Binary          → 100% once trigger_threshold is met
+//! Tiered(tiers)   → payout_bps of the furthest tier met
+//! Linear(exhaust) → (value − attachment) / (exhaust − attachment), clamped to [0, 100%]
+//! ```
+//!
+//! Entitlement is cumulative: `check_trigger` pays the difference between the
+//! current entitlement and what has already been paid, so a policy can be
+//! claimed in several steps as the index worsens.
+
+use crate::errors::InsuranceError;
+use crate::pricing::BPS;
+use crate::types::{InsurancePolicy, PayoutSchedule, TriggerCondition};
+
+/// Upper bound on tiers per policy, to keep trigger checks cheap
+pub const MAX_PAYOUT_TIERS: u32 = 10;
+
+/// `Some(true)` for conditions met by rising values, `Some(false)` for falling
+/// values, `None` for `EqualTo` (which only supports binary payouts).
+fn rising(condition: &TriggerCondition) -> Option<bool> {
+    match condition {
+        TriggerCondition::GreaterThan | TriggerCondition::GreaterOrEqual => Some(true),
+        TriggerCondition::LessThan | TriggerCondition::LessOrEqual => Some(false),
+        TriggerCondition::EqualTo => None,
+    }
+}
+
+/// Check a schedule is consistent with the policy's trigger.
+///
+/// Tiers must start at `trigger_threshold`, move further in the trigger's
+/// direction and pay strictly increasing percentages up to 100%. A linear
+/// exhaustion point must lie beyond the attachment point.
+pub fn validate_schedule(
+    schedule: &PayoutSchedule,
+    trigger_threshold: i128,
+    trigger_condition: &TriggerCondition,
+) -> Result<(), InsuranceError> {
+    if *schedule == PayoutSchedule::Binary {
+        return Ok(());
+    }
+    let up = rising(trigger_condition).ok_or(InsuranceError::InvalidPayoutSchedule)?;
+
+    match schedule {
+        PayoutSchedule::Binary => Ok(()),
+        PayoutSchedule::Tiered(tiers) => {
+            if tiers.is_empty() || tiers.len() > MAX_PAYOUT_TIERS {
+                return Err(InsuranceError::InvalidPayoutSchedule);
+            }
+            if tiers.get_unchecked(0).threshold != trigger_threshold {
+                return Err(InsuranceError::InvalidPayoutSchedule);
+            }
+            let mut prev: Option<(i128, u32)> = None;
+            for tier in tiers.iter() {
+                if tier.payout_bps == 0 || tier.payout_bps as i128 > BPS {
+                    return Err(InsuranceError::InvalidPayoutSchedule);
+                }
+                if let Some((prev_threshold, prev_bps)) = prev {
+                    let further = if up {
+                        tier.threshold > prev_threshold
+                    } else {
+                        tier.threshold < prev_threshold
+                    };
+                    if !further || tier.payout_bps <= prev_bps {
+                        return Err(InsuranceError::InvalidPayoutSchedule);
+                    }
+                }
+                prev = Some((tier.threshold, tier.payout_bps));
+            }
+            Ok(())
+        }
+        PayoutSchedule::Linear(exhaustion) => {
+            let beyond = if up {
+                *exhaustion > trigger_threshold
+            } else {
+                *exhaustion < trigger_threshold
+            };
+            if !beyond {
+                return Err(InsuranceError::InvalidPayoutSchedule);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Total share of coverage (in bps) owed at `value`.
+pub fn entitlement_bps(policy: &InsurancePolicy, value: i128) -> i128 {
+    let condition = &policy.trigger_condition;
+    if !condition.is_met(value, policy.trigger_threshold) {
+        return 0;
+    }
+
+    match &policy.payout_schedule {
+        PayoutSchedule::Binary => BPS,
+        PayoutSchedule::Tiered(tiers) => {
+            let mut bps = 0i128;
+            for tier in tiers.iter() {
+                if condition.is_met(value, tier.threshold) {
+                    bps = bps.max(tier.payout_bps as i128);
+                }
+            }
+            bps
+        }
+        PayoutSchedule::Linear(exhaustion) => {
+            let span = exhaustion - policy.trigger_threshold;
+            let progressed = value - policy.trigger_threshold;
+            // Same sign as span once the condition is met
+            (progressed.saturating_mul(BPS) / span).clamp(0, BPS)
+        }
+    }
+}
+
+/// Amount still owed to the policyholder at `value`, net of prior payouts.
+pub fn outstanding_payout(policy: &InsurancePolicy, value: i128) -> i128 {
+    let entitled = policy.coverage_amount * entitlement_bps(policy, value) / BPS;
+    entitled.min(policy.coverage_amount).saturating_sub(policy.paid_out).max(0)
+}
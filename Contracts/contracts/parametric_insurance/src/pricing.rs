@@ -0,0 +1,259 @@
+//! Premium pricing engine for the Parametric Insurance Protocol
+//!
+//! Quotes are derived from the policy terms, the live oracle reading and the
+//! state of the risk pool:
+//!
+//! ```text
+//! base      = coverage × base_rate × type_multiplier × duration / YEAR
+//! proximity = 1 + proximity_loading × max(0, 1 − distance / proximity_range)
+//! util      = 1 + utilisation_loading × (reserved + coverage) / total_liquidity
+//! premium   = clamp(base × proximity × util, coverage × min_premium, coverage)
+//! ```
+//!
+//! `distance` is how far the current oracle value sits from the trigger
+//! threshold, relative to `|threshold|`. A policy whose trigger is already
+//! satisfied is priced as a certain payout and refused.
+//!
+//! Composite triggers price each condition on its own and combine them:
+//! `AllOf` takes the cheapest condition, `AnyOf` sums them (capped at
+//! coverage), and `AllOfAny` takes the cheapest clause sum.
+//!
+//! Premiums are earned evenly over a policy's term. Cancelling refunds the
+//! unearned part less the configured fees:
+//!
+//! ```text
+//! unearned = premium × (end − now) / (end − start)
+//! refund   = max(0, unearned × (1 − cancellation_fee) − flat_fee)
+//! ```
+
+use soroban_sdk::{Map, Symbol, Vec};
+
+use crate::errors::InsuranceError;
+use crate::types::{
+    FeedCondition, PolicyType, PricingConfig, RefundConfig, RiskPool, TriggerCondition,
+    TriggerExpression,
+};
+
+/// Basis-point denominator
+pub const BPS: i128 = 10_000;
+
+/// Seconds in a 365-day year, used to annualise `base_rate_bps`
+pub const SECONDS_PER_YEAR: i128 = 31_536_000;
+
+/// Reject configurations that would produce meaningless quotes.
+pub fn validate_config(config: &PricingConfig) -> Result<(), InsuranceError> {
+    if config.weather_bps == 0
+        || config.flight_delay_bps == 0
+        || config.natural_disaster_bps == 0
+        || config.crop_bps == 0
+        || config.custom_bps == 0
+        || config.proximity_range_bps == 0
+        || config.min_premium_bps as i128 > BPS
+    {
+        return Err(InsuranceError::InvalidPricingConfig);
+    }
+    Ok(())
+}
+
+/// Reject refund fees above 100% or below zero.
+pub fn validate_refund_config(config: &RefundConfig) -> Result<(), InsuranceError> {
+    if config.cancellation_fee_bps as i128 > BPS || config.flat_fee < 0 {
+        return Err(InsuranceError::InvalidRefundConfig);
+    }
+    Ok(())
+}
+
+/// Amount refunded when a policy with `premium` covering `start..end` is
+/// cancelled at `now`.
+pub fn cancellation_refund(
+    config: &RefundConfig,
+    premium: i128,
+    start: u64,
+    end: u64,
+    now: u64,
+) -> i128 {
+    if now >= end || end <= start {
+        return 0;
+    }
+    let term = (end - start) as i128;
+    let remaining = (end - now.max(start)) as i128;
+    let unearned = premium * remaining / term;
+    let fee = unearned * config.cancellation_fee_bps as i128 / BPS;
+    (unearned - fee - config.flat_fee).max(0)
+}
+
+/// Risk multiplier for a policy category.
+pub fn type_multiplier_bps(config: &PricingConfig, policy_type: &PolicyType) -> i128 {
+    let bps = match policy_type {
+        PolicyType::Weather => config.weather_bps,
+        PolicyType::FlightDelay => config.flight_delay_bps,
+        PolicyType::NaturalDisaster => config.natural_disaster_bps,
+        PolicyType::Crop => config.crop_bps,
+        PolicyType::Custom => config.custom_bps,
+    };
+    bps as i128
+}
+
+/// Distance of `current_value` from `threshold`, in bps of `|threshold|`.
+pub fn trigger_distance_bps(current_value: i128, threshold: i128) -> i128 {
+    let diff = (current_value - threshold).abs();
+    diff.saturating_mul(BPS) / threshold.abs().max(1)
+}
+
+/// Proximity loading for a given trigger distance; tapers linearly to zero at
+/// `proximity_range_bps`.
+pub fn proximity_loading_bps(config: &PricingConfig, distance_bps: i128) -> i128 {
+    let range = config.proximity_range_bps as i128;
+    if distance_bps >= range {
+        return 0;
+    }
+    config.proximity_loading_bps as i128 * (range - distance_bps) / range
+}
+
+/// Utilisation loading once `coverage_amount` is reserved on top of the
+/// pool's existing reservations.
+pub fn utilisation_loading_bps(config: &PricingConfig, pool: &RiskPool, coverage_amount: i128) -> i128 {
+    let reserved = pool.reserved_liquidity + coverage_amount;
+    let utilisation_bps = if pool.total_liquidity <= 0 {
+        BPS
+    } else {
+        (reserved.saturating_mul(BPS) / pool.total_liquidity).min(BPS)
+    };
+    config.utilisation_loading_bps as i128 * utilisation_bps / BPS
+}
+
+/// Quote the premium for a policy given the current oracle reading.
+#[allow(clippy::too_many_arguments)]
+pub fn quote(
+    config: &PricingConfig,
+    pool: &RiskPool,
+    policy_type: &PolicyType,
+    trigger_threshold: i128,
+    trigger_condition: &TriggerCondition,
+    coverage_amount: i128,
+    duration_secs: u64,
+    current_value: i128,
+) -> Result<i128, InsuranceError> {
+    check_terms(coverage_amount, duration_secs)?;
+    if trigger_condition.is_met(current_value, trigger_threshold) {
+        return Err(InsuranceError::TriggerAlreadyMet);
+    }
+    condition_premium(
+        config,
+        pool,
+        policy_type,
+        trigger_threshold,
+        trigger_condition,
+        coverage_amount,
+        duration_secs,
+        current_value,
+    )
+}
+
+/// Quote a composite trigger from the current aggregated value of each feed.
+///
+/// The caller is responsible for rejecting expressions that already hold.
+#[allow(clippy::too_many_arguments)]
+pub fn quote_expression(
+    config: &PricingConfig,
+    pool: &RiskPool,
+    policy_type: &PolicyType,
+    expression: &TriggerExpression,
+    coverage_amount: i128,
+    duration_secs: u64,
+    values: &Map<Symbol, i128>,
+) -> Result<i128, InsuranceError> {
+    check_terms(coverage_amount, duration_secs)?;
+
+    let premium_of = |cond: &FeedCondition| -> Result<i128, InsuranceError> {
+        let value = values
+            .get(cond.feed.clone())
+            .ok_or(InsuranceError::OracleFailure)?;
+        condition_premium(
+            config,
+            pool,
+            policy_type,
+            cond.threshold,
+            &cond.condition,
+            coverage_amount,
+            duration_secs,
+            value,
+        )
+    };
+    let sum = |list: &Vec<FeedCondition>| -> Result<i128, InsuranceError> {
+        let mut total = 0i128;
+        for cond in list.iter() {
+            total += premium_of(&cond)?;
+        }
+        Ok(total.min(coverage_amount))
+    };
+
+    let premium = match expression {
+        TriggerExpression::AllOf(list) => {
+            let mut cheapest = coverage_amount;
+            for cond in list.iter() {
+                cheapest = cheapest.min(premium_of(&cond)?);
+            }
+            cheapest
+        }
+        TriggerExpression::AnyOf(list) => sum(list)?,
+        TriggerExpression::AllOfAny(clauses) => {
+            let mut cheapest = coverage_amount;
+            for clause in clauses.iter() {
+                cheapest = cheapest.min(sum(&clause)?);
+            }
+            cheapest
+        }
+    };
+
+    let floor = (coverage_amount * config.min_premium_bps as i128 / BPS).max(1);
+    Ok(premium.max(floor))
+}
+
+fn check_terms(coverage_amount: i128, duration_secs: u64) -> Result<(), InsuranceError> {
+    if coverage_amount <= 0 {
+        return Err(InsuranceError::InvalidAmount);
+    }
+    if duration_secs == 0 {
+        return Err(InsuranceError::InvalidDuration);
+    }
+    Ok(())
+}
+
+/// Premium for a single condition; a condition already met costs the full
+/// coverage.
+#[allow(clippy::too_many_arguments)]
+fn condition_premium(
+    config: &PricingConfig,
+    pool: &RiskPool,
+    policy_type: &PolicyType,
+    trigger_threshold: i128,
+    trigger_condition: &TriggerCondition,
+    coverage_amount: i128,
+    duration_secs: u64,
+    current_value: i128,
+) -> Result<i128, InsuranceError> {
+    if trigger_condition.is_met(current_value, trigger_threshold) {
+        return Ok(coverage_amount);
+    }
+
+    let base = coverage_amount
+        .checked_mul(config.base_rate_bps as i128)
+        .and_then(|v| v.checked_mul(type_multiplier_bps(config, policy_type)))
+        .and_then(|v| v.checked_mul(duration_secs as i128))
+        .ok_or(InsuranceError::InvalidAmount)?
+        / (BPS * BPS * SECONDS_PER_YEAR);
+
+    let distance = trigger_distance_bps(current_value, trigger_threshold);
+    let proximity = BPS + proximity_loading_bps(config, distance);
+    let utilisation = BPS + utilisation_loading_bps(config, pool, coverage_amount);
+
+    let loaded = base
+        .checked_mul(proximity)
+        .and_then(|v| v.checked_mul(utilisation))
+        .ok_or(InsuranceError::InvalidAmount)?
+        / (BPS * BPS);
+
+    let floor = (coverage_amount * config.min_premium_bps as i128 / BPS).max(1);
+    Ok(loaded.max(floor).min(coverage_amount))
+}
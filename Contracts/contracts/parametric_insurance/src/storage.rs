@@ -1,12 +1,19 @@
 //! Storage layer for the Parametric Insurance Protocol
 //!
 //! Storage tier strategy:
-//!   Instance   – admin, pool_token, oracle config, pool stats, pause flag, counter
-//!   Persistent – individual policies, LP positions, per-holder policy ID indexes
+//!   Instance   – admin, pool_token, oracle / pricing / refund / reinsurance config,
+//!                exposure limits, pool and reinsurance tranche stats, pause flag, counter,
+//!                withdrawal cooldown and queue bounds
+//!   Persistent – individual policies, policy transfer approvals, LP and reinsurer share
+//!                balances, queued withdrawal requests, per-holder and per-feed policy ID
+//!                indexes, per-feed and per-type exposure
 
-use soroban_sdk::{contracttype, Address, Env, Vec};
+use soroban_sdk::{contracttype, Address, Env, Symbol, Vec};
 
-use crate::types::{InsurancePolicy, OracleConfig, PolicyStatus, RiskPool};
+use crate::types::{
+    ExposureLimits, InsurancePolicy, OracleConfig, PolicyStatus, PolicyType, PricingConfig,
+    RefundConfig, ReinsuranceConfig, RiskPool, WithdrawalRequest,
+};
 
 // =============================================================================
 // Storage keys
@@ -25,16 +32,46 @@ pub enum InsuranceDataKey {
     PoolToken,
     /// Oracle source list + staleness settings
     OracleConfig,
+    /// Premium pricing parameters
+    PricingConfig,
+    /// Cancellation refund curve
+    RefundConfig,
     /// Monotonically increasing policy counter
     PolicyCounter,
     /// Aggregated risk pool accounting
     RiskPool,
     /// Individual policy record keyed by policy ID
     Policy(u64),
-    /// Amount an LP deposited (Address → i128)
-    LiquidityProvider(Address),
+    /// Pool shares held by an LP (Address → i128)
+    LpShares(Address),
+    /// Seconds an LP withdrawal request waits before it can be fulfilled
+    WithdrawalCooldown,
+    /// ID of the oldest withdrawal request not yet processed
+    WithdrawalQueueHead,
+    /// ID of the most recent withdrawal request
+    WithdrawalQueueTail,
+    /// Queued LP withdrawal keyed by request ID
+    WithdrawalRequest(u64),
     /// List of policy IDs owned by a holder (Address → Vec<u64>)
     PolicyIdsByHolder(Address),
+    /// Address approved to transfer a policy on its holder's behalf
+    PolicyApproval(u64),
+    /// Active policies watching an oracle feed (Symbol → Vec<u64>)
+    ActivePoliciesByFeed(Symbol),
+    /// Reward paid to keepers per executed batch action
+    KeeperReward,
+    /// Excess-of-loss reinsurance terms
+    ReinsuranceConfig,
+    /// Reinsurance tranche accounting
+    ReinsurancePool,
+    /// Reinsurance tranche shares held by a provider (Address → i128)
+    ReinsuranceShares(Address),
+    /// Per-feed / per-type concentration limits
+    ExposureLimits,
+    /// Outstanding coverage watching an oracle feed (Symbol → i128)
+    FeedExposure(Symbol),
+    /// Outstanding coverage of a policy category (PolicyType → i128)
+    TypeExposure(PolicyType),
 }
 
 // =============================================================================
@@ -107,6 +144,47 @@ impl InsuranceStorage {
         env.storage().instance().get(&InsuranceDataKey::OracleConfig)
     }
 
+    // ── Pricing config ────────────────────────────────────────────────────────
+
+    pub fn set_pricing_config(env: &Env, config: &PricingConfig) {
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::PricingConfig, config);
+    }
+
+    pub fn get_pricing_config(env: &Env) -> PricingConfig {
+        env.storage()
+            .instance()
+            .get(&InsuranceDataKey::PricingConfig)
+            .unwrap_or_default()
+    }
+
+    pub fn get_keeper_reward(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&InsuranceDataKey::KeeperReward)
+            .unwrap_or(0)
+    }
+
+    pub fn set_keeper_reward(env: &Env, reward: i128) {
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::KeeperReward, &reward);
+    }
+
+    pub fn set_refund_config(env: &Env, config: &RefundConfig) {
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::RefundConfig, config);
+    }
+
+    pub fn get_refund_config(env: &Env) -> RefundConfig {
+        env.storage()
+            .instance()
+            .get(&InsuranceDataKey::RefundConfig)
+            .unwrap_or_default()
+    }
+
     // ── Risk pool ─────────────────────────────────────────────────────────────
 
     pub fn get_pool(env: &Env) -> RiskPool {
@@ -186,6 +264,49 @@ impl InsuranceStorage {
         env.storage().persistent().set(&key, &ids);
     }
 
+    fn remove_policy_from_holder_index(env: &Env, holder: &Address, policy_id: u64) {
+        let key = InsuranceDataKey::PolicyIdsByHolder(holder.clone());
+        let ids = Self::get_policy_ids_by_holder(env, holder);
+        let mut kept = Vec::new(env);
+        for existing in ids.iter() {
+            if existing != policy_id {
+                kept.push_back(existing);
+            }
+        }
+        if kept.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &kept);
+        }
+    }
+
+    /// Move `policy` to `new_holder`, keeping both holder indexes in sync.
+    pub fn transfer_policy(env: &Env, policy: &mut InsurancePolicy, new_holder: &Address) {
+        Self::remove_policy_from_holder_index(env, &policy.policyholder, policy.id);
+        policy.policyholder = new_holder.clone();
+        Self::set_policy(env, policy);
+    }
+
+    // ── Transfer approvals (persistent) ───────────────────────────────────────
+
+    pub fn get_policy_approval(env: &Env, policy_id: u64) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&InsuranceDataKey::PolicyApproval(policy_id))
+    }
+
+    pub fn set_policy_approval(env: &Env, policy_id: u64, spender: &Address) {
+        env.storage()
+            .persistent()
+            .set(&InsuranceDataKey::PolicyApproval(policy_id), spender);
+    }
+
+    pub fn clear_policy_approval(env: &Env, policy_id: u64) {
+        env.storage()
+            .persistent()
+            .remove(&InsuranceDataKey::PolicyApproval(policy_id));
+    }
+
     pub fn get_policy_ids_by_holder(env: &Env, holder: &Address) -> Vec<u64> {
         env.storage()
             .persistent()
@@ -193,6 +314,39 @@ impl InsuranceStorage {
             .unwrap_or_else(|| Vec::new(env))
     }
 
+    // ── Per-feed active index (persistent) ────────────────────────────────────
+
+    pub fn get_active_policy_ids_by_feed(env: &Env, feed: &Symbol) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&InsuranceDataKey::ActivePoliciesByFeed(feed.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    pub fn add_active_policy_for_feed(env: &Env, feed: &Symbol, policy_id: u64) {
+        let mut ids = Self::get_active_policy_ids_by_feed(env, feed);
+        if ids.contains(policy_id) {
+            return;
+        }
+        ids.push_back(policy_id);
+        env.storage()
+            .persistent()
+            .set(&InsuranceDataKey::ActivePoliciesByFeed(feed.clone()), &ids);
+    }
+
+    pub fn remove_active_policy_for_feed(env: &Env, feed: &Symbol, policy_id: u64) {
+        let key = InsuranceDataKey::ActivePoliciesByFeed(feed.clone());
+        let mut ids = Self::get_active_policy_ids_by_feed(env, feed);
+        if let Some(idx) = ids.first_index_of(policy_id) {
+            ids.remove(idx);
+        }
+        if ids.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &ids);
+        }
+    }
+
     pub fn get_policies_by_holder(env: &Env, holder: &Address) -> Vec<InsurancePolicy> {
         let ids = Self::get_policy_ids_by_holder(env, holder);
         let mut policies = Vec::new(env);
@@ -204,18 +358,162 @@ impl InsuranceStorage {
         policies
     }
 
-    // ── LP positions (persistent) ─────────────────────────────────────────────
+    // ── LP shares (persistent) ────────────────────────────────────────────────
+
+    pub fn get_lp_shares(env: &Env, provider: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&InsuranceDataKey::LpShares(provider.clone()))
+            .unwrap_or(0)
+    }
+
+    pub fn set_lp_shares(env: &Env, provider: &Address, shares: i128) {
+        env.storage()
+            .persistent()
+            .set(&InsuranceDataKey::LpShares(provider.clone()), &shares);
+    }
+
+    // ── Withdrawal queue ──────────────────────────────────────────────────────
+
+    pub fn get_withdrawal_cooldown(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&InsuranceDataKey::WithdrawalCooldown)
+            .unwrap_or(0)
+    }
+
+    pub fn set_withdrawal_cooldown(env: &Env, secs: u64) {
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::WithdrawalCooldown, &secs);
+    }
+
+    /// ID of the oldest request still to be processed (requests start at 1)
+    pub fn get_withdrawal_queue_head(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&InsuranceDataKey::WithdrawalQueueHead)
+            .unwrap_or(1)
+    }
+
+    pub fn set_withdrawal_queue_head(env: &Env, head: u64) {
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::WithdrawalQueueHead, &head);
+    }
+
+    pub fn get_withdrawal_queue_tail(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&InsuranceDataKey::WithdrawalQueueTail)
+            .unwrap_or(0)
+    }
+
+    /// Allocate the next withdrawal request ID.
+    pub fn next_withdrawal_id(env: &Env) -> u64 {
+        let next = Self::get_withdrawal_queue_tail(env) + 1;
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::WithdrawalQueueTail, &next);
+        next
+    }
+
+    pub fn get_withdrawal_request(env: &Env, id: u64) -> Option<WithdrawalRequest> {
+        env.storage()
+            .persistent()
+            .get(&InsuranceDataKey::WithdrawalRequest(id))
+    }
+
+    pub fn set_withdrawal_request(env: &Env, request: &WithdrawalRequest) {
+        env.storage()
+            .persistent()
+            .set(&InsuranceDataKey::WithdrawalRequest(request.id), request);
+    }
+
+    pub fn remove_withdrawal_request(env: &Env, id: u64) {
+        env.storage()
+            .persistent()
+            .remove(&InsuranceDataKey::WithdrawalRequest(id));
+    }
+
+    // ── Reinsurance ───────────────────────────────────────────────────────────
+
+    pub fn get_reinsurance_config(env: &Env) -> Option<ReinsuranceConfig> {
+        env.storage()
+            .instance()
+            .get(&InsuranceDataKey::ReinsuranceConfig)
+    }
+
+    pub fn set_reinsurance_config(env: &Env, config: &ReinsuranceConfig) {
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::ReinsuranceConfig, config);
+    }
+
+    pub fn get_reinsurance_pool(env: &Env) -> RiskPool {
+        env.storage()
+            .instance()
+            .get(&InsuranceDataKey::ReinsurancePool)
+            .unwrap_or_else(RiskPool::new)
+    }
+
+    pub fn set_reinsurance_pool(env: &Env, pool: &RiskPool) {
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::ReinsurancePool, pool);
+    }
+
+    pub fn get_reinsurance_shares(env: &Env, provider: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&InsuranceDataKey::ReinsuranceShares(provider.clone()))
+            .unwrap_or(0)
+    }
+
+    pub fn set_reinsurance_shares(env: &Env, provider: &Address, shares: i128) {
+        env.storage()
+            .persistent()
+            .set(&InsuranceDataKey::ReinsuranceShares(provider.clone()), &shares);
+    }
+
+    // ── Exposure ──────────────────────────────────────────────────────────────
+
+    pub fn get_exposure_limits(env: &Env) -> ExposureLimits {
+        env.storage()
+            .instance()
+            .get(&InsuranceDataKey::ExposureLimits)
+            .unwrap_or_default()
+    }
+
+    pub fn set_exposure_limits(env: &Env, limits: &ExposureLimits) {
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::ExposureLimits, limits);
+    }
+
+    pub fn get_feed_exposure(env: &Env, feed: &Symbol) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&InsuranceDataKey::FeedExposure(feed.clone()))
+            .unwrap_or(0)
+    }
+
+    pub fn set_feed_exposure(env: &Env, feed: &Symbol, amount: i128) {
+        env.storage()
+            .persistent()
+            .set(&InsuranceDataKey::FeedExposure(feed.clone()), &amount);
+    }
 
-    pub fn get_lp_balance(env: &Env, provider: &Address) -> i128 {
+    pub fn get_type_exposure(env: &Env, policy_type: &PolicyType) -> i128 {
         env.storage()
             .persistent()
-            .get(&InsuranceDataKey::LiquidityProvider(provider.clone()))
+            .get(&InsuranceDataKey::TypeExposure(policy_type.clone()))
             .unwrap_or(0)
     }
 
-    pub fn set_lp_balance(env: &Env, provider: &Address, amount: i128) {
+    pub fn set_type_exposure(env: &Env, policy_type: &PolicyType, amount: i128) {
         env.storage()
             .persistent()
-            .set(&InsuranceDataKey::LiquidityProvider(provider.clone()), &amount);
+            .set(&InsuranceDataKey::TypeExposure(policy_type.clone()), &amount);
     }
 }
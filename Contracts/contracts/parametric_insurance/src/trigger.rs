@@ -0,0 +1,96 @@
+//! Composite multi-feed trigger evaluation for the Parametric Insurance Protocol
+//!
+//! A `CompositeTrigger` combines several `FeedCondition`s into one boolean
+//! expression. Every referenced feed is aggregated once per check, then the
+//! expression is evaluated against those values:
+//!
+//! ```text
+//! AllOf([a, b])          → a ∧ b
+//! AnyOf([a, b, c])       → a ∨ b ∨ c
+//! AllOfAny([[a, b], [c]]) → (a ∨ b) ∧ c
+//! ```
+//!
+//! The expression must then hold for `required_observations` consecutive
+//! fresh observations. An observation is fresh when the oldest oracle answer
+//! used is newer than the last one counted, so repeated calls against the
+//! same data never advance the counter.
+
+use soroban_sdk::{Env, Map, Symbol, Vec};
+
+use crate::errors::InsuranceError;
+use crate::types::{CompositeTrigger, FeedCondition, TriggerExpression};
+
+/// Upper bound on conditions per expression, to keep trigger checks cheap
+pub const MAX_TRIGGER_CONDITIONS: u32 = 8;
+
+/// Every condition in the expression, in declaration order.
+pub fn conditions(env: &Env, expression: &TriggerExpression) -> Vec<FeedCondition> {
+    match expression {
+        TriggerExpression::AllOf(list) | TriggerExpression::AnyOf(list) => list.clone(),
+        TriggerExpression::AllOfAny(clauses) => {
+            let mut out = Vec::new(env);
+            for clause in clauses.iter() {
+                out.append(&clause);
+            }
+            out
+        }
+    }
+}
+
+/// Distinct feeds referenced by the expression.
+pub fn feeds(env: &Env, expression: &TriggerExpression) -> Vec<Symbol> {
+    let mut out: Vec<Symbol> = Vec::new(env);
+    for cond in conditions(env, expression).iter() {
+        if !out.contains(&cond.feed) {
+            out.push_back(cond.feed);
+        }
+    }
+    out
+}
+
+/// Reject empty clauses, oversized expressions and zero observation counts.
+pub fn validate(env: &Env, trigger: &CompositeTrigger) -> Result<(), InsuranceError> {
+    if trigger.required_observations == 0 {
+        return Err(InsuranceError::InvalidTriggerExpression);
+    }
+    if let TriggerExpression::AllOfAny(clauses) = &trigger.expression {
+        if clauses.is_empty() {
+            return Err(InsuranceError::InvalidTriggerExpression);
+        }
+        for clause in clauses.iter() {
+            if clause.is_empty() {
+                return Err(InsuranceError::InvalidTriggerExpression);
+            }
+        }
+    }
+    let count = conditions(env, &trigger.expression).len();
+    if count == 0 || count > MAX_TRIGGER_CONDITIONS {
+        return Err(InsuranceError::InvalidTriggerExpression);
+    }
+    Ok(())
+}
+
+fn holds(cond: &FeedCondition, values: &Map<Symbol, i128>) -> bool {
+    values
+        .get(cond.feed.clone())
+        .map(|v| cond.condition.is_met(v, cond.threshold))
+        .unwrap_or(false)
+}
+
+fn all_hold(list: &Vec<FeedCondition>, values: &Map<Symbol, i128>) -> bool {
+    list.iter().all(|c| holds(&c, values))
+}
+
+fn any_holds(list: &Vec<FeedCondition>, values: &Map<Symbol, i128>) -> bool {
+    list.iter().any(|c| holds(&c, values))
+}
+
+/// Evaluate the expression against aggregated feed values. Conditions on a
+/// feed missing from `values` are treated as not met.
+pub fn evaluate(expression: &TriggerExpression, values: &Map<Symbol, i128>) -> bool {
+    match expression {
+        TriggerExpression::AllOf(list) => all_hold(list, values),
+        TriggerExpression::AnyOf(list) => any_holds(list, values),
+        TriggerExpression::AllOfAny(clauses) => clauses.iter().all(|c| any_holds(&c, values)),
+    }
+}
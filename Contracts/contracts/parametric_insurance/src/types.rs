@@ -34,6 +34,87 @@ pub enum TriggerCondition {
     EqualTo = 4,
 }
 
+impl TriggerCondition {
+    /// Returns `true` when `value` satisfies this condition against `threshold`.
+    pub fn is_met(&self, value: i128, threshold: i128) -> bool {
+        match self {
+            TriggerCondition::GreaterThan => value > threshold,
+            TriggerCondition::LessThan => value < threshold,
+            TriggerCondition::GreaterOrEqual => value >= threshold,
+            TriggerCondition::LessOrEqual => value <= threshold,
+            TriggerCondition::EqualTo => value == threshold,
+        }
+    }
+}
+
+/// A single comparison against one aggregated oracle feed
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedCondition {
+    /// Oracle feed key to read
+    pub feed: Symbol,
+    /// Comparison operator applied between the feed value and `threshold`
+    pub condition: TriggerCondition,
+    /// Threshold the feed value is compared against
+    pub threshold: i128,
+}
+
+/// Boolean combination of feed conditions
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum TriggerExpression {
+    /// Every condition must hold (e.g. rainfall < X AND temperature > Y)
+    AllOf(Vec<FeedCondition>),
+    /// At least one condition must hold (e.g. any of three weather stations)
+    AnyOf(Vec<FeedCondition>),
+    /// Every clause must have at least one condition holding (AND of ORs)
+    AllOfAny(Vec<Vec<FeedCondition>>),
+}
+
+/// Multi-feed trigger with a persistence requirement
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompositeTrigger {
+    /// Expression evaluated against the aggregated value of each feed
+    pub expression: TriggerExpression,
+    /// Number of consecutive fresh oracle observations the expression must
+    /// hold for before paying out (1 = pay on first observation)
+    pub required_observations: u32,
+}
+
+/// Which trigger a policy is evaluated against
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum TriggerKind {
+    /// `oracle_feed` compared with `trigger_threshold` via `trigger_condition`
+    SingleFeed,
+    /// Multi-feed expression with a persistence requirement
+    Composite(CompositeTrigger),
+}
+
+/// One step of a tiered payout schedule
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PayoutTier {
+    /// Oracle threshold compared using the policy's `trigger_condition`
+    pub threshold: i128,
+    /// Cumulative share of coverage owed once this tier is met (10_000 = 100%)
+    pub payout_bps: u32,
+}
+
+/// How much of the coverage a trigger pays
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum PayoutSchedule {
+    /// Full coverage once `trigger_threshold` is met, nothing otherwise
+    Binary,
+    /// Stepwise payouts; the first tier's threshold must equal `trigger_threshold`
+    Tiered(Vec<PayoutTier>),
+    /// Linear payout from 0% at `trigger_threshold` (attachment) to 100% at
+    /// the contained exhaustion value
+    Linear(i128),
+}
+
 /// Lifecycle state of a policy
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
@@ -42,7 +123,7 @@ pub enum PolicyStatus {
     Active = 0,
     /// Coverage period ended without a qualifying event
     Expired = 1,
-    /// Trigger was met and payout was executed
+    /// Full coverage has been paid out
     Claimed = 2,
     /// Cancelled by the policyholder before a trigger
     Cancelled = 3,
@@ -62,12 +143,27 @@ pub struct InsurancePolicy {
     pub coverage_amount: i128,
     /// Premium paid upfront in pool_token units
     pub premium_amount: i128,
-    /// Oracle feed key to monitor (e.g. Symbol::new("RAINFALL_NYC"))
+    /// Oracle feed key to monitor (e.g. Symbol::new("RAINFALL_NYC")); for
+    /// composite policies, the feed of the first condition
     pub oracle_feed: Symbol,
     /// The threshold value the oracle reading is compared against
     pub trigger_threshold: i128,
     /// Comparison operator applied between oracle value and threshold
     pub trigger_condition: TriggerCondition,
+    /// Binary, tiered or linear payout of `coverage_amount`
+    pub payout_schedule: PayoutSchedule,
+    /// Cumulative amount already paid by partial or full claims
+    pub paid_out: i128,
+    /// Single-feed or composite trigger
+    pub trigger_kind: TriggerKind,
+    /// Consecutive observations for which the composite trigger has held
+    pub consecutive_hits: u32,
+    /// Oracle timestamp of the last counted composite observation
+    pub last_observation: u64,
+    /// Portion of coverage above the retention backed by the reinsurance tranche
+    pub ceded_coverage: i128,
+    /// Portion of `premium_amount` paid to the reinsurance tranche
+    pub ceded_premium: i128,
     /// Unix timestamp when coverage begins
     pub start_time: u64,
     /// Unix timestamp when coverage expires
@@ -78,13 +174,74 @@ pub struct InsurancePolicy {
     pub created_at: u64,
 }
 
-/// Aggregate statistics and accounting for the shared risk pool
+impl InsurancePolicy {
+    /// Coverage still reserved for this policy (coverage minus payouts so far)
+    pub fn remaining_coverage(&self) -> i128 {
+        self.coverage_amount.saturating_sub(self.paid_out)
+    }
+
+    /// Coverage borne by the primary pool (first loss up to the retention)
+    pub fn retained_coverage(&self) -> i128 {
+        self.coverage_amount - self.ceded_coverage
+    }
+
+    /// Split `payout` into `(primary, reinsurance)` shares. Payouts fill the
+    /// retained layer first; anything above it is ceded.
+    pub fn split_payout(&self, payout: i128) -> (i128, i128) {
+        let primary = (self.retained_coverage() - self.paid_out).clamp(0, payout);
+        (primary, payout - primary)
+    }
+
+    /// Remaining `(primary, reinsurance)` reservations for this policy.
+    pub fn remaining_split(&self) -> (i128, i128) {
+        self.split_payout(self.remaining_coverage())
+    }
+}
+
+/// Fixed-point precision for share values (1 share = 10^7 base units of value)
+pub const SHARE_PRECISION: i128 = 10_000_000;
+
+/// Shares the first deposit into an empty pool locks with no owner. They
+/// keep a donation to a near-empty pool (an overpaid premium) from inflating
+/// one share past what later deposits round down to.
+pub const DEAD_SHARES: i128 = 1_000;
+
+/// Upper bound on policies visited by a single keeper batch call
+pub const MAX_KEEPER_BATCH: u32 = 50;
+
+/// Queued withdrawals visited each time an expiry releases coverage
+pub const MAX_WITHDRAWALS_PER_EXPIRY: u32 = 5;
+
+/// Outcome of a keeper batch (`check_triggers_for_feed` / `sweep_expired`)
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeeperReport {
+    /// Policies visited by this call
+    pub processed: u32,
+    /// Payouts, observations that completed a claim, and expiries executed
+    pub executed: u32,
+    /// Policies skipped because their oracle read or payout failed
+    pub failed: u32,
+    /// Cursor to pass to the next call
+    pub next_cursor: u64,
+    /// `true` once the cursor has reached the end of the set
+    pub done: bool,
+    /// Keeper reward paid for this call
+    pub reward: i128,
+}
+
+/// Aggregate statistics and accounting for the shared risk pool.
+///
+/// The reinsurance tranche uses the same layout: its providers hold shares of
+/// its own `total_liquidity`, and its policy counters track ceded policies.
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct RiskPool {
     /// All tokens currently held by the contract
     /// (LP deposits + premiums − payouts − LP withdrawals)
     pub total_liquidity: i128,
+    /// Outstanding LP shares; each share is a pro-rata claim on `total_liquidity`
+    pub total_shares: i128,
     /// Sum of coverage_amounts for all Active policies (cannot be withdrawn)
     pub reserved_liquidity: i128,
     /// Cumulative premium income received
@@ -95,17 +252,22 @@ pub struct RiskPool {
     pub total_policies: u64,
     /// Number of currently Active policies
     pub active_policies: u64,
+    /// Liquidity left in the pool after every share was redeemed; owned by
+    /// no LP and withdrawable by the admin
+    pub surplus: i128,
 }
 
 impl RiskPool {
     pub fn new() -> Self {
         Self {
             total_liquidity: 0,
+            total_shares: 0,
             reserved_liquidity: 0,
             total_premiums_collected: 0,
             total_payouts: 0,
             total_policies: 0,
             active_policies: 0,
+            surplus: 0,
         }
     }
 
@@ -113,6 +275,85 @@ impl RiskPool {
     pub fn available_liquidity(&self) -> i128 {
         self.total_liquidity.saturating_sub(self.reserved_liquidity)
     }
+
+    /// Shares minted to the depositor of `amount` at the current share value.
+    ///
+    /// A deposit into a pool with no shares mints 1:1, less `DEAD_SHARES`;
+    /// call `sweep_unowned` first so it does not claim liquidity left behind.
+    /// Shares that are worth nothing mint none.
+    pub fn shares_for_deposit(&self, amount: i128) -> i128 {
+        if self.total_shares == 0 {
+            amount - DEAD_SHARES
+        } else if self.total_liquidity <= 0 {
+            0
+        } else {
+            amount * self.total_shares / self.total_liquidity
+        }
+    }
+
+    /// Book a deposit of `amount` and return the shares minted to the
+    /// depositor. Nothing is booked if that is not a positive number.
+    pub fn deposit(&mut self, amount: i128) -> i128 {
+        let shares = self.shares_for_deposit(amount);
+        if shares > 0 {
+            if self.total_shares == 0 {
+                self.total_shares = DEAD_SHARES;
+            }
+            self.total_liquidity += amount;
+            self.total_shares += shares;
+        }
+        shares
+    }
+
+    /// `true` when outstanding shares are backed by no liquidity, so a
+    /// deposit would only absorb the loss.
+    pub fn is_wiped_out(&self) -> bool {
+        self.total_shares > 0 && self.total_liquidity <= 0
+    }
+
+    /// Move unreserved liquidity owned by no shares into `surplus`.
+    pub fn sweep_unowned(&mut self) {
+        if self.total_shares == 0 {
+            let unowned = self.available_liquidity().max(0);
+            self.total_liquidity -= unowned;
+            self.surplus += unowned;
+        }
+    }
+
+    /// Tokens redeemable for `shares` at the current share value (rounded down).
+    pub fn value_of_shares(&self, shares: i128) -> i128 {
+        if self.total_shares == 0 {
+            0
+        } else {
+            shares * self.total_liquidity.max(0) / self.total_shares
+        }
+    }
+
+    /// Value of one share scaled by `SHARE_PRECISION`.
+    pub fn share_value(&self) -> i128 {
+        if self.total_shares == 0 {
+            SHARE_PRECISION
+        } else {
+            self.total_liquidity.max(0) * SHARE_PRECISION / self.total_shares
+        }
+    }
+}
+
+/// A queued LP withdrawal. The shares are locked when the request is made and
+/// redeemed at the share value in force when the request is fulfilled.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WithdrawalRequest {
+    /// Position in the FIFO queue (auto-incremented)
+    pub id: u64,
+    /// LP that will receive the proceeds
+    pub provider: Address,
+    /// Pool shares locked for redemption
+    pub shares: i128,
+    /// Ledger timestamp of the request
+    pub requested_at: u64,
+    /// Earliest time the request can be fulfilled
+    pub unlock_at: u64,
 }
 
 /// Oracle configuration stored in contract instance storage
@@ -126,3 +367,98 @@ pub struct OracleConfig {
     /// Minimum number of agreeing oracle sources required
     pub min_sources: u32,
 }
+
+/// Parameters of the premium pricing model (all rates in basis points)
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PricingConfig {
+    /// Annualised base rate charged on coverage
+    pub base_rate_bps: u32,
+    /// Risk multiplier for `PolicyType::Weather` (10_000 = 1×)
+    pub weather_bps: u32,
+    /// Risk multiplier for `PolicyType::FlightDelay`
+    pub flight_delay_bps: u32,
+    /// Risk multiplier for `PolicyType::NaturalDisaster`
+    pub natural_disaster_bps: u32,
+    /// Risk multiplier for `PolicyType::Crop`
+    pub crop_bps: u32,
+    /// Risk multiplier for `PolicyType::Custom`
+    pub custom_bps: u32,
+    /// Extra loading when the oracle value sits right at the threshold
+    pub proximity_loading_bps: u32,
+    /// Relative distance from the threshold beyond which no proximity loading applies
+    pub proximity_range_bps: u32,
+    /// Extra loading at 100% pool utilisation, scaled linearly below that
+    pub utilisation_loading_bps: u32,
+    /// Minimum premium as a fraction of coverage
+    pub min_premium_bps: u32,
+}
+
+impl Default for PricingConfig {
+    fn default() -> Self {
+        Self {
+            base_rate_bps: 1_000,
+            weather_bps: 10_000,
+            flight_delay_bps: 15_000,
+            natural_disaster_bps: 20_000,
+            crop_bps: 12_000,
+            custom_bps: 25_000,
+            proximity_loading_bps: 20_000,
+            proximity_range_bps: 5_000,
+            utilisation_loading_bps: 10_000,
+            min_premium_bps: 100,
+        }
+    }
+}
+
+/// Cancellation refund terms set by the admin
+///
+/// A cancelled policy refunds the unearned part of its premium (pro-rata to
+/// the time left) minus `cancellation_fee_bps` of that amount and `flat_fee`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RefundConfig {
+    /// Share of the unearned premium kept by the pool (10_000 = no refund)
+    pub cancellation_fee_bps: u32,
+    /// Fixed amount deducted from every refund
+    pub flat_fee: i128,
+}
+
+impl Default for RefundConfig {
+    /// Premiums are fully earned until the admin opts into refunds
+    fn default() -> Self {
+        Self {
+            cancellation_fee_bps: 10_000,
+            flat_fee: 0,
+        }
+    }
+}
+
+/// Excess-of-loss reinsurance terms set by the admin
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReinsuranceConfig {
+    /// Per-policy coverage kept by the primary pool; anything above is ceded
+    pub retention: i128,
+    /// Share of a reinsured policy's premium paid to the reinsurance tranche
+    pub premium_share_bps: u32,
+}
+
+/// Concentration limits enforced at policy creation
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExposureLimits {
+    /// Max outstanding coverage on any one oracle feed, in bps of total capital
+    pub max_feed_exposure_bps: u32,
+    /// Max outstanding coverage per `PolicyType`, in bps of total capital
+    pub max_type_exposure_bps: u32,
+}
+
+impl Default for ExposureLimits {
+    fn default() -> Self {
+        Self {
+            max_feed_exposure_bps: 10_000,
+            max_type_exposure_bps: 10_000,
+        }
+    }
+}
@@ -12,15 +12,24 @@
 
 use parametric_insurance::{
     Error as InsuranceError,
+    InsuranceExposureLimits as ExposureLimits,
     InsurancePolicyType as PolicyType,
+    InsuranceRefundConfig as RefundConfig,
+    InsuranceReinsuranceConfig as ReinsuranceConfig,
     InsuranceTriggerCondition as TriggerCondition,
     ParametricInsuranceContract,
     ParametricInsuranceContractClient,
+    CompositeTrigger,
+    FeedCondition,
+    PayoutSchedule,
+    PayoutTier,
     Status as PolicyStatus,
+    TriggerExpression,
+    DEAD_SHARES,
 };
 use soroban_sdk::{
     contract, contractimpl,
-    testutils::{Address as _, Ledger, LedgerInfo},
+    testutils::{Address as _, IssuerFlags, Ledger, LedgerInfo},
     token, Address, Env, Symbol, Vec,
 };
 
@@ -105,8 +114,7 @@ fn setup() -> Ctx {
     sources.push_back(oracle.clone());
 
     ParametricInsuranceContractClient::new(&env, &contract)
-        .init(&admin, &pool_token, &sources, &3600u64, &1u32)
-        .unwrap();
+        .init(&admin, &pool_token, &sources, &3600u64, &1u32);
 
     Ctx { env, contract, pool_token, admin, lp, alice, oracle }
 }
@@ -150,12 +158,12 @@ fn pause_blocks_deposits() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.pause(&ctx.admin).unwrap();
+    c.pause(&ctx.admin);
     assert!(c.try_deposit_liquidity(&ctx.lp, &1000i128).is_err());
 
-    c.unpause(&ctx.admin).unwrap();
-    c.deposit_liquidity(&ctx.lp, &1000i128).unwrap();
-    assert_eq!(c.get_pool_stats().total_liquidity, 1000);
+    c.unpause(&ctx.admin);
+    c.deposit_liquidity(&ctx.lp, &2000i128);
+    assert_eq!(c.get_pool_stats().total_liquidity, 2000);
 }
 
 #[test]
@@ -174,14 +182,49 @@ fn deposit_and_full_withdrawal() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.deposit_liquidity(&ctx.lp, &200_000i128).unwrap();
-    assert_eq!(c.get_liquidity_position(&ctx.lp), 200_000);
+    c.deposit_liquidity(&ctx.lp, &200_000i128);
+    assert_eq!(c.get_liquidity_position(&ctx.lp), 200_000 - DEAD_SHARES);
     assert_eq!(balance(&ctx.env, &ctx.pool_token, &ctx.contract), 200_000);
 
-    c.withdraw_liquidity(&ctx.lp, &200_000i128).unwrap();
+    // The dead shares' stake stays behind
+    c.withdraw_liquidity(&ctx.lp, &(200_000 - DEAD_SHARES));
     assert_eq!(c.get_liquidity_position(&ctx.lp), 0);
-    assert_eq!(balance(&ctx.env, &ctx.pool_token, &ctx.contract), 0);
-    assert_eq!(balance(&ctx.env, &ctx.pool_token, &ctx.lp), 1_000_000);
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &ctx.contract), DEAD_SHARES);
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &ctx.lp), 1_000_000 - DEAD_SHARES);
+}
+
+#[test]
+fn first_depositor_cannot_inflate_share_value() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+
+    // Dust below the dead shares mints nothing
+    assert!(matches!(
+        c.try_deposit_liquidity(&ctx.alice, &DEAD_SHARES),
+        Err(Ok(InsuranceError::InvalidAmount))
+    ));
+
+    // Attacker takes the first share, then pumps the pool with a heavily
+    // overpaid premium on a tiny policy
+    assert_eq!(c.deposit_liquidity(&ctx.alice, &(DEAD_SHARES + 1)), 1);
+    c.create_policy(
+        &ctx.alice,
+        &PolicyType::Weather,
+        &Symbol::new(&ctx.env, "RAINFALL"),
+        &200i128,
+        &TriggerCondition::GreaterOrEqual,
+        &100i128,
+        &40_000i128,
+        &86_400u64,
+    );
+
+    // The donation went almost entirely to the dead shares, so the victim
+    // loses at most one share's worth to rounding, and the attacker's share
+    // is worth a fraction of what was paid in
+    c.deposit_liquidity(&ctx.lp, &10_000i128);
+    let victim = c.get_liquidity_position(&ctx.lp);
+    assert!(victim > 10_000 - 50, "victim kept {victim}");
+    assert!(c.get_liquidity_position(&ctx.alice) < 50);
 }
 
 #[test]
@@ -189,7 +232,7 @@ fn partial_withdrawal_respects_reservation() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.deposit_liquidity(&ctx.lp, &10_000i128).unwrap();
+    c.deposit_liquidity(&ctx.lp, &10_000i128);
 
     // Create a policy that reserves 8_000
     let feed = Symbol::new(&ctx.env, "WIND");
@@ -202,13 +245,13 @@ fn partial_withdrawal_respects_reservation() {
         &8_000i128,
         &800i128,
         &86_400u64,
-    )
-    .unwrap();
+    );
 
     // Pool: total = 10_800, reserved = 8_000, available = 2_800
-    // LP can only withdraw up to 2_800 despite having 10_000 recorded
+    // LP holds 10_000 shares worth 10_800 but can only redeem up to 2_800 of value
     assert!(c.try_withdraw_liquidity(&ctx.lp, &5_000i128).is_err());
-    c.withdraw_liquidity(&ctx.lp, &2_800i128).unwrap();
+    assert!(c.try_withdraw_liquidity(&ctx.lp, &2_600i128).is_err());
+    assert_eq!(c.withdraw_liquidity(&ctx.lp, &2_592i128), 2_799);
 }
 
 #[test]
@@ -227,7 +270,7 @@ fn create_policy_records_correctly() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.deposit_liquidity(&ctx.lp, &50_000i128).unwrap();
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
 
     let feed = Symbol::new(&ctx.env, "RAINFALL");
     let id = c.create_policy(
@@ -239,8 +282,7 @@ fn create_policy_records_correctly() {
         &10_000i128,
         &1_000i128,
         &86_400u64,
-    )
-    .unwrap();
+    );
 
     let policy = c.get_policy(&id).unwrap();
     assert_eq!(policy.id, 1);
@@ -279,7 +321,7 @@ fn cancel_policy_releases_reservation() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.deposit_liquidity(&ctx.lp, &20_000i128).unwrap();
+    c.deposit_liquidity(&ctx.lp, &20_000i128);
 
     let feed = Symbol::new(&ctx.env, "QUAKE");
     let id = c.create_policy(
@@ -291,11 +333,10 @@ fn cancel_policy_releases_reservation() {
         &15_000i128,
         &500i128,
         &86_400u64,
-    )
-    .unwrap();
+    );
 
     assert_eq!(c.get_pool_stats().reserved_liquidity, 15_000);
-    c.cancel_policy(&ctx.alice, &id).unwrap();
+    c.cancel_policy(&ctx.alice, &id);
     assert_eq!(c.get_pool_stats().reserved_liquidity, 0);
     assert_eq!(c.get_policy(&id).unwrap().status, PolicyStatus::Cancelled);
 }
@@ -305,7 +346,7 @@ fn cancel_already_cancelled_policy_fails() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.deposit_liquidity(&ctx.lp, &10_000i128).unwrap();
+    c.deposit_liquidity(&ctx.lp, &10_000i128);
 
     let feed = Symbol::new(&ctx.env, "HAIL");
     let id = c.create_policy(
@@ -317,10 +358,9 @@ fn cancel_already_cancelled_policy_fails() {
         &5_000i128,
         &500i128,
         &86_400u64,
-    )
-    .unwrap();
+    );
 
-    c.cancel_policy(&ctx.alice, &id).unwrap();
+    c.cancel_policy(&ctx.alice, &id);
     assert!(c.try_cancel_policy(&ctx.alice, &id).is_err());
 }
 
@@ -333,7 +373,7 @@ fn trigger_greater_than_pays_out() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.deposit_liquidity(&ctx.lp, &50_000i128).unwrap();
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
 
     let feed = Symbol::new(&ctx.env, "RAINFALL");
     let id = c.create_policy(
@@ -345,15 +385,14 @@ fn trigger_greater_than_pays_out() {
         &20_000i128,
         &2_000i128,
         &86_400u64,
-    )
-    .unwrap();
+    );
 
     let before = balance(&ctx.env, &ctx.pool_token, &ctx.alice);
 
     MockOracleClient::new(&ctx.env, &ctx.oracle)
         .set_price(&feed, &250i128, &ctx.env.ledger().timestamp());
 
-    assert!(c.check_trigger(&id).unwrap());
+    assert!(c.check_trigger(&id));
 
     let after = balance(&ctx.env, &ctx.pool_token, &ctx.alice);
     assert_eq!(after - before, 20_000);
@@ -370,7 +409,7 @@ fn trigger_less_than_pays_out() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.deposit_liquidity(&ctx.lp, &50_000i128).unwrap();
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
 
     let feed = Symbol::new(&ctx.env, "TEMP");
     let id = c.create_policy(
@@ -382,13 +421,12 @@ fn trigger_less_than_pays_out() {
         &15_000i128,
         &1_500i128,
         &86_400u64,
-    )
-    .unwrap();
+    );
 
     MockOracleClient::new(&ctx.env, &ctx.oracle)
         .set_price(&feed, &(-200i128), &ctx.env.ledger().timestamp());
 
-    assert!(c.check_trigger(&id).unwrap());
+    assert!(c.check_trigger(&id));
     assert_eq!(c.get_policy(&id).unwrap().status, PolicyStatus::Claimed);
 }
 
@@ -397,7 +435,7 @@ fn trigger_not_met_returns_false_and_keeps_active() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.deposit_liquidity(&ctx.lp, &50_000i128).unwrap();
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
 
     let feed = Symbol::new(&ctx.env, "WIND");
     let id = c.create_policy(
@@ -409,13 +447,12 @@ fn trigger_not_met_returns_false_and_keeps_active() {
         &10_000i128,
         &1_000i128,
         &86_400u64,
-    )
-    .unwrap();
+    );
 
     MockOracleClient::new(&ctx.env, &ctx.oracle)
         .set_price(&feed, &100i128, &ctx.env.ledger().timestamp());
 
-    assert!(!c.check_trigger(&id).unwrap());
+    assert!(!c.check_trigger(&id));
     assert_eq!(c.get_policy(&id).unwrap().status, PolicyStatus::Active);
 }
 
@@ -424,9 +461,11 @@ fn trigger_on_expired_policy_returns_error() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.deposit_liquidity(&ctx.lp, &50_000i128).unwrap();
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
 
     let feed = Symbol::new(&ctx.env, "DROUGHT");
+    MockOracleClient::new(&ctx.env, &ctx.oracle)
+        .set_price(&feed, &20i128, &ctx.env.ledger().timestamp());
     let id = c.create_policy(
         &ctx.alice,
         &PolicyType::Crop,
@@ -436,16 +475,18 @@ fn trigger_on_expired_policy_returns_error() {
         &8_000i128,
         &800i128,
         &3_600u64,   // 1-hour policy
-    )
-    .unwrap();
+    );
 
     advance_time(&ctx.env, 7_200);   // advance 2 hours past expiry
 
     MockOracleClient::new(&ctx.env, &ctx.oracle)
         .set_price(&feed, &5i128, &ctx.env.ledger().timestamp());
 
-    // check_trigger should auto-expire and return an error
+    // check_trigger refuses the lapsed policy; the error rolls back its
+    // auto-expiry, so the policy is expired explicitly
     assert!(c.try_check_trigger(&id).is_err());
+    assert_eq!(c.get_policy(&id).unwrap().status, PolicyStatus::Active);
+    c.expire_policy(&id);
     assert_eq!(c.get_policy(&id).unwrap().status, PolicyStatus::Expired);
 }
 
@@ -458,7 +499,7 @@ fn expire_policy_before_end_time_fails() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.deposit_liquidity(&ctx.lp, &50_000i128).unwrap();
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
 
     let feed = Symbol::new(&ctx.env, "SNOW");
     let id = c.create_policy(
@@ -470,8 +511,7 @@ fn expire_policy_before_end_time_fails() {
         &5_000i128,
         &500i128,
         &86_400u64,
-    )
-    .unwrap();
+    );
 
     // Policy hasn't expired yet — expire_policy should fail
     assert!(c.try_expire_policy(&id).is_err());
@@ -483,7 +523,7 @@ fn expire_policy_after_end_time_succeeds() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.deposit_liquidity(&ctx.lp, &50_000i128).unwrap();
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
 
     let feed = Symbol::new(&ctx.env, "ICE");
     let id = c.create_policy(
@@ -495,12 +535,11 @@ fn expire_policy_after_end_time_succeeds() {
         &7_000i128,
         &700i128,
         &3_600u64,
-    )
-    .unwrap();
+    );
 
     advance_time(&ctx.env, 7_200);
 
-    c.expire_policy(&id).unwrap();
+    c.expire_policy(&id);
     assert_eq!(c.get_policy(&id).unwrap().status, PolicyStatus::Expired);
     assert_eq!(c.get_pool_stats().reserved_liquidity, 0);
     assert_eq!(c.get_pool_stats().active_policies, 0);
@@ -515,7 +554,7 @@ fn multiple_policies_track_independently() {
     let ctx = setup();
     let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
 
-    c.deposit_liquidity(&ctx.lp, &100_000i128).unwrap();
+    c.deposit_liquidity(&ctx.lp, &100_000i128);
 
     let bob = Address::generate(&ctx.env);
     mint(&ctx.env, &ctx.pool_token, &bob, 10_000);
@@ -534,8 +573,7 @@ fn multiple_policies_track_independently() {
             &10_000i128,
             &500i128,
             &86_400u64,
-        )
-        .unwrap();
+        );
 
     let id2 = c
         .create_policy(
@@ -547,8 +585,7 @@ fn multiple_policies_track_independently() {
             &5_000i128,
             &250i128,
             &172_800u64,
-        )
-        .unwrap();
+        );
 
     let id3 = c
         .create_policy(
@@ -560,8 +597,7 @@ fn multiple_policies_track_independently() {
             &20_000i128,
             &1_000i128,
             &86_400u64,
-        )
-        .unwrap();
+        );
 
     let pool = c.get_pool_stats();
     assert_eq!(pool.active_policies, 3);
@@ -574,7 +610,7 @@ fn multiple_policies_track_independently() {
     // Trigger id1: rainfall = 250 (≥ 200 threshold)
     MockOracleClient::new(&ctx.env, &ctx.oracle)
         .set_price(&f1, &250i128, &ctx.env.ledger().timestamp());
-    assert!(c.check_trigger(&id1).unwrap());
+    assert!(c.check_trigger(&id1));
 
     // id2 and id3 should still be active
     assert_eq!(c.get_policy(&id2).unwrap().status, PolicyStatus::Active);
@@ -585,3 +621,1092 @@ fn multiple_policies_track_independently() {
     assert_eq!(pool.reserved_liquidity, 25_000);
     assert_eq!(pool.total_payouts, 10_000);
 }
+
+// =============================================================================
+// Graduated payout tests
+// =============================================================================
+
+fn tier(threshold: i128, payout_bps: u32) -> PayoutTier {
+    PayoutTier { threshold, payout_bps }
+}
+
+#[test]
+fn tiered_payout_pays_incrementally() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let oracle = MockOracleClient::new(&ctx.env, &ctx.oracle);
+
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
+
+    let feed = Symbol::new(&ctx.env, "RAIN_IDX");
+    let mut tiers = Vec::new(&ctx.env);
+    tiers.push_back(tier(100, 2_500));
+    tiers.push_back(tier(150, 5_000));
+    tiers.push_back(tier(200, 10_000));
+
+    let id = c.create_graduated_policy(
+        &ctx.alice,
+        &PolicyType::Crop,
+        &feed,
+        &100i128,
+        &TriggerCondition::GreaterOrEqual,
+        &PayoutSchedule::Tiered(tiers),
+        &10_000i128,
+        &1_000i128,
+        &86_400u64,
+    );
+    let before = balance(&ctx.env, &ctx.pool_token, &ctx.alice);
+    oracle.set_price(&feed, &50i128, &ctx.env.ledger().timestamp());
+    let full_renewal = c.quote_renewal(&id, &86_400u64);
+
+    // First tier: 25% paid, policy stays active for the rest
+    oracle.set_price(&feed, &120i128, &ctx.env.ledger().timestamp());
+    assert!(c.check_trigger(&id));
+    let policy = c.get_policy(&id).unwrap();
+    assert_eq!(policy.paid_out, 2_500);
+    assert_eq!(policy.status, PolicyStatus::Active);
+    assert_eq!(c.get_pool_stats().reserved_liquidity, 7_500);
+
+    // Renewal prices only the 7_500 of coverage left, not the original 10_000
+    oracle.set_price(&feed, &50i128, &ctx.env.ledger().timestamp());
+    let renewal = c.quote_renewal(&id, &86_400u64);
+    assert!(renewal * 4 <= full_renewal * 3, "{renewal} vs {full_renewal}");
+
+    // Second tier tops up to 50%
+    oracle.set_price(&feed, &160i128, &ctx.env.ledger().timestamp());
+    assert!(c.check_trigger(&id));
+    assert_eq!(c.get_policy(&id).unwrap().paid_out, 5_000);
+
+    // Falling back below the second tier owes nothing more
+    oracle.set_price(&feed, &130i128, &ctx.env.ledger().timestamp());
+    assert!(!c.check_trigger(&id));
+
+    // Final tier exhausts the coverage
+    oracle.set_price(&feed, &210i128, &ctx.env.ledger().timestamp());
+    assert!(c.check_trigger(&id));
+    assert_eq!(c.get_policy(&id).unwrap().status, PolicyStatus::Claimed);
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &ctx.alice) - before, 10_000);
+
+    let pool = c.get_pool_stats();
+    assert_eq!(pool.total_payouts, 10_000);
+    assert_eq!(pool.reserved_liquidity, 0);
+    assert_eq!(pool.active_policies, 0);
+}
+
+#[test]
+fn linear_payout_scales_and_cancel_releases_remainder() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let oracle = MockOracleClient::new(&ctx.env, &ctx.oracle);
+
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
+
+    // Drought cover: 0% at 50 mm of rain rising to 100% at 0 mm
+    let feed = Symbol::new(&ctx.env, "RAIN_MM");
+    oracle.set_price(&feed, &80i128, &ctx.env.ledger().timestamp());
+    let id = c.create_graduated_policy(
+        &ctx.alice,
+        &PolicyType::Crop,
+        &feed,
+        &50i128,
+        &TriggerCondition::LessThan,
+        &PayoutSchedule::Linear(0),
+        &10_000i128,
+        &1_000i128,
+        &86_400u64,
+    );
+
+    oracle.set_price(&feed, &40i128, &ctx.env.ledger().timestamp());
+    assert!(c.check_trigger(&id));
+    assert_eq!(c.get_policy(&id).unwrap().paid_out, 2_000);
+
+    oracle.set_price(&feed, &25i128, &ctx.env.ledger().timestamp());
+    assert!(c.check_trigger(&id));
+    assert_eq!(c.get_policy(&id).unwrap().paid_out, 5_000);
+    assert_eq!(c.get_pool_stats().reserved_liquidity, 5_000);
+
+    // Cancelling releases only the unpaid coverage
+    c.cancel_policy(&ctx.alice, &id);
+    let pool = c.get_pool_stats();
+    assert_eq!(pool.reserved_liquidity, 0);
+    assert_eq!(pool.total_payouts, 5_000);
+    assert_eq!(pool.active_policies, 0);
+}
+
+#[test]
+fn inconsistent_payout_schedules_are_rejected() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
+    let feed = Symbol::new(&ctx.env, "WIND_IDX");
+
+    let create = |condition: TriggerCondition, schedule: PayoutSchedule| {
+        c.try_create_graduated_policy(
+            &ctx.alice,
+            &PolicyType::Weather,
+            &feed,
+            &100i128,
+            &condition,
+            &schedule,
+            &10_000i128,
+            &1_000i128,
+            &86_400u64,
+        )
+    };
+    let invalid = |r: Result<_, _>| matches!(r, Err(Ok(InsuranceError::InvalidPayoutSchedule)));
+
+    // First tier must sit at the trigger threshold
+    let mut tiers = Vec::new(&ctx.env);
+    tiers.push_back(tier(120, 5_000));
+    assert!(invalid(create(TriggerCondition::GreaterThan, PayoutSchedule::Tiered(tiers))));
+
+    // Payout percentages must increase with severity
+    let mut tiers = Vec::new(&ctx.env);
+    tiers.push_back(tier(100, 5_000));
+    tiers.push_back(tier(150, 5_000));
+    assert!(invalid(create(TriggerCondition::GreaterThan, PayoutSchedule::Tiered(tiers))));
+
+    // Thresholds must move in the trigger's direction
+    let mut tiers = Vec::new(&ctx.env);
+    tiers.push_back(tier(100, 5_000));
+    tiers.push_back(tier(80, 10_000));
+    assert!(invalid(create(TriggerCondition::GreaterThan, PayoutSchedule::Tiered(tiers))));
+
+    // Exhaustion must lie beyond the attachment point
+    assert!(invalid(create(TriggerCondition::GreaterThan, PayoutSchedule::Linear(50))));
+
+    // Equality triggers only support binary payouts
+    assert!(invalid(create(TriggerCondition::EqualTo, PayoutSchedule::Linear(150))));
+
+    assert_eq!(c.get_policy_count(), 0);
+}
+
+// =============================================================================
+// Composite trigger tests
+// =============================================================================
+
+fn cond(env: &Env, feed: &str, condition: TriggerCondition, threshold: i128) -> FeedCondition {
+    FeedCondition { feed: Symbol::new(env, feed), condition, threshold }
+}
+
+#[test]
+fn composite_all_of_requires_every_feed() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let oracle = MockOracleClient::new(&ctx.env, &ctx.oracle);
+    let rain = Symbol::new(&ctx.env, "RAIN");
+    let temp = Symbol::new(&ctx.env, "TEMP");
+
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
+    oracle.set_price(&rain, &80i128, &ctx.env.ledger().timestamp());
+    oracle.set_price(&temp, &250i128, &ctx.env.ledger().timestamp());
+
+    // Heat-drought cover: rainfall < 50 AND temperature > 300
+    let mut conds = Vec::new(&ctx.env);
+    conds.push_back(cond(&ctx.env, "RAIN", TriggerCondition::LessThan, 50));
+    conds.push_back(cond(&ctx.env, "TEMP", TriggerCondition::GreaterThan, 300));
+    let trigger = CompositeTrigger {
+        expression: TriggerExpression::AllOf(conds),
+        required_observations: 1,
+    };
+    let id = c.create_composite_policy(
+        &ctx.alice,
+        &PolicyType::Crop,
+        &trigger,
+        &10_000i128,
+        &1_000i128,
+        &86_400u64,
+    );
+    assert_eq!(c.get_policy(&id).unwrap().oracle_feed, rain);
+
+    // Drought alone is not enough
+    oracle.set_price(&rain, &40i128, &ctx.env.ledger().timestamp());
+    assert!(!c.check_trigger(&id));
+
+    // Drought and heat together pay the full coverage
+    oracle.set_price(&temp, &320i128, &ctx.env.ledger().timestamp());
+    let before = balance(&ctx.env, &ctx.pool_token, &ctx.alice);
+    assert!(c.check_trigger(&id));
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &ctx.alice) - before, 10_000);
+    assert_eq!(c.get_policy(&id).unwrap().status, PolicyStatus::Claimed);
+}
+
+#[test]
+fn composite_any_of_needs_consecutive_fresh_observations() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let oracle = MockOracleClient::new(&ctx.env, &ctx.oracle);
+    let stations = ["WIND_A", "WIND_B", "WIND_C"];
+    let set_all = |values: [i128; 3]| {
+        for (name, value) in stations.iter().zip(values) {
+            oracle.set_price(&Symbol::new(&ctx.env, name), &value, &ctx.env.ledger().timestamp());
+        }
+    };
+
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
+    advance_time(&ctx.env, 1_000);
+    set_all([50, 50, 50]);
+
+    // Any of three stations above 100, for three consecutive readings
+    let mut conds = Vec::new(&ctx.env);
+    for name in stations {
+        conds.push_back(cond(&ctx.env, name, TriggerCondition::GreaterThan, 100));
+    }
+    let trigger = CompositeTrigger {
+        expression: TriggerExpression::AnyOf(conds),
+        required_observations: 3,
+    };
+    let id = c.create_composite_policy(
+        &ctx.alice,
+        &PolicyType::Weather,
+        &trigger,
+        &10_000i128,
+        &1_000i128,
+        &86_400u64,
+    );
+
+    advance_time(&ctx.env, 60);
+    set_all([50, 120, 50]);
+    assert!(!c.check_trigger(&id));
+    assert_eq!(c.get_policy(&id).unwrap().consecutive_hits, 1);
+
+    // Re-checking the same oracle data does not count again
+    assert!(!c.check_trigger(&id));
+    assert_eq!(c.get_policy(&id).unwrap().consecutive_hits, 1);
+
+    advance_time(&ctx.env, 60);
+    set_all([50, 50, 110]);
+    assert!(!c.check_trigger(&id));
+    assert_eq!(c.get_policy(&id).unwrap().consecutive_hits, 2);
+
+    // A calm reading resets the streak
+    advance_time(&ctx.env, 60);
+    set_all([50, 50, 50]);
+    assert!(!c.check_trigger(&id));
+    assert_eq!(c.get_policy(&id).unwrap().consecutive_hits, 0);
+
+    for _ in 0..2 {
+        advance_time(&ctx.env, 60);
+        set_all([130, 50, 50]);
+        assert!(!c.check_trigger(&id));
+    }
+    advance_time(&ctx.env, 60);
+    set_all([130, 50, 50]);
+    assert!(c.check_trigger(&id));
+    assert_eq!(c.get_policy(&id).unwrap().status, PolicyStatus::Claimed);
+    assert_eq!(c.get_pool_stats().total_payouts, 10_000);
+}
+
+#[test]
+fn composite_any_of_pays_on_the_feeds_that_report() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let oracle = MockOracleClient::new(&ctx.env, &ctx.oracle);
+    let wind_a = Symbol::new(&ctx.env, "WIND_A");
+    let wind_b = Symbol::new(&ctx.env, "WIND_B");
+
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
+    advance_time(&ctx.env, 1_000);
+    oracle.set_price(&wind_a, &50i128, &ctx.env.ledger().timestamp());
+    oracle.set_price(&wind_b, &50i128, &ctx.env.ledger().timestamp());
+
+    let mut conds = Vec::new(&ctx.env);
+    conds.push_back(cond(&ctx.env, "WIND_A", TriggerCondition::GreaterThan, 100));
+    conds.push_back(cond(&ctx.env, "WIND_B", TriggerCondition::GreaterThan, 100));
+    let id = c.create_composite_policy(
+        &ctx.alice,
+        &PolicyType::Weather,
+        &CompositeTrigger {
+            expression: TriggerExpression::AnyOf(conds),
+            required_observations: 1,
+        },
+        &10_000i128,
+        &1_000i128,
+        &86_400u64,
+    );
+
+    // With neither station reporting there is nothing to evaluate
+    advance_time(&ctx.env, 3_601);
+    assert_eq!(c.try_check_trigger(&id), Err(Ok(InsuranceError::OracleFailure)));
+
+    // WIND_A stays stale, but WIND_B alone can meet an AnyOf
+    oracle.set_price(&wind_b, &120i128, &ctx.env.ledger().timestamp());
+    assert!(c.check_trigger(&id));
+    assert_eq!(c.get_policy(&id).unwrap().status, PolicyStatus::Claimed);
+}
+
+#[test]
+fn composite_trigger_validation_and_pricing() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let oracle = MockOracleClient::new(&ctx.env, &ctx.oracle);
+
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
+    oracle.set_price(&Symbol::new(&ctx.env, "RAIN"), &80i128, &ctx.env.ledger().timestamp());
+    oracle.set_price(&Symbol::new(&ctx.env, "TEMP"), &250i128, &ctx.env.ledger().timestamp());
+
+    let mut conds = Vec::new(&ctx.env);
+    conds.push_back(cond(&ctx.env, "RAIN", TriggerCondition::LessThan, 75));
+    conds.push_back(cond(&ctx.env, "TEMP", TriggerCondition::GreaterThan, 260));
+
+    let create = |expression: TriggerExpression, required_observations: u32| {
+        c.try_create_composite_policy(
+            &ctx.alice,
+            &PolicyType::Crop,
+            &CompositeTrigger { expression, required_observations },
+            &10_000i128,
+            &5_000i128,
+            &86_400u64,
+        )
+    };
+
+    let invalid = |r: Result<_, _>| matches!(r, Err(Ok(InsuranceError::InvalidTriggerExpression)));
+    assert!(invalid(create(TriggerExpression::AllOf(conds.clone()), 0)));
+    assert!(invalid(create(TriggerExpression::AnyOf(Vec::new(&ctx.env)), 1)));
+
+    // An OR over conditions is at least as likely as an AND, so costs more
+    let quote = |expression: TriggerExpression| {
+        c.quote_composite_premium(&PolicyType::Crop, &expression, &10_000i128, &31_536_000u64)
+    };
+    assert!(quote(TriggerExpression::AnyOf(conds.clone())) > quote(TriggerExpression::AllOf(conds.clone())));
+
+    // Expressions that already hold are not insurable
+    let mut met = conds.clone();
+    met.push_back(cond(&ctx.env, "RAIN", TriggerCondition::GreaterThan, 10));
+    assert!(matches!(
+        create(TriggerExpression::AnyOf(met), 1),
+        Err(Ok(InsuranceError::TriggerAlreadyMet))
+    ));
+
+    assert_eq!(c.get_policy_count(), 0);
+}
+
+// =============================================================================
+// Reinsurance and exposure tests
+// =============================================================================
+
+#[test]
+fn reinsurance_tranche_takes_losses_above_retention() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let reinsurer = Address::generate(&ctx.env);
+    mint(&ctx.env, &ctx.pool_token, &reinsurer, 100_000);
+
+    c.deposit_liquidity(&ctx.lp, &20_000i128);
+    assert_eq!(c.deposit_reinsurance(&reinsurer, &30_000i128), 29_000);
+    c.set_reinsurance_config(
+        &ctx.admin,
+        &ReinsuranceConfig { retention: 5_000, premium_share_bps: 2_000 },
+    );
+
+    let feed = Symbol::new(&ctx.env, "HURR_MIA");
+    let id = c.create_policy(
+        &ctx.alice,
+        &PolicyType::NaturalDisaster,
+        &feed,
+        &100i128,
+        &TriggerCondition::GreaterOrEqual,
+        &15_000i128,
+        &1_500i128,
+        &86_400u64,
+    );
+    assert_eq!(c.get_policy(&id).unwrap().ceded_coverage, 10_000);
+
+    // Primary keeps 80% of the premium and the first 5_000 of risk
+    let pool = c.get_pool_stats();
+    assert_eq!(pool.total_liquidity, 21_200);
+    assert_eq!(pool.reserved_liquidity, 5_000);
+    let tranche = c.get_reinsurance_pool();
+    assert_eq!(tranche.total_liquidity, 30_300);
+    assert_eq!(tranche.reserved_liquidity, 10_000);
+
+    // Reserved tranche capital cannot be withdrawn
+    assert!(c.try_withdraw_reinsurance(&reinsurer, &25_000i128).is_err());
+
+    MockOracleClient::new(&ctx.env, &ctx.oracle)
+        .set_price(&feed, &120i128, &ctx.env.ledger().timestamp());
+    assert!(c.check_trigger(&id));
+
+    // Loss is split at the retention
+    let pool = c.get_pool_stats();
+    assert_eq!(pool.total_liquidity, 16_200);
+    assert_eq!(pool.total_payouts, 5_000);
+    assert_eq!(pool.reserved_liquidity, 0);
+    let tranche = c.get_reinsurance_pool();
+    assert_eq!(tranche.total_liquidity, 20_300);
+    assert_eq!(tranche.total_payouts, 10_000);
+    assert_eq!(tranche.reserved_liquidity, 0);
+    // 29_000 of the tranche's 30_000 shares
+    assert_eq!(c.get_reinsurance_position(&reinsurer), 19_623);
+
+    assert_eq!(c.withdraw_reinsurance(&reinsurer, &29_000i128), 19_623);
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &ctx.contract), 16_200 + 677);
+}
+
+#[test]
+fn partial_claims_fill_retention_before_tranche() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let oracle = MockOracleClient::new(&ctx.env, &ctx.oracle);
+    let reinsurer = Address::generate(&ctx.env);
+    mint(&ctx.env, &ctx.pool_token, &reinsurer, 100_000);
+
+    c.deposit_liquidity(&ctx.lp, &20_000i128);
+    c.deposit_reinsurance(&reinsurer, &20_000i128);
+    c.set_reinsurance_config(
+        &ctx.admin,
+        &ReinsuranceConfig { retention: 6_000, premium_share_bps: 0 },
+    );
+
+    let feed = Symbol::new(&ctx.env, "RAIN_IDX");
+    let mut tiers = Vec::new(&ctx.env);
+    tiers.push_back(tier(100, 5_000));
+    tiers.push_back(tier(150, 7_000));
+    tiers.push_back(tier(200, 10_000));
+    let id = c.create_graduated_policy(
+        &ctx.alice,
+        &PolicyType::Crop,
+        &feed,
+        &100i128,
+        &TriggerCondition::GreaterOrEqual,
+        &PayoutSchedule::Tiered(tiers),
+        &10_000i128,
+        &1_000i128,
+        &86_400u64,
+    );
+
+    // 5_000 falls entirely within the 6_000 retention
+    oracle.set_price(&feed, &120i128, &ctx.env.ledger().timestamp());
+    assert!(c.check_trigger(&id));
+    assert_eq!(c.get_pool_stats().total_payouts, 5_000);
+    assert_eq!(c.get_reinsurance_pool().total_payouts, 0);
+
+    // The next 2_000 straddles it: 1_000 retained, 1_000 ceded
+    oracle.set_price(&feed, &160i128, &ctx.env.ledger().timestamp());
+    assert!(c.check_trigger(&id));
+    assert_eq!(c.get_pool_stats().total_payouts, 6_000);
+    assert_eq!(c.get_reinsurance_pool().total_payouts, 1_000);
+
+    // Cancelling releases the unpaid ceded layer only
+    c.cancel_policy(&ctx.alice, &id);
+    assert_eq!(c.get_pool_stats().reserved_liquidity, 0);
+    let tranche = c.get_reinsurance_pool();
+    assert_eq!(tranche.reserved_liquidity, 0);
+    assert_eq!(tranche.active_policies, 0);
+    assert_eq!(c.get_feed_exposure(&feed), 0);
+}
+
+#[test]
+fn exposure_caps_limit_concentration() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    mint(&ctx.env, &ctx.pool_token, &ctx.alice, 50_000);
+
+    c.deposit_liquidity(&ctx.lp, &100_000i128);
+
+    let invalid = ExposureLimits { max_feed_exposure_bps: 0, max_type_exposure_bps: 5_000 };
+    assert!(matches!(
+        c.try_set_exposure_limits(&ctx.admin, &invalid),
+        Err(Ok(InsuranceError::InvalidRiskLimits))
+    ));
+    c.set_exposure_limits(
+        &ctx.admin,
+        &ExposureLimits { max_feed_exposure_bps: 3_000, max_type_exposure_bps: 5_000 },
+    );
+
+    let rain = Symbol::new(&ctx.env, "RAIN_NYC");
+    let wind = Symbol::new(&ctx.env, "WIND_NYC");
+    let hail = Symbol::new(&ctx.env, "HAIL_NYC");
+    let create = |feed: &Symbol, coverage: i128| {
+        c.try_create_policy(
+            &ctx.alice,
+            &PolicyType::Weather,
+            feed,
+            &200i128,
+            &TriggerCondition::GreaterOrEqual,
+            &coverage,
+            &(coverage / 10),
+            &86_400u64,
+        )
+    };
+
+    // 30% of capital per feed
+    let first = create(&rain, 20_000).unwrap().unwrap();
+    assert!(matches!(create(&rain, 15_000), Err(Ok(InsuranceError::FeedExposureExceeded))));
+    assert_eq!(c.get_feed_exposure(&rain), 20_000);
+
+    // 50% of capital per policy type across feeds
+    create(&wind, 25_000).unwrap().unwrap();
+    assert!(matches!(create(&hail, 10_000), Err(Ok(InsuranceError::TypeExposureExceeded))));
+    assert_eq!(c.get_type_exposure(&PolicyType::Weather), 45_000);
+
+    // Ending a policy frees its exposure
+    c.cancel_policy(&ctx.alice, &first);
+    assert_eq!(c.get_feed_exposure(&rain), 0);
+    assert!(create(&hail, 10_000).is_ok());
+}
+
+// =============================================================================
+// Policy transfer tests
+// =============================================================================
+
+#[test]
+fn transferred_policy_pays_new_holder() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let bob = Address::generate(&ctx.env);
+
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
+
+    let feed = Symbol::new(&ctx.env, "RAINFALL");
+    let id = c.create_policy(
+        &ctx.alice,
+        &PolicyType::Weather,
+        &feed,
+        &200i128,
+        &TriggerCondition::GreaterThan,
+        &20_000i128,
+        &2_000i128,
+        &86_400u64,
+    );
+
+    assert!(matches!(
+        c.try_transfer_policy(&ctx.alice, &ctx.alice, &id),
+        Err(Ok(InsuranceError::InvalidRecipient))
+    ));
+    c.transfer_policy(&ctx.alice, &bob, &id);
+
+    assert_eq!(c.get_policy(&id).unwrap().policyholder, bob);
+    assert_eq!(c.get_policies_by_holder(&ctx.alice).len(), 0);
+    assert_eq!(c.get_policies_by_holder(&bob).len(), 1);
+
+    // The previous holder has no rights left over the policy
+    assert!(matches!(
+        c.try_cancel_policy(&ctx.alice, &id),
+        Err(Ok(InsuranceError::Unauthorized))
+    ));
+    assert!(matches!(
+        c.try_transfer_policy(&ctx.alice, &bob, &id),
+        Err(Ok(InsuranceError::Unauthorized))
+    ));
+
+    let alice_before = balance(&ctx.env, &ctx.pool_token, &ctx.alice);
+    MockOracleClient::new(&ctx.env, &ctx.oracle)
+        .set_price(&feed, &250i128, &ctx.env.ledger().timestamp());
+    assert!(c.check_trigger(&id));
+
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &bob), 20_000);
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &ctx.alice), alice_before);
+
+    // Settled policies can no longer change hands
+    assert!(matches!(
+        c.try_transfer_policy(&bob, &ctx.alice, &id),
+        Err(Ok(InsuranceError::PolicyNotActive))
+    ));
+}
+
+#[test]
+fn approved_spender_can_transfer_once() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let market = Address::generate(&ctx.env);
+    let bob = Address::generate(&ctx.env);
+    let carol = Address::generate(&ctx.env);
+
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
+
+    let id = c.create_policy(
+        &ctx.alice,
+        &PolicyType::Weather,
+        &Symbol::new(&ctx.env, "RAINFALL"),
+        &200i128,
+        &TriggerCondition::GreaterThan,
+        &10_000i128,
+        &1_000i128,
+        &86_400u64,
+    );
+
+    // Without an approval the spender is rejected
+    assert!(matches!(
+        c.try_transfer_policy_from(&market, &ctx.alice, &bob, &id),
+        Err(Ok(InsuranceError::Unauthorized))
+    ));
+
+    // Only the holder can approve
+    assert!(matches!(
+        c.try_approve_policy_transfer(&bob, &market, &id),
+        Err(Ok(InsuranceError::Unauthorized))
+    ));
+    c.approve_policy_transfer(&ctx.alice, &market, &id);
+    assert_eq!(c.get_policy_approval(&id), Some(market.clone()));
+
+    // Revoking withdraws the approval
+    c.revoke_policy_approval(&ctx.alice, &id);
+    assert_eq!(c.get_policy_approval(&id), None);
+    assert!(matches!(
+        c.try_transfer_policy_from(&market, &ctx.alice, &bob, &id),
+        Err(Ok(InsuranceError::Unauthorized))
+    ));
+
+    c.approve_policy_transfer(&ctx.alice, &market, &id);
+    c.transfer_policy_from(&market, &ctx.alice, &bob, &id);
+    assert_eq!(c.get_policy(&id).unwrap().policyholder, bob);
+
+    // The approval does not survive the transfer
+    assert_eq!(c.get_policy_approval(&id), None);
+    assert!(matches!(
+        c.try_transfer_policy_from(&market, &bob, &carol, &id),
+        Err(Ok(InsuranceError::Unauthorized))
+    ));
+
+    // Past the coverage window the policy is no longer transferable
+    advance_time(&ctx.env, 86_401);
+    assert!(matches!(
+        c.try_transfer_policy(&bob, &carol, &id),
+        Err(Ok(InsuranceError::PolicyExpired))
+    ));
+}
+
+// =============================================================================
+// Refund and renewal tests
+// =============================================================================
+
+#[test]
+fn cancellation_refunds_unearned_premium_less_fees() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
+
+    let create = || {
+        c.create_policy(
+            &ctx.alice,
+            &PolicyType::Weather,
+            &Symbol::new(&ctx.env, "RAINFALL"),
+            &200i128,
+            &TriggerCondition::GreaterThan,
+            &20_000i128,
+            &2_000i128,
+            &86_400u64,
+        )
+    };
+
+    // By default premiums are fully earned
+    let id = create();
+    assert_eq!(c.cancel_policy(&ctx.alice, &id), 0);
+
+    let invalid = RefundConfig { cancellation_fee_bps: 10_001, flat_fee: 0 };
+    assert!(matches!(
+        c.try_set_refund_config(&ctx.admin, &invalid),
+        Err(Ok(InsuranceError::InvalidRefundConfig))
+    ));
+    c.set_refund_config(
+        &ctx.admin,
+        &RefundConfig { cancellation_fee_bps: 1_000, flat_fee: 10 },
+    );
+
+    let id = create();
+    let liquidity = c.get_pool_stats().total_liquidity;
+    let before = balance(&ctx.env, &ctx.pool_token, &ctx.alice);
+
+    // Half the term left: 1_000 unearned, minus 10% and the flat fee
+    advance_time(&ctx.env, 43_200);
+    assert_eq!(c.cancel_policy(&ctx.alice, &id), 890);
+
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &ctx.alice) - before, 890);
+    let pool = c.get_pool_stats();
+    assert_eq!(pool.total_liquidity, liquidity - 890);
+    assert_eq!(pool.reserved_liquidity, 0);
+}
+
+#[test]
+fn renewal_extends_term_without_releasing_coverage() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+
+    c.deposit_liquidity(&ctx.lp, &50_000i128);
+
+    let feed = Symbol::new(&ctx.env, "RAINFALL");
+    let id = c.create_policy(
+        &ctx.alice,
+        &PolicyType::Weather,
+        &feed,
+        &200i128,
+        &TriggerCondition::GreaterThan,
+        &20_000i128,
+        &2_000i128,
+        &86_400u64,
+    );
+    let policy = c.get_policy(&id).unwrap();
+    let pool = c.get_pool_stats();
+
+    assert!(matches!(
+        c.try_renew_policy(&id, &0u64),
+        Err(Ok(InsuranceError::InvalidDuration))
+    ));
+
+    let quoted = c.quote_renewal(&id, &86_400u64);
+    let before = balance(&ctx.env, &ctx.pool_token, &ctx.alice);
+    assert_eq!(c.renew_policy(&id, &86_400u64), quoted);
+    assert_eq!(before - balance(&ctx.env, &ctx.pool_token, &ctx.alice), quoted);
+
+    let renewed = c.get_policy(&id).unwrap();
+    assert_eq!(renewed.end_time, policy.end_time + 86_400);
+    assert_eq!(renewed.premium_amount, 2_000 + quoted);
+
+    // The reservation is untouched; only the premium enters the pool
+    let after = c.get_pool_stats();
+    assert_eq!(after.reserved_liquidity, pool.reserved_liquidity);
+    assert_eq!(after.active_policies, pool.active_policies);
+    assert_eq!(after.total_liquidity, pool.total_liquidity + quoted);
+
+    // Still covered past the original end time
+    advance_time(&ctx.env, 86_401);
+    MockOracleClient::new(&ctx.env, &ctx.oracle)
+        .set_price(&feed, &150i128, &ctx.env.ledger().timestamp());
+    assert!(!c.check_trigger(&id));
+
+    advance_time(&ctx.env, 86_400);
+    assert!(matches!(
+        c.try_renew_policy(&id, &86_400u64),
+        Err(Ok(InsuranceError::PolicyExpired))
+    ));
+}
+
+// =============================================================================
+// Keeper batch tests
+// =============================================================================
+
+#[test]
+fn batch_trigger_check_settles_feed_in_pages() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let keeper = Address::generate(&ctx.env);
+
+    c.deposit_liquidity(&ctx.lp, &100_000i128);
+    c.set_keeper_reward(&ctx.admin, &50i128);
+
+    let rain = Symbol::new(&ctx.env, "RAINFALL");
+    let wind = Symbol::new(&ctx.env, "WIND");
+    let create = |feed: &Symbol| {
+        c.create_policy(
+            &ctx.alice,
+            &PolicyType::Weather,
+            feed,
+            &200i128,
+            &TriggerCondition::GreaterThan,
+            &10_000i128,
+            &1_000i128,
+            &86_400u64,
+        )
+    };
+    let ids = [create(&rain), create(&rain), create(&rain)];
+    let other = create(&wind);
+    assert_eq!(c.get_active_policies_for_feed(&rain).len(), 3);
+
+    let before = balance(&ctx.env, &ctx.pool_token, &ctx.alice);
+    MockOracleClient::new(&ctx.env, &ctx.oracle)
+        .set_price(&rain, &250i128, &ctx.env.ledger().timestamp());
+
+    // Settled policies leave the index, so the cursor stays put
+    let first = c.check_triggers_for_feed(&keeper, &rain, &0u64, &2u32);
+    assert_eq!(first.processed, 2);
+    assert_eq!(first.executed, 2);
+    assert_eq!(first.next_cursor, 0);
+    assert!(!first.done);
+    assert_eq!(first.reward, 100);
+
+    let second = c.check_triggers_for_feed(&keeper, &rain, &first.next_cursor, &2u32);
+    assert_eq!(second.processed, 1);
+    assert_eq!(second.executed, 1);
+    assert!(second.done);
+
+    for id in ids {
+        assert_eq!(c.get_policy(&id).unwrap().status, PolicyStatus::Claimed);
+    }
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &ctx.alice) - before, 30_000);
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &keeper), 150);
+    assert_eq!(c.get_active_policies_for_feed(&rain).len(), 0);
+
+    // Policies on other feeds are untouched
+    assert_eq!(c.get_policy(&other).unwrap().status, PolicyStatus::Active);
+    assert_eq!(c.get_active_policies_for_feed(&wind).len(), 1);
+
+    // Nothing left to do earns nothing
+    let idle = c.check_triggers_for_feed(&keeper, &rain, &0u64, &10u32);
+    assert_eq!(idle.processed, 0);
+    assert_eq!(idle.reward, 0);
+    assert!(idle.done);
+}
+
+#[test]
+fn keeper_batch_skips_policies_whose_oracle_read_fails() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let oracle = MockOracleClient::new(&ctx.env, &ctx.oracle);
+    let keeper = Address::generate(&ctx.env);
+    let rain = Symbol::new(&ctx.env, "RAIN");
+    let temp = Symbol::new(&ctx.env, "TEMP");
+
+    c.deposit_liquidity(&ctx.lp, &100_000i128);
+    advance_time(&ctx.env, 1_000);
+    oracle.set_price(&rain, &80i128, &ctx.env.ledger().timestamp());
+    oracle.set_price(&temp, &250i128, &ctx.env.ledger().timestamp());
+
+    // A composite policy on RAIN and TEMP, then a single-feed one on RAIN
+    let mut conds = Vec::new(&ctx.env);
+    conds.push_back(cond(&ctx.env, "RAIN", TriggerCondition::LessThan, 50));
+    conds.push_back(cond(&ctx.env, "TEMP", TriggerCondition::GreaterThan, 300));
+    let composite = c.create_composite_policy(
+        &ctx.alice,
+        &PolicyType::Crop,
+        &CompositeTrigger {
+            expression: TriggerExpression::AllOf(conds),
+            required_observations: 1,
+        },
+        &10_000i128,
+        &1_000i128,
+        &86_400u64,
+    );
+    let single = c.create_policy(
+        &ctx.alice,
+        &PolicyType::Crop,
+        &rain,
+        &50i128,
+        &TriggerCondition::LessThan,
+        &10_000i128,
+        &1_000i128,
+        &86_400u64,
+    );
+
+    // RAIN goes stale: both policies fail, and the batch still completes
+    advance_time(&ctx.env, 3_601);
+    let report = c.check_triggers_for_feed(&keeper, &rain, &0u64, &10u32);
+    assert_eq!(report.processed, 2);
+    assert_eq!(report.failed, 2);
+    assert_eq!(report.executed, 0);
+    assert_eq!(report.next_cursor, 2);
+    assert!(report.done);
+
+    // Fresh RAIN but stale TEMP: only the composite policy fails
+    oracle.set_price(&rain, &40i128, &ctx.env.ledger().timestamp());
+    let report = c.check_triggers_for_feed(&keeper, &rain, &0u64, &10u32);
+    assert_eq!(report.failed, 1);
+    assert_eq!(report.executed, 1);
+    assert_eq!(c.get_policy(&single).unwrap().status, PolicyStatus::Claimed);
+    assert_eq!(c.get_policy(&composite).unwrap().status, PolicyStatus::Active);
+    assert_eq!(report.next_cursor, 1);
+}
+
+#[test]
+fn keeper_batch_skips_policies_whose_payout_is_refused() {
+    let ctx = setup();
+
+    // A pool token whose issuer can freeze holders
+    let sac = ctx.env.register_stellar_asset_contract_v2(ctx.admin.clone());
+    sac.issuer().set_flag(IssuerFlags::RevocableFlag);
+    let pool_token = sac.address();
+    let contract = ctx.env.register_contract(None, ParametricInsuranceContract);
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &contract);
+    let sources = Vec::from_array(&ctx.env, [ctx.oracle.clone()]);
+    c.init(&ctx.admin, &pool_token, &sources, &3600u64, &1u32);
+
+    let keeper = Address::generate(&ctx.env);
+    let bob = Address::generate(&ctx.env);
+    for holder in [&ctx.lp, &ctx.alice, &bob] {
+        mint(&ctx.env, &pool_token, holder, 100_000);
+    }
+
+    c.deposit_liquidity(&ctx.lp, &100_000i128);
+    let rain = Symbol::new(&ctx.env, "RAINFALL");
+    let create = |holder: &Address| {
+        c.create_policy(
+            holder,
+            &PolicyType::Weather,
+            &rain,
+            &200i128,
+            &TriggerCondition::GreaterThan,
+            &10_000i128,
+            &1_000i128,
+            &86_400u64,
+        )
+    };
+    let frozen = create(&ctx.alice);
+    let paid = create(&bob);
+    let reserved = c.get_pool_stats().reserved_liquidity;
+
+    // The token refuses transfers to alice, so her payout cannot be made
+    token::StellarAssetClient::new(&ctx.env, &pool_token).set_authorized(&ctx.alice, &false);
+    MockOracleClient::new(&ctx.env, &ctx.oracle)
+        .set_price(&rain, &250i128, &ctx.env.ledger().timestamp());
+    assert_eq!(c.try_check_trigger(&frozen), Err(Ok(InsuranceError::PayoutFailed)));
+
+    // In a batch her policy is recorded as failed and left untouched
+    let report = c.check_triggers_for_feed(&keeper, &rain, &0u64, &10u32);
+    assert_eq!(report.failed, 1);
+    assert_eq!(report.executed, 1);
+    assert_eq!(c.get_policy(&paid).unwrap().status, PolicyStatus::Claimed);
+    let policy = c.get_policy(&frozen).unwrap();
+    assert_eq!(policy.status, PolicyStatus::Active);
+    assert_eq!(policy.paid_out, 0);
+    assert_eq!(c.get_pool_stats().reserved_liquidity, reserved - 10_000);
+    assert_eq!(balance(&ctx.env, &pool_token, &bob), 100_000 - 1_000 + 10_000);
+}
+
+#[test]
+fn sweep_expired_pages_through_policy_ids() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let keeper = Address::generate(&ctx.env);
+
+    c.deposit_liquidity(&ctx.lp, &100_000i128);
+    c.set_keeper_reward(&ctx.admin, &50i128);
+    assert!(matches!(
+        c.try_set_keeper_reward(&ctx.admin, &(-1i128)),
+        Err(Ok(InsuranceError::InvalidAmount))
+    ));
+
+    let feed = Symbol::new(&ctx.env, "RAINFALL");
+    let create = |duration: u64| {
+        c.create_policy(
+            &ctx.alice,
+            &PolicyType::Weather,
+            &feed,
+            &200i128,
+            &TriggerCondition::GreaterThan,
+            &10_000i128,
+            &1_000i128,
+            &duration,
+        )
+    };
+    let short_a = create(100);
+    let short_b = create(100);
+    let long = create(86_400);
+
+    advance_time(&ctx.env, 200);
+
+    let first = c.sweep_expired(&keeper, &0u64, &2u32);
+    assert_eq!(first.processed, 2);
+    assert_eq!(first.executed, 2);
+    assert_eq!(first.next_cursor, 3);
+    assert!(!first.done);
+
+    let second = c.sweep_expired(&keeper, &first.next_cursor, &2u32);
+    assert_eq!(second.processed, 1);
+    assert_eq!(second.executed, 0);
+    assert!(second.done);
+
+    assert_eq!(c.get_policy(&short_a).unwrap().status, PolicyStatus::Expired);
+    assert_eq!(c.get_policy(&short_b).unwrap().status, PolicyStatus::Expired);
+    assert_eq!(c.get_policy(&long).unwrap().status, PolicyStatus::Active);
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &keeper), 100);
+
+    let pool = c.get_pool_stats();
+    assert_eq!(pool.reserved_liquidity, 10_000);
+    assert_eq!(pool.active_policies, 1);
+    assert_eq!(c.get_active_policies_for_feed(&feed), Vec::from_array(&ctx.env, [long]));
+}
+
+// =============================================================================
+// Withdrawal queue tests
+// =============================================================================
+
+#[test]
+fn queued_withdrawal_is_fulfilled_when_coverage_expires() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+
+    c.deposit_liquidity(&ctx.lp, &20_000i128);
+    c.set_withdrawal_cooldown(&ctx.admin, &500u64);
+
+    let id = c.create_policy(
+        &ctx.alice,
+        &PolicyType::Weather,
+        &Symbol::new(&ctx.env, "RAINFALL"),
+        &200i128,
+        &TriggerCondition::GreaterThan,
+        &15_000i128,
+        &1_500i128,
+        &1_000u64,
+    );
+
+    // Instant exits are closed while a cooldown is configured
+    assert!(matches!(
+        c.try_withdraw_liquidity(&ctx.lp, &1_000i128),
+        Err(Ok(InsuranceError::WithdrawalCooldownActive))
+    ));
+
+    let request = c.request_withdrawal(&ctx.lp, &19_000i128);
+    assert_eq!(c.get_lp_shares(&ctx.lp), 0);
+    assert_eq!(c.get_withdrawal_request(&request).unwrap().unlock_at, 500);
+
+    // Not matured yet
+    assert_eq!(c.process_withdrawals(&10u32), 0);
+
+    // Matured, but most of the pool is still reserved
+    advance_time(&ctx.env, 600);
+    assert_eq!(c.process_withdrawals(&10u32), 0);
+
+    let before = balance(&ctx.env, &ctx.pool_token, &ctx.lp);
+    advance_time(&ctx.env, 500);
+    c.expire_policy(&id);
+
+    // Expiry released the coverage and paid out the queue, premium included;
+    // the dead shares keep their 1_000 / 20_000 of the pool
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &ctx.lp) - before, 20_425);
+    assert_eq!(c.get_withdrawal_request(&request), None);
+    assert_eq!(c.get_withdrawal_queue_head(), request + 1);
+    let pool = c.get_pool_stats();
+    assert_eq!(pool.total_liquidity, 1_075);
+    assert_eq!(pool.total_shares, DEAD_SHARES);
+}
+
+#[test]
+fn withdrawal_queue_is_first_in_first_out() {
+    let ctx = setup();
+    let c = ParametricInsuranceContractClient::new(&ctx.env, &ctx.contract);
+    let lp2 = Address::generate(&ctx.env);
+    mint(&ctx.env, &ctx.pool_token, &lp2, 10_000);
+
+    c.deposit_liquidity(&ctx.lp, &10_000i128);
+    c.deposit_liquidity(&lp2, &10_000i128);
+    c.set_withdrawal_cooldown(&ctx.admin, &100u64);
+
+    c.create_policy(
+        &ctx.alice,
+        &PolicyType::Weather,
+        &Symbol::new(&ctx.env, "RAINFALL"),
+        &200i128,
+        &TriggerCondition::GreaterThan,
+        &15_000i128,
+        &1_500i128,
+        &86_400u64,
+    );
+
+    let large = c.request_withdrawal(&ctx.lp, &9_000i128);
+    let small = c.request_withdrawal(&lp2, &1_000i128);
+    advance_time(&ctx.env, 200);
+
+    // The small request fits but may not jump the blocked one ahead of it
+    assert_eq!(c.process_withdrawals(&10u32), 0);
+    assert!(c.get_withdrawal_request(&small).is_some());
+
+    // Only the owner can cancel; cancelling unlocks the shares
+    assert!(matches!(
+        c.try_cancel_withdrawal(&lp2, &large),
+        Err(Ok(InsuranceError::Unauthorized))
+    ));
+    c.cancel_withdrawal(&ctx.lp, &large);
+    assert_eq!(c.get_lp_shares(&ctx.lp), 9_000);
+    assert!(matches!(
+        c.try_cancel_withdrawal(&ctx.lp, &large),
+        Err(Ok(InsuranceError::WithdrawalRequestNotFound))
+    ));
+
+    let before = balance(&ctx.env, &ctx.pool_token, &lp2);
+    assert_eq!(c.process_withdrawals(&10u32), 1);
+    assert_eq!(balance(&ctx.env, &ctx.pool_token, &lp2) - before, 1_075);
+    assert_eq!(c.get_lp_shares(&lp2), 9_000);
+    assert_eq!(c.get_withdrawal_queue_head(), small + 1);
+}
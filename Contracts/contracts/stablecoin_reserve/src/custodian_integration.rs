@@ -1,20 +1,29 @@
-use crate::{ReserveAsset, ReserveError};
-use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Map, Symbol, Vec};
-
-const CUSTODIAN_REGISTRY: Symbol = symbol_short!("custodian_reg");
-const SYNC_HISTORY: Symbol = symbol_short!("sync_history");
+use crate::{AssetType, ReserveAsset, ReserveError};
+use shared::governance::GovernanceRole;
+use soroban_sdk::{
+    contracttype, symbol_short, xdr::ToXdr, Address, BytesN, Env, Map, Symbol, Vec,
+};
+
+const CUSTODIAN_REGISTRY: Symbol = symbol_short!("cust_reg");
+const SYNC_HISTORY: Symbol = symbol_short!("sync_hist");
 const LAST_SYNC: Symbol = symbol_short!("last_sync");
+const ATTESTATION_MAX_AGE: Symbol = symbol_short!("att_age");
+
+/// Default age after which a custodian's last attestation is stale (24 hours)
+const DEFAULT_ATTESTATION_MAX_AGE: u64 = 24 * 60 * 60;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CustodianInfo {
     pub address: Address,
     pub name: Symbol,
-    pub api_endpoint: Symbol,
     pub verification_method: VerificationMethod,
     pub is_active: bool,
-    pub last_sync: u64,
+    pub last_sync: u64, // timestamp of the last accepted attestation
     pub sync_status: SyncStatus,
+    pub signing_keys: Vec<BytesN<32>>, // ed25519 public keys allowed to sign reports
+    pub threshold: u32,                // distinct signatures needed per report
+    pub last_nonce: u64,               // nonce of the last accepted attestation
 }
 
 #[contracttype]
@@ -24,7 +33,7 @@ pub enum VerificationMethod {
     API = 0,
     Manual = 1,
     Oracle = 2,
-    MultiSig = 3,
+    MultiSig = 3, // reports need `threshold` of the custodian's keys
 }
 
 #[contracttype]
@@ -35,6 +44,7 @@ pub enum SyncStatus {
     Pending = 1,
     Failed = 2,
     Disabled = 3,
+    Stale = 4, // no fresh attestation within the max age
 }
 
 #[contracttype]
@@ -50,30 +60,57 @@ pub struct SyncOperation {
     pub error_message: Option<Symbol>,
 }
 
+/// The canonical payload custodians sign. Signatures cover its XDR
+/// encoding; binding the reserve contract stops a report being replayed
+/// against another deployment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BalanceAttestation {
+    pub reserve: Address,
+    pub custodian: Address,
+    pub asset_type: AssetType,
+    pub amount: u128,
+    pub nonce: u64,
+    pub timestamp: u64,
+}
+
+/// One signature over a `BalanceAttestation`, by the key at `key_index`
+/// in the custodian's `signing_keys`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AttestationSignature {
+    pub key_index: u32,
+    pub signature: BytesN<64>,
+}
+
 pub fn register_custodian(
     env: Env,
+    admin: Address,
     address: Address,
     name: Symbol,
-    api_endpoint: Symbol,
     verification_method: VerificationMethod,
+    signing_keys: Vec<BytesN<32>>,
+    threshold: u32,
 ) -> Result<(), ReserveError> {
-    if !shared::governance::has_role(
-        env.clone(),
-        env.invoker(),
-        shared::governance::GovernanceRole::Admin,
-    ) {
-        return Err(ReserveError::Unauthorized);
-    }
+    crate::require_role(&env, &admin, GovernanceRole::Admin)?;
 
-    let custodian_info = CustodianInfo {
-        address: address.clone(),
-        name,
-        api_endpoint,
-        verification_method,
-        is_active: true,
-        last_sync: 0,
-        sync_status: SyncStatus::Pending,
+    // Single-key methods sign with one key; only MultiSig may ask for more
+    let threshold = if verification_method == VerificationMethod::MultiSig {
+        threshold
+    } else {
+        1
     };
+    if threshold == 0 || threshold > signing_keys.len() {
+        return Err(ReserveError::CustodianError);
+    }
+
+    // Signatures are counted per key index, so a repeated key would let one
+    // signer count twice towards the threshold
+    for (i, key) in signing_keys.iter().enumerate() {
+        if signing_keys.first_index_of(&key) != Some(i as u32) {
+            return Err(ReserveError::CustodianError);
+        }
+    }
 
     let mut registry = env
         .storage()
@@ -81,68 +118,210 @@ pub fn register_custodian(
         .get(&CUSTODIAN_REGISTRY)
         .unwrap_or(Map::<Address, CustodianInfo>::new(&env));
 
+    // Re-registering (e.g. to rotate keys) keeps the nonce and sync history,
+    // so attestations already accepted cannot be replayed
+    let (last_nonce, last_sync, sync_status) = match registry.get(address.clone()) {
+        Some(existing) => (existing.last_nonce, existing.last_sync, existing.sync_status),
+        None => (0, 0, SyncStatus::Pending),
+    };
+
+    let custodian_info = CustodianInfo {
+        address: address.clone(),
+        name,
+        verification_method,
+        is_active: true,
+        last_sync,
+        sync_status,
+        signing_keys,
+        threshold,
+        last_nonce,
+    };
+
     registry.set(address.clone(), custodian_info);
     env.storage().instance().set(&CUSTODIAN_REGISTRY, &registry);
 
     env.events().publish(
-        (symbol_short!("custodian"), symbol_short!("registered")),
-        (address, verification_method),
+        (symbol_short!("custodian"), symbol_short!("register")),
+        (address, verification_method, threshold),
     );
 
     Ok(())
 }
 
-pub fn sync_with_custodian(env: Env, custodian: Address) -> Result<(), ReserveError> {
+/// Accept a signed balance report for one of `custodian`'s reserve assets.
+///
+/// Anyone may relay the report; it is authenticated by at least
+/// `threshold` distinct signatures from the custodian's keys. The nonce must
+/// increase and the timestamp must be within the max attestation age.
+/// `ed25519_verify` traps on a bad signature, rejecting the whole report.
+pub fn submit_attestation(
+    env: Env,
+    custodian: Address,
+    asset_type: AssetType,
+    amount: u128,
+    nonce: u64,
+    timestamp: u64,
+    signatures: Vec<AttestationSignature>,
+) -> Result<(), ReserveError> {
     let now = env.ledger().timestamp();
 
-    let registry = env
+    let mut registry: Map<Address, CustodianInfo> = env
         .storage()
         .instance()
         .get(&CUSTODIAN_REGISTRY)
         .ok_or(ReserveError::CustodianError)?;
 
-    let custodian_info = registry
+    let mut custodian_info = registry
         .get(custodian.clone())
         .ok_or(ReserveError::CustodianError)?;
 
     if !custodian_info.is_active {
         return Err(ReserveError::CustodianError);
     }
+    if nonce <= custodian_info.last_nonce {
+        return Err(ReserveError::StaleAttestation);
+    }
+    if timestamp > now
+        || timestamp < custodian_info.last_sync
+        || now - timestamp > get_attestation_max_age(env.clone())
+    {
+        return Err(ReserveError::StaleAttestation);
+    }
 
-    let sync_result = match custodian_info.verification_method {
-        VerificationMethod::API => sync_via_api(env.clone(), &custodian_info)?,
-        VerificationMethod::Manual => sync_via_manual(env.clone(), &custodian_info)?,
-        VerificationMethod::Oracle => sync_via_oracle(env.clone(), &custodian_info)?,
-        VerificationMethod::MultiSig => sync_via_multisig(env.clone(), &custodian_info)?,
+    let attestation = BalanceAttestation {
+        reserve: env.current_contract_address(),
+        custodian: custodian.clone(),
+        asset_type,
+        amount,
+        nonce,
+        timestamp,
     };
+    let payload = attestation.to_xdr(&env);
+
+    // Count each key once, however many times it is presented
+    let mut signed_by: Vec<u32> = Vec::new(&env);
+    for entry in signatures.iter() {
+        let key = custodian_info
+            .signing_keys
+            .get(entry.key_index)
+            .ok_or(ReserveError::CustodianError)?;
+        if signed_by.contains(entry.key_index) {
+            continue;
+        }
+        env.crypto().ed25519_verify(&key, &payload, &entry.signature);
+        signed_by.push_back(entry.key_index);
+    }
+    if signed_by.len() < custodian_info.threshold {
+        return Err(ReserveError::CustodianError);
+    }
 
-    let sync_status = sync_result.status;
-    record_sync_operation(env.clone(), sync_result)?;
-
-    let mut updated_info = custodian_info;
-    updated_info.last_sync = now;
-    updated_info.sync_status = sync_status;
+    let verification_hash = BytesN::from_array(&env, &env.crypto().sha256(&payload).to_array());
 
-    let mut registry = env
+    // Apply the report to the custodian's holding of this asset type
+    let mut assets: Vec<ReserveAsset> = env
         .storage()
         .instance()
-        .get(&CUSTODIAN_REGISTRY)
-        .unwrap_or(Map::<Address, CustodianInfo>::new(&env));
-    registry.set(custodian.clone(), updated_info);
+        .get(&symbol_short!("rsv_asset"))
+        .ok_or(ReserveError::InvalidAsset)?;
+    let asset_index = assets
+        .iter()
+        .position(|asset| asset.custodian == custodian && asset.asset_type == asset_type)
+        .ok_or(ReserveError::InvalidAsset)? as u32;
+    let asset = assets.get(asset_index).unwrap();
+    let old_amount = asset.amount;
+    assets.set(
+        asset_index,
+        ReserveAsset {
+            amount,
+            last_verified: timestamp,
+            verification_hash: verification_hash.clone(),
+            ..asset
+        },
+    );
+    env.storage()
+        .instance()
+        .set(&symbol_short!("rsv_asset"), &assets);
+
+    record_sync_operation(
+        env.clone(),
+        SyncOperation {
+            timestamp,
+            custodian: custodian.clone(),
+            asset_type,
+            old_amount,
+            new_amount: amount,
+            verification_hash,
+            status: SyncStatus::Success,
+            error_message: None,
+        },
+    )?;
+
+    custodian_info.last_sync = timestamp;
+    custodian_info.last_nonce = nonce;
+    custodian_info.sync_status = SyncStatus::Success;
+    registry.set(custodian.clone(), custodian_info);
     env.storage().instance().set(&CUSTODIAN_REGISTRY, &registry);
 
     env.storage().instance().set(&LAST_SYNC, &now);
 
     env.events().publish(
-        (symbol_short!("custodian"), symbol_short!("synced")),
-        (custodian, sync_status),
+        (symbol_short!("custodian"), symbol_short!("attested")),
+        (custodian, asset_type, old_amount, amount, nonce),
     );
 
+    crate::reserve_tracking::update_snapshot(env.clone())
+}
+
+/// Flag every active custodian whose last attestation is older than the
+/// max age, emitting an alert for each one newly gone stale. Permissionless,
+/// for keepers to poll; returns the stale custodians.
+pub fn check_stale_attestations(env: Env) -> Result<Vec<Address>, ReserveError> {
+    let now = env.ledger().timestamp();
+    let max_age = get_attestation_max_age(env.clone());
+
+    let mut registry: Map<Address, CustodianInfo> = env
+        .storage()
+        .instance()
+        .get(&CUSTODIAN_REGISTRY)
+        .unwrap_or(Map::new(&env));
+
+    let mut stale: Vec<Address> = Vec::new(&env);
+    for (address, mut custodian_info) in registry.iter() {
+        if !custodian_info.is_active || now.saturating_sub(custodian_info.last_sync) <= max_age {
+            continue;
+        }
+        stale.push_back(address.clone());
+        if custodian_info.sync_status != SyncStatus::Stale {
+            custodian_info.sync_status = SyncStatus::Stale;
+            env.events().publish(
+                (symbol_short!("custodian"), symbol_short!("stale")),
+                (address.clone(), custodian_info.last_sync, now),
+            );
+            registry.set(address, custodian_info);
+        }
+    }
+
+    env.storage().instance().set(&CUSTODIAN_REGISTRY, &registry);
+    Ok(stale)
+}
+
+pub fn set_attestation_max_age(env: Env, admin: Address, max_age: u64) -> Result<(), ReserveError> {
+    crate::require_role(&env, &admin, GovernanceRole::Admin)?;
+    if max_age == 0 {
+        return Err(ReserveError::CustodianError);
+    }
+
+    env.storage().instance().set(&ATTESTATION_MAX_AGE, &max_age);
+
+    env.events().publish(
+        (symbol_short!("custodian"), symbol_short!("max_age")),
+        max_age,
+    );
     Ok(())
 }
 
 pub fn get_custodian_info(env: Env, custodian: Address) -> Result<CustodianInfo, ReserveError> {
-    let registry = env
+    let registry: Map<Address, CustodianInfo> = env
         .storage()
         .instance()
         .get(&CUSTODIAN_REGISTRY)
@@ -152,7 +331,7 @@ pub fn get_custodian_info(env: Env, custodian: Address) -> Result<CustodianInfo,
 }
 
 pub fn get_all_custodians(env: Env) -> Result<Vec<CustodianInfo>, ReserveError> {
-    let registry = env
+    let registry: Map<Address, CustodianInfo> = env
         .storage()
         .instance()
         .get(&CUSTODIAN_REGISTRY)
@@ -189,16 +368,14 @@ pub fn get_sync_history(
     }
 }
 
-pub fn deactivate_custodian(env: Env, custodian: Address) -> Result<(), ReserveError> {
-    if !shared::governance::has_role(
-        env.clone(),
-        env.invoker(),
-        shared::governance::GovernanceRole::Admin,
-    ) {
-        return Err(ReserveError::Unauthorized);
-    }
+pub fn deactivate_custodian(
+    env: Env,
+    admin: Address,
+    custodian: Address,
+) -> Result<(), ReserveError> {
+    crate::require_role(&env, &admin, GovernanceRole::Admin)?;
 
-    let mut registry = env
+    let mut registry: Map<Address, CustodianInfo> = env
         .storage()
         .instance()
         .get(&CUSTODIAN_REGISTRY)
@@ -215,167 +392,18 @@ pub fn deactivate_custodian(env: Env, custodian: Address) -> Result<(), ReserveE
     env.storage().instance().set(&CUSTODIAN_REGISTRY, &registry);
 
     env.events().publish(
-        (symbol_short!("custodian"), symbol_short!("deactivated")),
+        (symbol_short!("custodian"), symbol_short!("disabled")),
         custodian,
     );
 
     Ok(())
 }
 
-fn sync_via_api(env: Env, custodian_info: &CustodianInfo) -> Result<SyncOperation, ReserveError> {
-    let assets = env
-        .storage()
-        .instance()
-        .get(&symbol_short!("reserve_assets"))
-        .ok_or(ReserveError::InvalidAsset)?;
-
-    for asset in assets.iter() {
-        if asset.custodian == custodian_info.address {
-            let new_amount = simulate_api_call(env.clone(), custodian_info, asset.asset_type)?;
-            let verification_hash = generate_verification_hash(env.clone(), &new_amount);
-
-            return Ok(SyncOperation {
-                timestamp: env.ledger().timestamp(),
-                custodian: custodian_info.address.clone(),
-                asset_type: asset.asset_type,
-                old_amount: asset.amount,
-                new_amount,
-                verification_hash,
-                status: SyncStatus::Success,
-                error_message: None,
-            });
-        }
-    }
-
-    Err(ReserveError::CustodianError)
-}
-
-fn sync_via_manual(
-    env: Env,
-    custodian_info: &CustodianInfo,
-) -> Result<SyncOperation, ReserveError> {
-    let assets = env
-        .storage()
+fn get_attestation_max_age(env: Env) -> u64 {
+    env.storage()
         .instance()
-        .get(&symbol_short!("reserve_assets"))
-        .ok_or(ReserveError::InvalidAsset)?;
-
-    for asset in assets.iter() {
-        if asset.custodian == custodian_info.address {
-            return Ok(SyncOperation {
-                timestamp: env.ledger().timestamp(),
-                custodian: custodian_info.address.clone(),
-                asset_type: asset.asset_type,
-                old_amount: asset.amount,
-                new_amount: asset.amount,
-                verification_hash: asset.verification_hash,
-                status: SyncStatus::Success,
-                error_message: None,
-            });
-        }
-    }
-
-    Err(ReserveError::CustodianError)
-}
-
-fn sync_via_oracle(
-    env: Env,
-    custodian_info: &CustodianInfo,
-) -> Result<SyncOperation, ReserveError> {
-    let assets = env
-        .storage()
-        .instance()
-        .get(&symbol_short!("reserve_assets"))
-        .ok_or(ReserveError::InvalidAsset)?;
-
-    for asset in assets.iter() {
-        if asset.custodian == custodian_info.address {
-            let oracle_verified_amount = simulate_oracle_verification(env.clone(), asset)?;
-            let verification_hash =
-                generate_verification_hash(env.clone(), &oracle_verified_amount);
-
-            return Ok(SyncOperation {
-                timestamp: env.ledger().timestamp(),
-                custodian: custodian_info.address.clone(),
-                asset_type: asset.asset_type,
-                old_amount: asset.amount,
-                new_amount: oracle_verified_amount,
-                verification_hash,
-                status: SyncStatus::Success,
-                error_message: None,
-            });
-        }
-    }
-
-    Err(ReserveError::CustodianError)
-}
-
-fn sync_via_multisig(
-    env: Env,
-    custodian_info: &CustodianInfo,
-) -> Result<SyncOperation, ReserveError> {
-    let assets = env
-        .storage()
-        .instance()
-        .get(&symbol_short!("reserve_assets"))
-        .ok_or(ReserveError::InvalidAsset)?;
-
-    for asset in assets.iter() {
-        if asset.custodian == custodian_info.address {
-            let multisig_verified_amount = simulate_multisig_verification(env.clone(), asset)?;
-            let verification_hash =
-                generate_verification_hash(env.clone(), &multisig_verified_amount);
-
-            return Ok(SyncOperation {
-                timestamp: env.ledger().timestamp(),
-                custodian: custodian_info.address.clone(),
-                asset_type: asset.asset_type,
-                old_amount: asset.amount,
-                new_amount: multisig_verified_amount,
-                verification_hash,
-                status: SyncStatus::Success,
-                error_message: None,
-            });
-        }
-    }
-
-    Err(ReserveError::CustodianError)
-}
-
-fn simulate_api_call(
-    _env: Env,
-    _custodian_info: &CustodianInfo,
-    asset_type: crate::AssetType,
-) -> Result<u128, ReserveError> {
-    match asset_type {
-        crate::AssetType::USD => Ok(1_000_000_000_000u128),
-        crate::AssetType::Treasury => Ok(750_000_000_000u128),
-        crate::AssetType::Repo => Ok(500_000_000_000u128),
-        crate::AssetType::CorporateBond => Ok(250_000_000_000u128),
-        crate::AssetType::ETF => Ok(100_000_000_000u128),
-    }
-}
-
-fn simulate_oracle_verification(_env: Env, asset: &ReserveAsset) -> Result<u128, ReserveError> {
-    let adjustment = match asset.asset_type {
-        crate::AssetType::USD => 0,
-        crate::AssetType::Treasury => asset.amount / 1000,
-        crate::AssetType::Repo => asset.amount / 2000,
-        crate::AssetType::CorporateBond => asset.amount / 500,
-        crate::AssetType::ETF => asset.amount / 1000,
-    };
-
-    Ok(asset.amount + adjustment)
-}
-
-fn simulate_multisig_verification(_env: Env, asset: &ReserveAsset) -> Result<u128, ReserveError> {
-    Ok(asset.amount)
-}
-
-fn generate_verification_hash(env: Env, amount: &u128) -> BytesN<32> {
-    let data = (*amount, env.ledger().timestamp());
-    let hash = env.crypto().keccak256(&data.to_xdr());
-    BytesN::from_array(&env, &hash)
+        .get(&ATTESTATION_MAX_AGE)
+        .unwrap_or(DEFAULT_ATTESTATION_MAX_AGE)
 }
 
 fn record_sync_operation(env: Env, operation: SyncOperation) -> Result<(), ReserveError> {
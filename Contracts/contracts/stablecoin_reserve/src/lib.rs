@@ -1,8 +1,9 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Map,
-    Symbol, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN,
+    Env, Symbol, Vec,
 };
+use shared::acl::{ACL, PERMISSION_APPROVE, PERMISSION_EXECUTE, PERMISSION_PROPOSE};
 use shared::events::{extended_topics, ReserveAssetAddedEvent, ReserveAssetUpdatedEvent};
 
 mod custodian_integration;
@@ -15,7 +16,18 @@ mod reserve_tracking;
 #[cfg(test)]
 mod test;
 
-use shared::governance::{GovernanceRole, ProposalStatus, UpgradeProposal};
+pub use custodian_integration::{
+    AttestationSignature, BalanceAttestation, CustodianInfo, SyncOperation, SyncStatus,
+    VerificationMethod,
+};
+pub use proof_of_reserves::{ProofCommitment, PublishedProof};
+pub use rebalancing::{
+    LegStatus, LegVenue, RebalancingLeg, RebalancingOperation, TargetAllocation,
+};
+pub use redemption::RedemptionQueue;
+pub use regulatory_reporting::{ComplianceSummary, RegulatoryReport, ReportType};
+
+use shared::governance::{GovernanceManager, GovernanceRole, UpgradeProposal};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -31,6 +43,13 @@ pub enum ReserveError {
     CustodianError = 3009,
     ReportingError = 3010,
     GovernanceError = 3011,
+    RebalancingPending = 3012,
+    SlippageExceeded = 3013,
+    StaleAttestation = 3014,
+    // Revealed liabilities differ from the stablecoin supply at the snapshot
+    LiabilitiesMismatch = 3015,
+    RedemptionNotFound = 3016,
+    RedemptionNotApproved = 3017,
 }
 
 #[contracttype]
@@ -68,8 +87,10 @@ pub struct ReserveSnapshot {
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RedemptionRequest {
+    pub id: u64,
     pub requester: Address,
-    pub amount: u128,
+    pub amount: u128, // stablecoins escrowed by the contract
+    pub payout_asset: AssetType, // reserve asset paid out on processing
     pub request_time: u64,
     pub status: RedemptionStatus,
     pub processed_time: Option<u64>,
@@ -85,6 +106,26 @@ pub enum RedemptionStatus {
     Processed = 3,
 }
 
+/// Require `caller`'s authorization and that it holds `role` in the
+/// governance ACL
+pub(crate) fn require_role(
+    env: &Env,
+    caller: &Address,
+    role: GovernanceRole,
+) -> Result<(), ReserveError> {
+    caller.require_auth();
+    let permission = match role {
+        GovernanceRole::Admin => PERMISSION_PROPOSE,
+        GovernanceRole::Approver => PERMISSION_APPROVE,
+        GovernanceRole::Executor => PERMISSION_EXECUTE,
+    };
+    if !ACL::has_permission(env, caller, &permission) {
+        return Err(ReserveError::Unauthorized);
+    }
+    Ok(())
+}
+
+#[contract]
 pub struct StablecoinReserveContract;
 
 #[contractimpl]
@@ -96,9 +137,14 @@ impl StablecoinReserveContract {
         approvers: Vec<Address>,
         executor: Address,
         stablecoin_address: Address,
-    ) {
+    ) -> Result<(), ReserveError> {
+        if env.storage().instance().has(&symbol_short!("stblcoin")) {
+            return Err(ReserveError::Unauthorized);
+        }
+        admin.require_auth();
+
         // Initialize governance
-        shared::governance::initialize_governance(env.clone(), admin, approvers, executor);
+        GovernanceManager::init_governance_roles(&env, admin, approvers, executor);
 
         // Set stablecoin address
         env.storage()
@@ -106,7 +152,7 @@ impl StablecoinReserveContract {
             .set(&symbol_short!("stblcoin"), &stablecoin_address);
 
         // Initialize reserve tracking
-        reserve_tracking::initialize(env.clone());
+        reserve_tracking::initialize(env.clone())?;
 
         // Initialize rebalancing with 5% threshold
         rebalancing::initialize(env.clone(), 500); // 5% in basis points
@@ -116,20 +162,20 @@ impl StablecoinReserveContract {
             (symbol_short!("reserve"), symbol_short!("initd")),
             (stablecoin_address, env.ledger().timestamp()),
         );
+        Ok(())
     }
 
     /// Add a new reserve asset
     pub fn add_reserve_asset(
         env: Env,
+        admin: Address,
         asset_type: AssetType,
         amount: u128,
         custodian: Address,
         verification_hash: BytesN<32>,
     ) -> Result<(), ReserveError> {
         // Check governance authorization
-        if !shared::governance::has_role(env.clone(), env.invoker(), GovernanceRole::Admin) {
-            return Err(ReserveError::Unauthorized);
-        }
+        require_role(&env, &admin, GovernanceRole::Admin)?;
 
         reserve_tracking::add_asset(
             env.clone(),
@@ -144,7 +190,7 @@ impl StablecoinReserveContract {
             ReserveAssetAddedEvent {
                 asset: custodian.clone(),
                 target_allocation: 0,
-                added_by: env.invoker(),
+                added_by: admin,
                 timestamp: env.ledger().timestamp(),
             },
         );
@@ -154,14 +200,13 @@ impl StablecoinReserveContract {
     /// Update reserve asset amount
     pub fn update_reserve_asset(
         env: Env,
+        admin: Address,
         asset_index: u32,
         new_amount: u128,
         verification_hash: BytesN<32>,
     ) -> Result<(), ReserveError> {
         // Check governance authorization
-        if !shared::governance::has_role(env.clone(), env.invoker(), GovernanceRole::Admin) {
-            return Err(ReserveError::Unauthorized);
-        }
+        require_role(&env, &admin, GovernanceRole::Admin)?;
 
         reserve_tracking::update_asset(env.clone(), asset_index, new_amount, verification_hash.clone())?;
 
@@ -170,21 +215,51 @@ impl StablecoinReserveContract {
             ReserveAssetUpdatedEvent {
                 asset: env.current_contract_address(),
                 new_allocation: asset_index,
-                updated_by: env.invoker(),
+                updated_by: admin,
                 timestamp: env.ledger().timestamp(),
             },
         );
         Ok(())
     }
 
-    /// Generate daily proof of reserves
-    pub fn generate_proof_of_reserves(env: Env) -> Result<BytesN<32>, ReserveError> {
-        // Check governance authorization
-        if !shared::governance::has_role(env.clone(), env.invoker(), GovernanceRole::Admin) {
-            return Err(ReserveError::Unauthorized);
-        }
+    /// Commit to the daily holder-balance tree, pinning the snapshot ledger
+    /// and the stablecoin's current total supply
+    pub fn commit_proof_of_reserves(
+        env: Env,
+        admin: Address,
+        commitment: BytesN<32>,
+    ) -> Result<(), ReserveError> {
+        proof_of_reserves::commit_proof(env.clone(), admin, commitment)
+    }
 
-        proof_of_reserves::generate_daily_proof(env.clone())
+    /// Reveal the committed holder-balance tree and publish its root. Only
+    /// the builder that committed it may reveal.
+    pub fn reveal_proof_of_reserves(
+        env: Env,
+        builder: Address,
+        root: BytesN<32>,
+        total_liabilities: u128,
+        leaf_count: u32,
+        salt: BytesN<32>,
+    ) -> Result<BytesN<32>, ReserveError> {
+        proof_of_reserves::reveal_proof(
+            env.clone(),
+            builder,
+            root,
+            total_liabilities,
+            leaf_count,
+            salt,
+        )
+    }
+
+    /// Get the latest published proof of reserves
+    pub fn get_published_proof(env: Env) -> Result<PublishedProof, ReserveError> {
+        proof_of_reserves::get_published_proof(env.clone())
+    }
+
+    /// Get the proof commitment awaiting its reveal, if any
+    pub fn get_pending_proof_commitment(env: Env) -> Option<ProofCommitment> {
+        proof_of_reserves::get_pending_commitment(env.clone())
     }
 
     /// Verify a specific user's inclusion in the proof of reserves
@@ -204,54 +279,275 @@ impl StablecoinReserveContract {
     }
 
     /// Execute rebalancing if needed
-    pub fn execute_rebalancing(env: Env) -> Result<(), ReserveError> {
+    pub fn execute_rebalancing(env: Env, executor: Address) -> Result<(), ReserveError> {
         // Check governance authorization
-        if !shared::governance::has_role(env.clone(), env.invoker(), GovernanceRole::Executor) {
-            return Err(ReserveError::Unauthorized);
-        }
+        require_role(&env, &executor, GovernanceRole::Executor)?;
 
         rebalancing::execute_rebalancing(env.clone())
     }
 
-    /// Generate regulatory report
-    pub fn generate_regulatory_report(env: Env) -> Result<Symbol, ReserveError> {
+    /// Register the token an asset type is held in for on-chain rebalancing
+    pub fn set_rebalancing_token(
+        env: Env,
+        admin: Address,
+        asset_type: AssetType,
+        token: Address,
+    ) -> Result<(), ReserveError> {
+        rebalancing::set_asset_token(env.clone(), admin, asset_type, token)
+    }
+
+    /// Set the AMM router token legs swap through and the max slippage (bps)
+    pub fn set_amm_router(
+        env: Env,
+        admin: Address,
+        router: Address,
+        max_slippage_bps: u64,
+    ) -> Result<(), ReserveError> {
+        rebalancing::set_amm_router(env.clone(), admin, router, max_slippage_bps)
+    }
+
+    /// Set how far (bps) a custodian leg may settle above its `amount_in`
+    pub fn set_rebalancing_leg_tolerance(
+        env: Env,
+        admin: Address,
+        tolerance_bps: u64,
+    ) -> Result<(), ReserveError> {
+        rebalancing::set_leg_tolerance(env.clone(), admin, tolerance_bps)
+    }
+
+    /// Custodian reports what an off-chain rebalancing leg delivered
+    pub fn settle_rebalancing_leg(
+        env: Env,
+        custodian: Address,
+        leg_id: u64,
+        amount_out: u128,
+    ) -> Result<(), ReserveError> {
+        rebalancing::settle_custodian_leg(env.clone(), custodian, leg_id, amount_out)
+    }
+
+    /// Cancel a pending rebalancing leg
+    pub fn cancel_rebalancing_leg(
+        env: Env,
+        admin: Address,
+        leg_id: u64,
+    ) -> Result<(), ReserveError> {
+        rebalancing::cancel_leg(env.clone(), admin, leg_id)
+    }
+
+    /// Get a rebalancing leg by id
+    pub fn get_rebalancing_leg(env: Env, leg_id: u64) -> Result<RebalancingLeg, ReserveError> {
+        rebalancing::get_leg(&env, leg_id).ok_or(ReserveError::InvalidAsset)
+    }
+
+    /// Get rebalancing legs awaiting custodian settlement
+    pub fn get_pending_rebalancing_legs(env: Env) -> Vec<RebalancingLeg> {
+        rebalancing::get_pending_legs(env.clone())
+    }
+
+    /// Get settled rebalancing operations, oldest first
+    pub fn get_rebalancing_history(
+        env: Env,
+    ) -> Result<Vec<RebalancingOperation>, ReserveError> {
+        rebalancing::get_rebalancing_history(env.clone())
+    }
+
+    /// Update the target allocation of one asset type
+    pub fn update_target_allocation(
+        env: Env,
+        admin: Address,
+        asset_type: AssetType,
+        target_percentage: u64,
+        min_percentage: u64,
+        max_percentage: u64,
+    ) -> Result<(), ReserveError> {
+        rebalancing::update_target_allocation(
+            env.clone(),
+            admin,
+            asset_type,
+            target_percentage,
+            min_percentage,
+            max_percentage,
+        )
+    }
+
+    /// Get the target allocation of every asset type
+    pub fn get_target_allocations(env: Env) -> Result<Vec<TargetAllocation>, ReserveError> {
+        rebalancing::get_target_allocations(env.clone())
+    }
+
+    /// Generate regulatory report; returns its id
+    pub fn generate_regulatory_report(env: Env, admin: Address) -> Result<u64, ReserveError> {
         // Check governance authorization
-        if !shared::governance::has_role(env.clone(), env.invoker(), GovernanceRole::Admin) {
-            return Err(ReserveError::Unauthorized);
-        }
+        require_role(&env, &admin, GovernanceRole::Admin)?;
 
         regulatory_reporting::generate_report(env.clone())
     }
 
-    /// Sync with custodian API
-    pub fn sync_with_custodian(env: Env, custodian: Address) -> Result<(), ReserveError> {
-        // Check governance authorization
-        if !shared::governance::has_role(env.clone(), env.invoker(), GovernanceRole::Admin) {
-            return Err(ReserveError::Unauthorized);
-        }
+    /// Get a regulatory report by id
+    pub fn get_regulatory_report(
+        env: Env,
+        report_id: u64,
+    ) -> Result<RegulatoryReport, ReserveError> {
+        regulatory_reporting::get_report(env.clone(), report_id)
+    }
 
-        custodian_integration::sync_with_custodian(env.clone(), custodian)
+    /// Get every stored regulatory report of one type
+    pub fn get_regulatory_reports_by_type(
+        env: Env,
+        report_type: ReportType,
+    ) -> Result<Vec<RegulatoryReport>, ReserveError> {
+        regulatory_reporting::get_reports_by_type(env.clone(), report_type)
     }
 
-    /// Request redemption for large holders ($1M+)
-    pub fn request_redemption(env: Env, amount: u128) -> Result<u64, ReserveError> {
-        // Check minimum amount ($1M equivalent in smallest units)
-        if amount < 1_000_000_000_000 {
-            // Assuming 12 decimals
-            return Err(ReserveError::RedemptionAmountTooSmall);
-        }
+    /// Get the current compliance status and report freshness
+    pub fn get_compliance_summary(env: Env) -> Result<ComplianceSummary, ReserveError> {
+        regulatory_reporting::get_compliance_summary(env.clone())
+    }
 
-        redemption::request_redemption(env.clone(), env.invoker(), amount)
+    /// Export a regulatory report as XDR
+    pub fn export_regulatory_report(env: Env, report_id: u64) -> Result<Bytes, ReserveError> {
+        regulatory_reporting::export_report_data(env.clone(), report_id)
     }
 
-    /// Process approved redemption
-    pub fn process_redemption(env: Env, request_id: u64) -> Result<(), ReserveError> {
-        // Check governance authorization
-        if !shared::governance::has_role(env.clone(), env.invoker(), GovernanceRole::Executor) {
-            return Err(ReserveError::Unauthorized);
-        }
+    /// Register a custodian and the ed25519 keys that sign its balance reports
+    pub fn register_custodian(
+        env: Env,
+        admin: Address,
+        custodian: Address,
+        name: Symbol,
+        verification_method: VerificationMethod,
+        signing_keys: Vec<BytesN<32>>,
+        threshold: u32,
+    ) -> Result<(), ReserveError> {
+        custodian_integration::register_custodian(
+            env.clone(),
+            admin,
+            custodian,
+            name,
+            verification_method,
+            signing_keys,
+            threshold,
+        )
+    }
+
+    /// Submit a custodian's signed balance attestation for one reserve asset
+    pub fn submit_custodian_attestation(
+        env: Env,
+        custodian: Address,
+        asset_type: AssetType,
+        amount: u128,
+        nonce: u64,
+        timestamp: u64,
+        signatures: Vec<AttestationSignature>,
+    ) -> Result<(), ReserveError> {
+        custodian_integration::submit_attestation(
+            env.clone(),
+            custodian,
+            asset_type,
+            amount,
+            nonce,
+            timestamp,
+            signatures,
+        )
+    }
+
+    /// Flag custodians without a fresh attestation; returns the stale ones
+    pub fn check_stale_attestations(env: Env) -> Result<Vec<Address>, ReserveError> {
+        custodian_integration::check_stale_attestations(env.clone())
+    }
 
-        redemption::process_redemption(env.clone(), request_id)
+    /// Set how old an attestation may be before it is stale (seconds)
+    pub fn set_attestation_max_age(
+        env: Env,
+        admin: Address,
+        max_age: u64,
+    ) -> Result<(), ReserveError> {
+        custodian_integration::set_attestation_max_age(env.clone(), admin, max_age)
+    }
+
+    /// Stop accepting attestations from a custodian
+    pub fn deactivate_custodian(
+        env: Env,
+        admin: Address,
+        custodian: Address,
+    ) -> Result<(), ReserveError> {
+        custodian_integration::deactivate_custodian(env.clone(), admin, custodian)
+    }
+
+    /// Get a registered custodian
+    pub fn get_custodian_info(env: Env, custodian: Address) -> Result<CustodianInfo, ReserveError> {
+        custodian_integration::get_custodian_info(env.clone(), custodian)
+    }
+
+    /// Get every registered custodian
+    pub fn get_all_custodians(env: Env) -> Result<Vec<CustodianInfo>, ReserveError> {
+        custodian_integration::get_all_custodians(env.clone())
+    }
+
+    /// Get accepted attestations, optionally for one custodian
+    pub fn get_sync_history(
+        env: Env,
+        custodian: Option<Address>,
+    ) -> Result<Vec<SyncOperation>, ReserveError> {
+        custodian_integration::get_sync_history(env.clone(), custodian)
+    }
+
+    /// Request redemption for large holders ($1M+). The stablecoins are
+    /// escrowed until the request is processed or rejected.
+    pub fn request_redemption(
+        env: Env,
+        requester: Address,
+        amount: u128,
+        payout_asset: AssetType,
+    ) -> Result<u64, ReserveError> {
+        redemption::request_redemption(env.clone(), requester, amount, payout_asset)
+    }
+
+    /// Approve a pending redemption for processing
+    pub fn approve_redemption(
+        env: Env,
+        admin: Address,
+        request_id: u64,
+    ) -> Result<(), ReserveError> {
+        redemption::approve_redemption(env.clone(), admin, request_id)
+    }
+
+    /// Reject a redemption and refund the escrowed stablecoins
+    pub fn reject_redemption(
+        env: Env,
+        admin: Address,
+        request_id: u64,
+        reason: Symbol,
+    ) -> Result<(), ReserveError> {
+        redemption::reject_redemption(env.clone(), admin, request_id, reason)
+    }
+
+    /// Process approved redemption: burn the escrow and pay out the reserve asset
+    pub fn process_redemption(
+        env: Env,
+        executor: Address,
+        request_id: u64,
+    ) -> Result<(), ReserveError> {
+        redemption::process_redemption(env.clone(), executor, request_id)
+    }
+
+    /// Update the redemption limits, delay and emergency pause
+    pub fn update_redemption_config(
+        env: Env,
+        admin: Address,
+        large_holder_threshold: u128,
+        processing_delay: u64,
+        max_daily_redemption: u128,
+        emergency_pause: bool,
+    ) -> Result<(), ReserveError> {
+        redemption::update_redemption_config(
+            env.clone(),
+            admin,
+            large_holder_threshold,
+            processing_delay,
+            max_daily_redemption,
+            emergency_pause,
+        )
     }
 
     /// Get current reserve snapshot
@@ -282,50 +578,67 @@ impl StablecoinReserveContract {
         redemption::get_pending_redemptions(env.clone())
     }
 
-    /// Governance functions
+    /// Get the queue of escrowed, not yet settled redemptions
+    pub fn get_redemption_queue(env: Env) -> Result<RedemptionQueue, ReserveError> {
+        redemption::get_redemption_queue(env.clone())
+    }
+
+    // ── Governance upgrade ────────────────────────────────────────────────────
+
     pub fn propose_upgrade(
         env: Env,
-        new_contract_hash: BytesN<32>,
+        admin: Address,
+        new_contract_hash: Symbol,
         description: Symbol,
         approvers: Vec<Address>,
         approval_threshold: u32,
         timelock_delay: u64,
     ) -> Result<u64, ReserveError> {
-        if !shared::governance::has_role(env.clone(), env.invoker(), GovernanceRole::Admin) {
-            return Err(ReserveError::GovernanceError);
-        }
-
-        let proposal = UpgradeProposal {
-            id: env.ledger().sequence(),
-            proposer: env.invoker(),
-            new_contract_hash: symbol_short!("new_hash"), // Convert bytes to symbol
-            target_contract: env.current_contract_address(),
+        require_role(&env, &admin, GovernanceRole::Admin)
+            .map_err(|_| ReserveError::GovernanceError)?;
+
+        GovernanceManager::propose_upgrade(
+            &env,
+            admin,
+            new_contract_hash,
+            env.current_contract_address(),
             description,
             approval_threshold,
             approvers,
-            approvals_count: 0,
-            status: ProposalStatus::Pending,
-            created_at: env.ledger().timestamp(),
-            execution_time: env.ledger().timestamp() + timelock_delay,
-            executed: false,
-        };
-
-        shared::governance::create_proposal(env.clone(), proposal)
+            timelock_delay,
+        )
+        .map_err(|_| ReserveError::GovernanceError)
     }
 
-    pub fn approve_upgrade(env: Env, proposal_id: u64) -> Result<(), ReserveError> {
-        if !shared::governance::has_role(env.clone(), env.invoker(), GovernanceRole::Approver) {
-            return Err(ReserveError::GovernanceError);
-        }
+    pub fn approve_upgrade(
+        env: Env,
+        proposal_id: u64,
+        approver: Address,
+    ) -> Result<(), ReserveError> {
+        require_role(&env, &approver, GovernanceRole::Approver)
+            .map_err(|_| ReserveError::GovernanceError)?;
 
-        shared::governance::approve_proposal(env.clone(), proposal_id)
+        GovernanceManager::approve_proposal(&env, proposal_id, approver)
+            .map_err(|_| ReserveError::GovernanceError)
     }
 
-    pub fn execute_upgrade(env: Env, proposal_id: u64) -> Result<(), ReserveError> {
-        if !shared::governance::has_role(env.clone(), env.invoker(), GovernanceRole::Executor) {
-            return Err(ReserveError::GovernanceError);
-        }
+    pub fn execute_upgrade(
+        env: Env,
+        proposal_id: u64,
+        executor: Address,
+    ) -> Result<(), ReserveError> {
+        require_role(&env, &executor, GovernanceRole::Executor)
+            .map_err(|_| ReserveError::GovernanceError)?;
 
-        shared::governance::execute_proposal(env.clone(), proposal_id)
+        GovernanceManager::execute_proposal(&env, proposal_id, executor)
+            .map_err(|_| ReserveError::GovernanceError)
     }
-}
\ No newline at end of file
+
+    pub fn get_upgrade_proposal(
+        env: Env,
+        proposal_id: u64,
+    ) -> Result<UpgradeProposal, ReserveError> {
+        GovernanceManager::get_proposal(&env, proposal_id)
+            .map_err(|_| ReserveError::GovernanceError)
+    }
+}
@@ -1,51 +1,183 @@
-use crate::{ReserveAsset, ReserveError};
-use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Map, Symbol, Vec};
-
-const MERKLE_TREES: Symbol = symbol_short!("merkle_trees");
-const PROOF_GENERATION_TIME: Symbol = symbol_short!("proof_time");
+//! Holder-balance proof of reserves.
+//!
+//! The holder tree is built off-chain from the stablecoin ledger and
+//! published in two steps:
+//!
+//! 1. `commit_proof` records a hash of the tree and pins the snapshot: the
+//!    ledger and the stablecoin's on-chain `total_supply` at that point.
+//! 2. `reveal_proof` opens the commitment in a later ledger. The revealed
+//!    liabilities must equal the supply pinned at commit, so the tree cannot
+//!    understate what is owed; a holder left out finds no leaf for their
+//!    balance in `verify_inclusion`.
+//!
+//! A commitment not revealed within `REVEAL_WINDOW` may be replaced, and the
+//! one-proof-a-day limit runs from the last reveal. Per-holder balances are
+//! trusted to the builder: the contract cannot read balances at a past
+//! ledger, so holders check their own leaf against their snapshot balance.
+//!
+//! Leaves are `keccak256(0x00 || xdr((holder, balance)))` and parents are
+//! `keccak256(0x01 || left || right)`, so a parent can never pass for a
+//! leaf. The last node of an odd level is paired with itself, which makes
+//! every proof as long as the tree is deep.
+
+use crate::ReserveError;
+use shared::governance::GovernanceRole;
+use soroban_sdk::{
+    contracttype, symbol_short, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Vec,
+};
+
+const COMMITMENT: Symbol = symbol_short!("por_commt");
+const PUBLISHED_PROOF: Symbol = symbol_short!("por_proof");
+const PROOF_GENERATION_TIME: Symbol = symbol_short!("proof_tm");
+const STABLECOIN: Symbol = symbol_short!("stblcoin");
+
+/// Time a builder has to reveal a committed tree (1 hour)
+const REVEAL_WINDOW: u64 = 60 * 60;
+
+/// Domain prefixes for leaf and parent hashes
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// A committed, not yet revealed, holder tree
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofCommitment {
+    pub builder: Address,
+    pub commitment: BytesN<32>, // sha256(xdr((root, total_liabilities, leaf_count, salt)))
+    pub snapshot_ledger: u32,
+    pub snapshot_time: u64,
+    pub total_supply: u128, // stablecoin supply at the snapshot
+}
 
-pub struct MerkleProof {
-    pub leaf: BytesN<32>,
-    pub proof: Vec<BytesN<32>>,
-    pub leaf_index: u32,
+/// The latest revealed holder tree
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PublishedProof {
     pub root: BytesN<32>,
+    pub total_liabilities: u128,
+    pub leaf_count: u32,
+    pub snapshot_ledger: u32,
+    pub snapshot_time: u64,
+    pub total_reserves: u128, // reserves recorded when the proof was revealed
+    pub published_at: u64,
 }
 
-pub fn generate_daily_proof(env: Env) -> Result<BytesN<32>, ReserveError> {
+/// Commit to a holder tree for the current ledger on behalf of `builder`,
+/// an admin who alone may reveal it. One published proof per day; a
+/// commitment whose reveal window lapsed may be replaced.
+pub fn commit_proof(
+    env: Env,
+    builder: Address,
+    commitment: BytesN<32>,
+) -> Result<(), ReserveError> {
+    crate::require_role(&env, &builder, GovernanceRole::Admin)?;
     let now = env.ledger().timestamp();
 
-    // Check if proof was already generated today
-    if let Some(last_generation) = env.storage().instance().get(&PROOF_GENERATION_TIME) {
+    // A commitment still open for reveal cannot be swapped for another tree
+    if let Some(pending) = get_pending_commitment(env.clone()) {
+        if now <= pending.snapshot_time + REVEAL_WINDOW {
+            return Err(ReserveError::InvalidMerkleProof);
+        }
+    }
+
+    // Check if proof was already published today
+    if let Some(last_generation) = env
+        .storage()
+        .instance()
+        .get::<_, u64>(&PROOF_GENERATION_TIME)
+    {
         let days_since_last = (now - last_generation) / (24 * 60 * 60);
         if days_since_last == 0 {
             return Err(ReserveError::InvalidMerkleProof);
         }
     }
 
-    // Get current reserve assets
-    let assets = env
+    let total_supply = stablecoin_total_supply(&env)?;
+    let pending = ProofCommitment {
+        builder,
+        commitment: commitment.clone(),
+        snapshot_ledger: env.ledger().sequence(),
+        snapshot_time: now,
+        total_supply,
+    };
+    env.storage().instance().set(&COMMITMENT, &pending);
+
+    env.events().publish(
+        (symbol_short!("proof"), symbol_short!("committed")),
+        (commitment, pending.snapshot_ledger, total_supply),
+    );
+
+    Ok(())
+}
+
+/// Open the pending commitment and publish its tree. Liabilities must match
+/// the stablecoin supply pinned at commit; reserves' liabilities are updated
+/// to it.
+pub fn reveal_proof(
+    env: Env,
+    builder: Address,
+    root: BytesN<32>,
+    total_liabilities: u128,
+    leaf_count: u32,
+    salt: BytesN<32>,
+) -> Result<BytesN<32>, ReserveError> {
+    builder.require_auth();
+    let now = env.ledger().timestamp();
+
+    let pending: ProofCommitment = env
         .storage()
         .instance()
-        .get(&symbol_short!("reserve_assets"))
-        .ok_or(ReserveError::InvalidAsset)?;
+        .get(&COMMITMENT)
+        .ok_or(ReserveError::InvalidMerkleProof)?;
+    if pending.builder != builder {
+        return Err(ReserveError::Unauthorized);
+    }
 
-    // Generate Merkle tree from all holders and their balances
-    let holder_balances = get_all_holder_balances(env.clone())?;
-    let merkle_root = generate_merkle_tree(env.clone(), &holder_balances)?;
+    // The reveal must land after the commit, and before the window closes
+    if env.ledger().sequence() <= pending.snapshot_ledger
+        || now > pending.snapshot_time + REVEAL_WINDOW
+    {
+        return Err(ReserveError::InvalidMerkleProof);
+    }
 
-    // Store the Merkle tree data
-    env.storage().instance().set(&MERKLE_TREES, &merkle_root);
+    let opened = (root.clone(), total_liabilities, leaf_count, salt).to_xdr(&env);
+    let expected: BytesN<32> = env.crypto().sha256(&opened).into();
+    if expected != pending.commitment {
+        return Err(ReserveError::InvalidMerkleProof);
+    }
+    if total_liabilities != pending.total_supply {
+        return Err(ReserveError::LiabilitiesMismatch);
+    }
+
+    crate::reserve_tracking::update_total_supply(env.clone(), total_liabilities)?;
+
+    let proof = PublishedProof {
+        root: root.clone(),
+        total_liabilities,
+        leaf_count,
+        snapshot_ledger: pending.snapshot_ledger,
+        snapshot_time: pending.snapshot_time,
+        total_reserves: crate::reserve_tracking::get_total_reserves(env.clone())?,
+        published_at: now,
+    };
+    env.storage().instance().set(&PUBLISHED_PROOF, &proof);
     env.storage().instance().set(&PROOF_GENERATION_TIME, &now);
+    env.storage().instance().remove(&COMMITMENT);
 
     // Log proof generation
     env.events().publish(
         (symbol_short!("proof"), symbol_short!("generated")),
-        (merkle_root, now, holder_balances.len()),
+        (root.clone(), pending.snapshot_ledger, total_liabilities, leaf_count),
     );
 
-    Ok(merkle_root)
+    Ok(root)
 }
 
+/// Check that the latest published tree has a leaf for `user` holding
+/// `amount`. The root pins every leaf, so the user's balance now does not
+/// matter, but the leaves themselves are the builder's: only the declared
+/// total is checked on-chain, against the supply. Fails if the proof is not
+/// exactly as long as the tree is deep.
 pub fn verify_inclusion(
     env: Env,
     user: Address,
@@ -53,185 +185,93 @@ pub fn verify_inclusion(
     proof: Vec<BytesN<32>>,
     leaf_index: u32,
 ) -> Result<bool, ReserveError> {
-    // Get current Merkle root
-    let current_root = env
+    let published: PublishedProof = env
         .storage()
         .instance()
-        .get(&MERKLE_TREES)
+        .get(&PUBLISHED_PROOF)
         .ok_or(ReserveError::InvalidMerkleProof)?;
+    if leaf_index >= published.leaf_count || proof.len() != tree_depth(published.leaf_count) {
+        return Err(ReserveError::InvalidMerkleProof);
+    }
 
-    // Generate leaf hash for user
-    let leaf_data = (user, amount);
-    let leaf_hash = env.crypto().keccak256(&leaf_data.to_xdr());
-
-    // Verify Merkle proof
-    let computed_root = verify_merkle_proof(env.clone(), leaf_hash, proof, leaf_index)?;
+    let computed_root =
+        verify_merkle_proof(&env, leaf_hash(&env, &user, amount), proof, leaf_index);
+    Ok(computed_root == published.root)
+}
 
-    Ok(computed_root == current_root)
+pub fn get_published_proof(env: Env) -> Result<PublishedProof, ReserveError> {
+    env.storage()
+        .instance()
+        .get(&PUBLISHED_PROOF)
+        .ok_or(ReserveError::InvalidMerkleProof)
 }
 
-fn get_all_holder_balances(env: Env) -> Result<Vec<(Address, u128)>, ReserveError> {
-    // In a real implementation, this would query the stablecoin contract
-    // for all holder balances. For now, we'll return a simplified version.
+pub fn get_pending_commitment(env: Env) -> Option<ProofCommitment> {
+    env.storage().instance().get(&COMMITMENT)
+}
 
-    let stablecoin_address = env
-        .storage()
+/// The stablecoin token this reserve backs
+pub fn stablecoin(env: &Env) -> Result<Address, ReserveError> {
+    env.storage()
         .instance()
-        .get(&symbol_short!("stablecoin"))
-        .ok_or(ReserveError::InvalidAsset)?;
-
-    // This would typically involve querying the token contract
-    // For demonstration, we'll return empty data
-    Ok(Vec::new(&env))
+        .get(&STABLECOIN)
+        .ok_or(ReserveError::InvalidAsset)
 }
 
-fn generate_merkle_tree(
-    env: Env,
-    holder_balances: &Vec<(Address, u128)>,
-) -> Result<BytesN<32>, ReserveError> {
-    if holder_balances.is_empty() {
-        return Ok(BytesN::from_array(&env, &[0u8; 32]));
-    }
+/// The stablecoin's on-chain `total_supply`
+fn stablecoin_total_supply(env: &Env) -> Result<u128, ReserveError> {
+    let supply: i128 = env.invoke_contract(
+        &stablecoin(env)?,
+        &Symbol::new(env, "total_supply"),
+        Vec::new(env),
+    );
+    u128::try_from(supply).map_err(|_| ReserveError::InvalidAsset)
+}
 
-    // Generate leaf nodes
-    let mut leaves: Vec<BytesN<32>> = Vec::new(&env);
-    for (holder, balance) in holder_balances {
-        let leaf_data = (*holder, *balance);
-        let leaf_hash = env.crypto().keccak256(&leaf_data.to_xdr());
-        leaves.push_back(BytesN::from_array(&env, &leaf_hash));
+/// Levels above the leaves in a tree of `leaf_count` leaves
+fn tree_depth(leaf_count: u32) -> u32 {
+    let mut depth = 0;
+    let mut width = leaf_count;
+    while width > 1 {
+        width = width.div_ceil(2);
+        depth += 1;
     }
+    depth
+}
 
-    // Build Merkle tree
-    let mut current_level = leaves;
-    while current_level.len() > 1 {
-        let mut next_level: Vec<BytesN<32>> = Vec::new(&env);
-
-        for i in (0..current_level.len()).step_by(2) {
-            let left = current_level.get(i).unwrap();
-            let right = if i + 1 < current_level.len() {
-                current_level.get(i + 1).unwrap()
-            } else {
-                left // Duplicate last element if odd number
-            };
-
-            let combined = [left.to_array(), right.to_array()].concat();
-            let parent_hash = env.crypto().keccak256(&combined);
-            next_level.push_back(BytesN::from_array(&env, &parent_hash));
-        }
-
-        current_level = next_level;
-    }
+fn leaf_hash(env: &Env, holder: &Address, balance: u128) -> BytesN<32> {
+    let mut leaf_data = Bytes::from_array(env, &[LEAF_PREFIX]);
+    leaf_data.append(&(holder.clone(), balance).to_xdr(env));
+    env.crypto().keccak256(&leaf_data).into()
+}
 
-    Ok(current_level.get(0).unwrap())
+fn hash_pair(env: &Env, left: &BytesN<32>, right: &BytesN<32>) -> BytesN<32> {
+    let mut combined = Bytes::from_array(env, &[NODE_PREFIX]);
+    combined.append(&Bytes::from_array(env, &left.to_array()));
+    combined.append(&Bytes::from_array(env, &right.to_array()));
+    env.crypto().keccak256(&combined).into()
 }
 
 fn verify_merkle_proof(
-    env: Env,
-    leaf: [u8; 32],
+    env: &Env,
+    leaf: BytesN<32>,
     proof: Vec<BytesN<32>>,
     leaf_index: u32,
-) -> Result<BytesN<32>, ReserveError> {
+) -> BytesN<32> {
     let mut computed_hash = leaf;
     let mut index = leaf_index;
 
     for proof_element in proof.iter() {
-        if index % 2 == 0 {
+        computed_hash = if index.is_multiple_of(2) {
             // Current node is left child
-            let combined = [computed_hash, proof_element.to_array()].concat();
-            computed_hash = env.crypto().keccak256(&combined);
+            hash_pair(env, &computed_hash, &proof_element)
         } else {
             // Current node is right child
-            let combined = [proof_element.to_array(), computed_hash].concat();
-            computed_hash = env.crypto().keccak256(&combined);
-        }
-
-        index = index / 2;
-    }
-
-    Ok(BytesN::from_array(&env, &computed_hash))
-}
-
-pub fn generate_user_proof(
-    env: Env,
-    user: Address,
-    amount: u128,
-) -> Result<MerkleProof, ReserveError> {
-    let holder_balances = get_all_holder_balances(env.clone())?;
-
-    // Find user's position in the list
-    let mut leaf_index = None;
-    for (i, (holder, balance)) in holder_balances.iter().enumerate() {
-        if *holder == user && *balance == amount {
-            leaf_index = Some(i as u32);
-            break;
-        }
-    }
-
-    let leaf_index = leaf_index.ok_or(ReserveError::InvalidMerkleProof)?;
-
-    // Generate Merkle tree to get proof
-    let merkle_root = generate_merkle_tree(env.clone(), &holder_balances)?;
-    let proof = generate_proof_for_leaf(env.clone(), &holder_balances, leaf_index)?;
-
-    // Generate leaf hash
-    let leaf_data = (user, amount);
-    let leaf_hash = env.crypto().keccak256(&leaf_data.to_xdr());
-
-    Ok(MerkleProof {
-        leaf: BytesN::from_array(&env, &leaf_hash),
-        proof,
-        leaf_index,
-        root: merkle_root,
-    })
-}
-
-fn generate_proof_for_leaf(
-    env: Env,
-    holder_balances: &Vec<(Address, u128)>,
-    leaf_index: u32,
-) -> Result<Vec<BytesN<32>>, ReserveError> {
-    // Generate leaf nodes
-    let mut leaves: Vec<BytesN<32>> = Vec::new(&env);
-    for (holder, balance) in holder_balances {
-        let leaf_data = (*holder, *balance);
-        let leaf_hash = env.crypto().keccak256(&leaf_data.to_xdr());
-        leaves.push_back(BytesN::from_array(&env, &leaf_hash));
-    }
-
-    // Build Merkle tree and collect proof elements
-    let mut current_level = leaves;
-    let mut proof: Vec<BytesN<32>> = Vec::new(&env);
-    let mut index = leaf_index;
-
-    while current_level.len() > 1 {
-        if index % 2 == 0 {
-            // Current node is left child, add right sibling to proof
-            if index + 1 < current_level.len() as u32 {
-                proof.push_back(current_level.get((index + 1) as usize).unwrap());
-            }
-        } else {
-            // Current node is right child, add left sibling to proof
-            proof.push_back(current_level.get((index - 1) as usize).unwrap());
-        }
-
-        // Build next level
-        let mut next_level: Vec<BytesN<32>> = Vec::new(&env);
-        for i in (0..current_level.len()).step_by(2) {
-            let left = current_level.get(i).unwrap();
-            let right = if i + 1 < current_level.len() {
-                current_level.get(i + 1).unwrap()
-            } else {
-                left
-            };
-
-            let combined = [left.to_array(), right.to_array()].concat();
-            let parent_hash = env.crypto().keccak256(&combined);
-            next_level.push_back(BytesN::from_array(&env, &parent_hash));
-        }
+            hash_pair(env, &proof_element, &computed_hash)
+        };
 
-        current_level = next_level;
-        index = index / 2;
+        index /= 2;
     }
 
-    Ok(proof)
+    computed_hash
 }
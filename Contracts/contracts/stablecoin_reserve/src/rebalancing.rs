@@ -1,10 +1,29 @@
 use crate::{AssetType, ReserveAsset, ReserveError};
-use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
-
-const REBALANCING_THRESHOLD: Symbol = symbol_short!("rebal_threshold");
-const TARGET_ALLOCATIONS: Symbol = symbol_short!("target_alloc");
-const LAST_REBALANCING: Symbol = symbol_short!("last_rebal");
-const REBALANCING_HISTORY: Symbol = symbol_short!("rebal_history");
+use shared::governance::GovernanceRole;
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contracttype, symbol_short,
+    token::Client as TokenClient,
+    vec, Address, BytesN, Env, IntoVal, InvokeError, Map, Symbol, Val, Vec,
+};
+
+const REBALANCING_THRESHOLD: Symbol = symbol_short!("rebal_thr");
+const TARGET_ALLOCATIONS: Symbol = symbol_short!("tgt_alloc");
+const LAST_REBALANCING: Symbol = symbol_short!("last_rbal");
+const REBALANCING_HISTORY: Symbol = symbol_short!("rbal_hist");
+const ASSET_TOKENS: Symbol = symbol_short!("rebal_tok");
+const AMM_ROUTER: Symbol = symbol_short!("amm_rtr");
+const MAX_SLIPPAGE: Symbol = symbol_short!("max_slip");
+const REBALANCING_LEGS: Symbol = symbol_short!("rebal_leg");
+const PENDING_LEGS: Symbol = symbol_short!("leg_pend");
+const LEG_COUNTER: Symbol = symbol_short!("leg_count");
+const LEG_TOLERANCE: Symbol = symbol_short!("leg_tol");
+
+/// Default max slippage accepted on a rebalancing leg (0.5%)
+const DEFAULT_MAX_SLIPPAGE_BPS: u64 = 50;
+
+/// Default amount a custodian leg may settle above its `amount_in` (none)
+const DEFAULT_LEG_TOLERANCE_BPS: u64 = 0;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -15,6 +34,9 @@ pub struct TargetAllocation {
     pub max_percentage: u64,
 }
 
+/// A settled rebalancing leg. `old_amount`/`new_amount` are the sold
+/// asset's holdings before and after; `amount_out` is what actually arrived
+/// in `to_asset`.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RebalancingOperation {
@@ -23,6 +45,44 @@ pub struct RebalancingOperation {
     pub old_amount: u128,
     pub new_amount: u128,
     pub reason: Symbol,
+    pub to_asset: AssetType,
+    pub amount_in: u128,
+    pub amount_out: u128,
+    pub slippage_bps: u64, // shortfall of amount_out against amount_in
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum LegVenue {
+    Custodian = 0, // off-chain trade by the sold asset's custodian
+    Amm = 1,       // on-chain swap through the AMM router
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum LegStatus {
+    Pending = 0,
+    Settled = 1,
+    Failed = 2,
+}
+
+/// One instruction of a rebalancing plan: sell `amount_in` of `from_asset`
+/// for at least `min_amount_out` of `to_asset`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RebalancingLeg {
+    pub id: u64,
+    pub from_asset: AssetType,
+    pub to_asset: AssetType,
+    pub amount_in: u128,
+    pub min_amount_out: u128,
+    pub amount_out: u128, // what settled; 0 until then
+    pub venue: LegVenue,
+    pub custodian: Address, // custodian expected to settle a Custodian leg
+    pub status: LegStatus,
+    pub created_at: u64,
 }
 
 pub fn initialize(env: Env, threshold: u64) {
@@ -70,10 +130,10 @@ pub fn initialize(env: Env, threshold: u64) {
 }
 
 pub fn check_rebalancing_needed(env: Env) -> Result<bool, ReserveError> {
-    let assets = env
+    let assets: Vec<ReserveAsset> = env
         .storage()
         .instance()
-        .get(&symbol_short!("reserve_assets"))
+        .get(&symbol_short!("rsv_asset"))
         .ok_or(ReserveError::InvalidAsset)?;
 
     let threshold = env
@@ -87,7 +147,7 @@ pub fn check_rebalancing_needed(env: Env) -> Result<bool, ReserveError> {
         return Ok(false);
     }
 
-    let target_allocations = env
+    let target_allocations: Vec<TargetAllocation> = env
         .storage()
         .instance()
         .get(&TARGET_ALLOCATIONS)
@@ -96,13 +156,9 @@ pub fn check_rebalancing_needed(env: Env) -> Result<bool, ReserveError> {
     // Check each asset type's allocation
     for target in target_allocations.iter() {
         let current_amount = get_asset_amount_by_type(&assets, target.asset_type);
-        let current_percentage = (current_amount * 10000) / total_reserves;
+        let current_percentage = ((current_amount * 10000) / total_reserves) as u64;
 
-        let deviation = if current_percentage > target.target_percentage {
-            current_percentage - target.target_percentage
-        } else {
-            target.target_percentage - current_percentage
-        };
+        let deviation = current_percentage.abs_diff(target.target_percentage);
 
         if deviation > threshold {
             return Ok(true);
@@ -112,6 +168,10 @@ pub fn check_rebalancing_needed(env: Env) -> Result<bool, ReserveError> {
     Ok(false)
 }
 
+/// Build a plan of legs moving value from over-weight to under-weight asset
+/// types, swap the legs whose both sides are tokens through the AMM router,
+/// and leave the rest pending for custodians to settle. Holdings only move by
+/// what actually settled.
 pub fn execute_rebalancing(env: Env) -> Result<(), ReserveError> {
     let now = env.ledger().timestamp();
 
@@ -120,10 +180,15 @@ pub fn execute_rebalancing(env: Env) -> Result<(), ReserveError> {
         return Err(ReserveError::RebalancingRequired);
     }
 
-    let mut assets = env
+    // A new plan would double up on trades custodians are still executing
+    if !get_pending_legs(env.clone()).is_empty() {
+        return Err(ReserveError::RebalancingPending);
+    }
+
+    let mut assets: Vec<ReserveAsset> = env
         .storage()
         .instance()
-        .get(&symbol_short!("reserve_assets"))
+        .get(&symbol_short!("rsv_asset"))
         .ok_or(ReserveError::InvalidAsset)?;
 
     let total_reserves: u128 = assets.iter().map(|asset| asset.amount).sum();
@@ -131,90 +196,275 @@ pub fn execute_rebalancing(env: Env) -> Result<(), ReserveError> {
         return Err(ReserveError::InsufficientReserves);
     }
 
-    let target_allocations = env
+    let target_allocations: Vec<TargetAllocation> = env
         .storage()
         .instance()
         .get(&TARGET_ALLOCATIONS)
         .unwrap_or(Vec::new(&env));
 
-    let mut rebalancing_operations: Vec<RebalancingOperation> = Vec::new(&env);
-
-    // Calculate required adjustments for each asset type
+    // Split the drift into surpluses to sell and deficits to buy
+    let mut surpluses: Vec<(AssetType, u128)> = Vec::new(&env);
+    let mut deficits: Vec<(AssetType, u128)> = Vec::new(&env);
     for target in target_allocations.iter() {
         let current_amount = get_asset_amount_by_type(&assets, target.asset_type);
-        let current_percentage = (current_amount * 10000) / total_reserves;
+        let target_amount = (total_reserves * target.target_percentage as u128) / 10000;
 
-        let target_amount = (total_reserves * target.target_percentage) / 10000;
+        if current_amount > target_amount {
+            surpluses.push_back((target.asset_type, current_amount - target_amount));
+        } else if target_amount > current_amount {
+            deficits.push_back((target.asset_type, target_amount - current_amount));
+        }
+    }
 
-        if current_amount != target_amount {
-            let operation = RebalancingOperation {
-                timestamp: now,
-                asset_type: target.asset_type,
-                old_amount: current_amount,
-                new_amount: target_amount,
-                reason: symbol_short!("rebalancing"),
+    let max_slippage = get_max_slippage(env.clone());
+    let mut rebalancing_operations: Vec<RebalancingOperation> = Vec::new(&env);
+    let mut pending: Vec<u64> = Vec::new(&env);
+    let mut deficit_index = 0;
+
+    // Pair surpluses with deficits greedily, in target-allocation order
+    for (from_asset, mut surplus) in surpluses.iter() {
+        while surplus > 0 && deficit_index < deficits.len() {
+            let (to_asset, deficit) = deficits.get(deficit_index).unwrap();
+            let amount_in = surplus.min(deficit);
+            surplus -= amount_in;
+            if amount_in == deficit {
+                deficit_index += 1;
+            } else {
+                deficits.set(deficit_index, (to_asset, deficit - amount_in));
+            }
+
+            let mut leg = RebalancingLeg {
+                id: next_leg_id(env.clone()),
+                from_asset,
+                to_asset,
+                amount_in,
+                min_amount_out: amount_in * (10000 - max_slippage as u128) / 10000,
+                amount_out: 0,
+                venue: LegVenue::Custodian,
+                custodian: get_asset_custodian(&env, &assets, from_asset),
+                status: LegStatus::Pending,
+                created_at: now,
             };
 
-            rebalancing_operations.push_back(operation);
-
-            // Update asset amounts (in production, this would involve actual trades)
-            update_asset_amount_by_type(&mut assets, target.asset_type, target_amount);
+            if let Some((token_in, token_out, router)) = amm_route(&env, from_asset, to_asset) {
+                leg.venue = LegVenue::Amm;
+                leg.custodian = env.current_contract_address();
+                match swap_leg(&env, &router, &token_in, &token_out, &leg) {
+                    Some(amount_out) => {
+                        leg.amount_out = amount_out;
+                        leg.status = LegStatus::Settled;
+                        rebalancing_operations.push_back(settle_leg(
+                            &env,
+                            &mut assets,
+                            &leg,
+                            symbol_short!("amm"),
+                        )?);
+                    }
+                    None => leg.status = LegStatus::Failed,
+                }
+            }
+
+            env.events().publish(
+                (symbol_short!("rebal"), symbol_short!("leg")),
+                (leg.id, leg.from_asset, leg.to_asset, leg.amount_in, leg.venue, leg.status),
+            );
+            if leg.status == LegStatus::Pending {
+                pending.push_back(leg.id);
+            }
+            store_leg(&env, &leg);
         }
     }
 
+    env.storage().instance().set(&PENDING_LEGS, &pending);
+
     // Store updated assets
     env.storage()
         .instance()
-        .set(&symbol_short!("reserve_assets"), &assets);
+        .set(&symbol_short!("rsv_asset"), &assets);
 
-    // Update rebalancing history
-    let mut history = env
+    record_operations(env.clone(), &rebalancing_operations);
+    env.storage().instance().set(&LAST_REBALANCING, &now);
+
+    // Log rebalancing
+    env.events().publish(
+        (symbol_short!("rebal"), symbol_short!("executed")),
+        (now, rebalancing_operations.len()),
+    );
+
+    // Update reserve snapshot
+    crate::reserve_tracking::update_snapshot(env.clone())?;
+
+    Ok(())
+}
+
+/// Record what a custodian actually delivered for a pending off-chain leg.
+/// Fails with `SlippageExceeded` below the leg's `min_amount_out` or above
+/// its `amount_in` plus the configured tolerance: the custodian selling the
+/// asset reports the fill, so it must not be able to credit more value than
+/// the leg took out.
+pub fn settle_custodian_leg(
+    env: Env,
+    custodian: Address,
+    leg_id: u64,
+    amount_out: u128,
+) -> Result<(), ReserveError> {
+    custodian.require_auth();
+
+    let mut leg = get_leg(&env, leg_id).ok_or(ReserveError::InvalidAsset)?;
+    if leg.status != LegStatus::Pending || leg.venue != LegVenue::Custodian {
+        return Err(ReserveError::InvalidAsset);
+    }
+    if leg.custodian != custodian {
+        return Err(ReserveError::Unauthorized);
+    }
+    let max_amount_out =
+        leg.amount_in + leg.amount_in * get_leg_tolerance(&env) as u128 / 10000;
+    if amount_out < leg.min_amount_out || amount_out > max_amount_out {
+        return Err(ReserveError::SlippageExceeded);
+    }
+
+    let mut assets: Vec<ReserveAsset> = env
         .storage()
         .instance()
-        .get(&REBALANCING_HISTORY)
-        .unwrap_or(Vec::new(&env));
+        .get(&symbol_short!("rsv_asset"))
+        .ok_or(ReserveError::InvalidAsset)?;
 
-    for operation in rebalancing_operations.iter() {
-        history.push_back(operation.clone());
+    leg.amount_out = amount_out;
+    leg.status = LegStatus::Settled;
+    let operation = settle_leg(&env, &mut assets, &leg, symbol_short!("custody"))?;
+
+    store_leg(&env, &leg);
+    remove_pending_leg(&env, leg_id);
+    env.storage()
+        .instance()
+        .set(&symbol_short!("rsv_asset"), &assets);
+    record_operations(env.clone(), &vec![&env, operation.clone()]);
+
+    env.events().publish(
+        (symbol_short!("rebal"), symbol_short!("leg_settl")),
+        (leg_id, custodian, amount_out, operation.slippage_bps),
+    );
+
+    crate::reserve_tracking::update_snapshot(env.clone())
+}
+
+/// Drop a pending custodian leg that will not settle, unblocking the next
+/// rebalancing.
+pub fn cancel_leg(env: Env, admin: Address, leg_id: u64) -> Result<(), ReserveError> {
+    crate::require_role(&env, &admin, GovernanceRole::Admin)?;
+
+    let mut leg = get_leg(&env, leg_id).ok_or(ReserveError::InvalidAsset)?;
+    if leg.status != LegStatus::Pending {
+        return Err(ReserveError::InvalidAsset);
     }
+    leg.status = LegStatus::Failed;
+    store_leg(&env, &leg);
+    remove_pending_leg(&env, leg_id);
 
-    // Keep only last 100 rebalancing operations
-    while history.len() > 100 {
-        history.pop_front();
+    env.events().publish(
+        (symbol_short!("rebal"), symbol_short!("leg_cancl")),
+        leg_id,
+    );
+    Ok(())
+}
+
+/// Register the token an asset type is held in on-chain. Legs between two
+/// token-backed types swap through the AMM router; token units are taken to
+/// track reserve value one-to-one.
+pub fn set_asset_token(
+    env: Env,
+    admin: Address,
+    asset_type: AssetType,
+    token: Address,
+) -> Result<(), ReserveError> {
+    crate::require_role(&env, &admin, GovernanceRole::Admin)?;
+
+    let mut tokens = env
+        .storage()
+        .instance()
+        .get(&ASSET_TOKENS)
+        .unwrap_or(Map::<AssetType, Address>::new(&env));
+    tokens.set(asset_type, token.clone());
+    env.storage().instance().set(&ASSET_TOKENS, &tokens);
+
+    env.events().publish(
+        (symbol_short!("rebal"), symbol_short!("token")),
+        (asset_type, token),
+    );
+    Ok(())
+}
+
+/// Set the AMM router used for token legs and the slippage it may take
+pub fn set_amm_router(
+    env: Env,
+    admin: Address,
+    router: Address,
+    max_slippage_bps: u64,
+) -> Result<(), ReserveError> {
+    crate::require_role(&env, &admin, GovernanceRole::Admin)?;
+    if max_slippage_bps > 10000 {
+        return Err(ReserveError::SlippageExceeded);
     }
 
-    env.storage().instance().set(&REBALANCING_HISTORY, &history);
-    env.storage().instance().set(&LAST_REBALANCING, &now);
+    env.storage().instance().set(&AMM_ROUTER, &router);
+    env.storage().instance().set(&MAX_SLIPPAGE, &max_slippage_bps);
 
-    // Log rebalancing
     env.events().publish(
-        (symbol_short!("rebalancing"), symbol_short!("executed")),
-        (now, rebalancing_operations.len()),
+        (symbol_short!("rebal"), symbol_short!("router")),
+        (router, max_slippage_bps),
     );
+    Ok(())
+}
 
-    // Update reserve snapshot
-    crate::reserve_tracking::update_snapshot(env.clone())?;
+/// Set how far above `amount_in` (bps) a custodian may settle a leg
+pub fn set_leg_tolerance(env: Env, admin: Address, tolerance_bps: u64) -> Result<(), ReserveError> {
+    crate::require_role(&env, &admin, GovernanceRole::Admin)?;
+    if tolerance_bps > 10000 {
+        return Err(ReserveError::SlippageExceeded);
+    }
+
+    env.storage().instance().set(&LEG_TOLERANCE, &tolerance_bps);
 
+    env.events().publish(
+        (symbol_short!("rebal"), symbol_short!("leg_tol")),
+        tolerance_bps,
+    );
     Ok(())
 }
 
+/// The token an asset type is held in on-chain, if registered
+pub fn get_asset_token(env: &Env, asset_type: AssetType) -> Option<Address> {
+    let tokens: Map<AssetType, Address> = env.storage().instance().get(&ASSET_TOKENS)?;
+    tokens.get(asset_type)
+}
+
+/// A rebalancing leg by id, whatever its status
+pub fn get_leg(env: &Env, leg_id: u64) -> Option<RebalancingLeg> {
+    env.storage().persistent().get(&(REBALANCING_LEGS, leg_id))
+}
+
+pub fn get_pending_legs(env: Env) -> Vec<RebalancingLeg> {
+    let mut pending: Vec<RebalancingLeg> = Vec::new(&env);
+    for leg_id in pending_leg_ids(&env).iter() {
+        if let Some(leg) = get_leg(&env, leg_id) {
+            pending.push_back(leg);
+        }
+    }
+    pending
+}
+
 pub fn update_target_allocation(
     env: Env,
+    admin: Address,
     asset_type: AssetType,
     target_percentage: u64,
     min_percentage: u64,
     max_percentage: u64,
 ) -> Result<(), ReserveError> {
     // Check governance authorization
-    if !shared::governance::has_role(
-        env.clone(),
-        env.invoker(),
-        shared::governance::GovernanceRole::Admin,
-    ) {
-        return Err(ReserveError::Unauthorized);
-    }
+    crate::require_role(&env, &admin, GovernanceRole::Admin)?;
 
-    let mut allocations = env
+    let mut allocations: Vec<TargetAllocation> = env
         .storage()
         .instance()
         .get(&TARGET_ALLOCATIONS)
@@ -254,7 +504,7 @@ pub fn update_target_allocation(
 
     // Log allocation update
     env.events().publish(
-        (symbol_short!("allocation"), symbol_short!("updated")),
+        (symbol_short!("alloc"), symbol_short!("updated")),
         (asset_type, target_percentage),
     );
 
@@ -275,6 +525,176 @@ pub fn get_target_allocations(env: Env) -> Result<Vec<TargetAllocation>, Reserve
         .ok_or(ReserveError::InvalidAsset)
 }
 
+/// Legs are kept per id; only the ids still awaiting a custodian stay in
+/// instance storage
+fn store_leg(env: &Env, leg: &RebalancingLeg) {
+    env.storage()
+        .persistent()
+        .set(&(REBALANCING_LEGS, leg.id), leg);
+}
+
+fn pending_leg_ids(env: &Env) -> Vec<u64> {
+    env.storage()
+        .instance()
+        .get(&PENDING_LEGS)
+        .unwrap_or(Vec::new(env))
+}
+
+fn remove_pending_leg(env: &Env, leg_id: u64) {
+    let mut pending = pending_leg_ids(env);
+    if let Some(position) = pending.first_index_of(leg_id) {
+        pending.remove(position);
+        env.storage().instance().set(&PENDING_LEGS, &pending);
+    }
+}
+
+fn next_leg_id(env: Env) -> u64 {
+    let next_id = env.storage().instance().get(&LEG_COUNTER).unwrap_or(0u64) + 1;
+    env.storage().instance().set(&LEG_COUNTER, &next_id);
+    next_id
+}
+
+fn get_leg_tolerance(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&LEG_TOLERANCE)
+        .unwrap_or(DEFAULT_LEG_TOLERANCE_BPS)
+}
+
+fn get_max_slippage(env: Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&MAX_SLIPPAGE)
+        .unwrap_or(DEFAULT_MAX_SLIPPAGE_BPS)
+}
+
+/// Tokens for both sides of a leg and the router, if it can settle on-chain
+fn amm_route(
+    env: &Env,
+    from_asset: AssetType,
+    to_asset: AssetType,
+) -> Option<(Address, Address, Address)> {
+    let tokens: Map<AssetType, Address> = env.storage().instance().get(&ASSET_TOKENS)?;
+    let router: Address = env.storage().instance().get(&AMM_ROUTER)?;
+    Some((tokens.get(from_asset)?, tokens.get(to_asset)?, router))
+}
+
+/// Swap `leg.amount_in` of `token_in` for `token_out` through the router.
+/// Returns the amount received, measured from the contract's own balances,
+/// or `None` if the swap failed or returned less than `min_amount_out`.
+fn swap_leg(
+    env: &Env,
+    router: &Address,
+    token_in: &Address,
+    token_out: &Address,
+    leg: &RebalancingLeg,
+) -> Option<u128> {
+    let this = env.current_contract_address();
+    let amount_in = i128::try_from(leg.amount_in).ok()?;
+    let min_amount_out = i128::try_from(leg.min_amount_out).ok()?;
+    let tok_in = TokenClient::new(env, token_in);
+    let tok_out = TokenClient::new(env, token_out);
+    if tok_in.balance(&this) < amount_in {
+        return None;
+    }
+    let in_before = tok_in.balance(&this);
+    let out_before = tok_out.balance(&this);
+
+    // The router pulls token_in from this contract during the swap
+    env.authorize_as_current_contract(vec![
+        env,
+        InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: token_in.clone(),
+                fn_name: Symbol::new(env, "transfer"),
+                args: (this.clone(), router.clone(), amount_in).into_val(env),
+            },
+            sub_invocations: Vec::new(env),
+        }),
+    ]);
+
+    let args: Vec<Val> = (
+        this.clone(),
+        token_in.clone(),
+        token_out.clone(),
+        amount_in,
+        min_amount_out,
+        env.ledger().timestamp(),
+        Option::<i128>::None,
+    )
+        .into_val(env);
+    let result = env.try_invoke_contract::<Val, InvokeError>(router, &symbol_short!("swap"), args);
+    if !matches!(result, Ok(Ok(_))) {
+        return None;
+    }
+
+    // Trust balances, not the router's report, for what settled
+    let spent = in_before - tok_in.balance(&this);
+    let received = tok_out.balance(&this) - out_before;
+    if spent != amount_in || received < min_amount_out {
+        return None;
+    }
+    u128::try_from(received).ok()
+}
+
+/// Apply a settled leg to the holdings and describe it for the history.
+/// Fails if the holdings of `from_asset` no longer cover the leg.
+fn settle_leg(
+    env: &Env,
+    assets: &mut Vec<ReserveAsset>,
+    leg: &RebalancingLeg,
+    reason: Symbol,
+) -> Result<RebalancingOperation, ReserveError> {
+    let old_amount = get_asset_amount_by_type(assets, leg.from_asset);
+    debit_asset_type(env, assets, leg.from_asset, leg.amount_in)?;
+    credit_asset_type(env, assets, leg.to_asset, leg.amount_out, &leg.custodian);
+
+    let slippage_bps = if leg.amount_out >= leg.amount_in {
+        0
+    } else {
+        ((leg.amount_in - leg.amount_out) * 10000 / leg.amount_in) as u64
+    };
+
+    Ok(RebalancingOperation {
+        timestamp: env.ledger().timestamp(),
+        asset_type: leg.from_asset,
+        old_amount,
+        new_amount: get_asset_amount_by_type(assets, leg.from_asset),
+        reason,
+        to_asset: leg.to_asset,
+        amount_in: leg.amount_in,
+        amount_out: leg.amount_out,
+        slippage_bps,
+    })
+}
+
+fn record_operations(env: Env, operations: &Vec<RebalancingOperation>) {
+    let mut history = env
+        .storage()
+        .instance()
+        .get(&REBALANCING_HISTORY)
+        .unwrap_or(Vec::new(&env));
+
+    for operation in operations.iter() {
+        history.push_back(operation.clone());
+    }
+
+    // Keep only last 100 rebalancing operations
+    while history.len() > 100 {
+        history.pop_front();
+    }
+
+    env.storage().instance().set(&REBALANCING_HISTORY, &history);
+}
+
+fn get_asset_custodian(env: &Env, assets: &Vec<ReserveAsset>, asset_type: AssetType) -> Address {
+    assets
+        .iter()
+        .find(|asset| asset.asset_type == asset_type)
+        .map(|asset| asset.custodian)
+        .unwrap_or(env.current_contract_address())
+}
+
 fn get_asset_amount_by_type(assets: &Vec<ReserveAsset>, asset_type: AssetType) -> u128 {
     assets
         .iter()
@@ -283,20 +703,70 @@ fn get_asset_amount_by_type(assets: &Vec<ReserveAsset>, asset_type: AssetType) -
         .sum()
 }
 
-fn update_asset_amount_by_type(
+/// Take `debit` from the holdings of `asset_type` in order, across as many
+/// holdings as it takes. Fails with `InsufficientReserves`, leaving the
+/// holdings untouched, if the type does not hold that much in total.
+fn debit_asset_type(
+    env: &Env,
     assets: &mut Vec<ReserveAsset>,
     asset_type: AssetType,
-    new_amount: u128,
+    debit: u128,
+) -> Result<(), ReserveError> {
+    if get_asset_amount_by_type(assets, asset_type) < debit {
+        return Err(ReserveError::InsufficientReserves);
+    }
+
+    let mut remaining = debit;
+    for i in 0..assets.len() {
+        if remaining == 0 {
+            break;
+        }
+        let asset = assets.get(i).unwrap();
+        if asset.asset_type == asset_type {
+            let taken = asset.amount.min(remaining);
+            remaining -= taken;
+            assets.set(
+                i,
+                ReserveAsset {
+                    amount: asset.amount - taken,
+                    last_verified: env.ledger().timestamp(),
+                    ..asset
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Add `credit` to the first holding of `asset_type`, opening a holding with
+/// `custodian` if there is none yet.
+fn credit_asset_type(
+    env: &Env,
+    assets: &mut Vec<ReserveAsset>,
+    asset_type: AssetType,
+    credit: u128,
+    custodian: &Address,
 ) {
     for i in 0..assets.len() {
         let asset = assets.get(i).unwrap();
         if asset.asset_type == asset_type {
             let updated_asset = ReserveAsset {
-                amount: new_amount,
+                amount: asset.amount + credit,
+                last_verified: env.ledger().timestamp(),
                 ..asset
             };
             assets.set(i, updated_asset);
-            break;
+            return;
         }
     }
+
+    if credit > 0 {
+        assets.push_back(ReserveAsset {
+            asset_type,
+            amount: credit,
+            custodian: custodian.clone(),
+            last_verified: env.ledger().timestamp(),
+            verification_hash: BytesN::from_array(env, &[0u8; 32]),
+        });
+    }
 }
@@ -1,15 +1,21 @@
-use crate::{RedemptionRequest, RedemptionStatus, ReserveError};
-use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
-
-const REDEMPTION_REQUESTS: Symbol = symbol_short!("redemption_reqs");
-const REDEMPTION_COUNTER: Symbol = symbol_short!("redemption_counter");
+use crate::{AssetType, RedemptionRequest, RedemptionStatus, ReserveAsset, ReserveError};
+use shared::governance::GovernanceRole;
+use soroban_sdk::{
+    contracttype, symbol_short, token::Client as TokenClient, Address, Env, Symbol, Vec,
+};
+
+const REDEMPTION_REQUESTS: Symbol = symbol_short!("rdm_reqs");
+const REDEMPTION_COUNTER: Symbol = symbol_short!("rdm_count");
 const LARGE_HOLDER_THRESHOLD: u128 = 1_000_000_000_000; // $1M in smallest units
-const REDEMPTION_QUEUE: Symbol = symbol_short!("redemption_queue");
+const REDEMPTION_QUEUE: Symbol = symbol_short!("rdm_queue");
 
+/// Requests not yet paid out or refunded. Their stablecoins are held in
+/// escrow by the contract, so `total_pending_amount` always equals the
+/// escrowed balance.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RedemptionQueue {
-    pub pending_requests: Vec<u64>, // Request IDs
+    pub pending_requests: Vec<u64>, // Request IDs, Pending or Approved
     pub total_pending_amount: u128,
     pub last_processed: u64,
 }
@@ -23,7 +29,15 @@ pub struct RedemptionConfig {
     pub emergency_pause: bool,
 }
 
-pub fn request_redemption(env: Env, requester: Address, amount: u128) -> Result<u64, ReserveError> {
+/// Escrow `amount` of the requester's stablecoins and queue a redemption
+/// into `payout_asset`, which must be held in a registered token.
+pub fn request_redemption(
+    env: Env,
+    requester: Address,
+    amount: u128,
+    payout_asset: AssetType,
+) -> Result<u64, ReserveError> {
+    requester.require_auth();
     let now = env.ledger().timestamp();
 
     // Check if redemption is paused
@@ -43,23 +57,23 @@ pub fn request_redemption(env: Env, requester: Address, amount: u128) -> Result<
         return Err(ReserveError::RedemptionAmountTooLarge);
     }
 
-    // Verify sufficient reserves
-    let total_reserves = crate::reserve_tracking::get_total_reserves(env.clone())?;
-    let total_supply = env
-        .storage()
-        .instance()
-        .get(&symbol_short!("total_supply"))
-        .unwrap_or(0u128);
-
-    if total_reserves < amount {
+    // Verify sufficient reserves of the chosen asset, net of queued payouts
+    crate::rebalancing::get_asset_token(&env, payout_asset).ok_or(ReserveError::InvalidAsset)?;
+    let queued = get_queued_amount(env.clone(), payout_asset)?;
+    if get_asset_amount(&env, payout_asset) < queued + amount {
         return Err(ReserveError::InsufficientReserves);
     }
 
+    let escrow = i128::try_from(amount).map_err(|_| ReserveError::RedemptionAmountTooLarge)?;
+    let stablecoin = crate::proof_of_reserves::stablecoin(&env)?;
+
     // Create redemption request
     let request_id = get_next_request_id(env.clone())?;
     let request = RedemptionRequest {
+        id: request_id,
         requester: requester.clone(),
         amount,
+        payout_asset,
         request_time: now,
         status: RedemptionStatus::Pending,
         processed_time: None,
@@ -79,168 +93,168 @@ pub fn request_redemption(env: Env, requester: Address, amount: u128) -> Result<
     // Add to queue
     add_to_queue(env.clone(), request_id, amount)?;
 
+    // Hold the stablecoins until the request is paid out or refunded
+    TokenClient::new(&env, &stablecoin).transfer(
+        &requester,
+        env.current_contract_address(),
+        &escrow,
+    );
+
     // Log redemption request
     env.events().publish(
-        (symbol_short!("redemption"), symbol_short!("requested")),
-        (requester, amount, request_id),
+        (symbol_short!("redeem"), symbol_short!("requested")),
+        (requester, amount, request_id, payout_asset),
     );
 
     Ok(request_id)
 }
 
-pub fn process_redemption(env: Env, request_id: u64) -> Result<(), ReserveError> {
+/// Pay out an approved request once the processing delay has passed: burn
+/// the escrowed stablecoins and transfer the same amount of the payout
+/// asset's token to the requester.
+pub fn process_redemption(
+    env: Env,
+    executor: Address,
+    request_id: u64,
+) -> Result<(), ReserveError> {
     // Check authorization
-    if !shared::governance::has_role(
-        env.clone(),
-        env.invoker(),
-        shared::governance::GovernanceRole::Executor,
-    ) {
-        return Err(ReserveError::Unauthorized);
-    }
+    crate::require_role(&env, &executor, GovernanceRole::Executor)?;
 
     let now = env.ledger().timestamp();
     let config = get_redemption_config(env.clone())?;
 
-    // Get redemption request
-    let mut requests = env
-        .storage()
-        .instance()
-        .get(&REDEMPTION_REQUESTS)
-        .ok_or(ReserveError::RedemptionAmountTooSmall)?;
+    let mut request = get_redemption_request(env.clone(), request_id)?;
 
-    let mut request = None;
-    for i in 0..requests.len() {
-        let req = requests.get(i).unwrap();
-        if get_request_id(&req) == request_id {
-            request = Some(req);
-            break;
-        }
-    }
-
-    let mut request = request.ok_or(ReserveError::RedemptionAmountTooSmall)?;
-
-    // Check if request can be processed
-    if request.status != RedemptionStatus::Pending {
-        return Err(ReserveError::RedemptionAmountTooSmall);
+    // Only approved requests are paid out
+    if request.status != RedemptionStatus::Approved {
+        return Err(ReserveError::RedemptionNotApproved);
     }
 
     // Check processing delay
     if now - request.request_time < config.processing_delay {
-        return Err(ReserveError::RedemptionAmountTooSmall);
+        return Err(ReserveError::RedemptionNotApproved);
     }
 
-    // Verify sufficient reserves again
-    let total_reserves = crate::reserve_tracking::get_total_reserves(env.clone())?;
-    if total_reserves < request.amount {
-        request.status = RedemptionStatus::Rejected;
-        update_request(env.clone(), request_id, request)?;
-        return Err(ReserveError::InsufficientReserves);
-    }
+    let payout_token = crate::rebalancing::get_asset_token(&env, request.payout_asset)
+        .ok_or(ReserveError::InvalidAsset)?;
+    let amount =
+        i128::try_from(request.amount).map_err(|_| ReserveError::RedemptionAmountTooLarge)?;
 
-    // Process redemption
-    // In a real implementation, this would:
-    // 1. Burn the stablecoin tokens
-    // 2. Transfer reserve assets to the requester
-    // 3. Update reserve tracking
+    // Take the payout out of the reserve holdings of that asset
+    let mut assets: Vec<ReserveAsset> = env
+        .storage()
+        .instance()
+        .get(&symbol_short!("rsv_asset"))
+        .ok_or(ReserveError::InvalidAsset)?;
+    let asset_index = assets
+        .iter()
+        .position(|asset| {
+            asset.asset_type == request.payout_asset && asset.amount >= request.amount
+        })
+        .ok_or(ReserveError::InsufficientReserves)? as u32;
+    let asset = assets.get(asset_index).unwrap();
+    assets.set(
+        asset_index,
+        ReserveAsset {
+            amount: asset.amount - request.amount,
+            ..asset
+        },
+    );
+    env.storage()
+        .instance()
+        .set(&symbol_short!("rsv_asset"), &assets);
 
-    // For now, we'll simulate the processing
     request.status = RedemptionStatus::Processed;
     request.processed_time = Some(now);
-
-    // Update request
-    update_request(env.clone(), request_id, request)?;
-
-    // Remove from queue
+    update_request(env.clone(), request_id, request.clone())?;
     remove_from_queue(env.clone(), request_id, request.amount)?;
 
-    // Update total supply (reduce by redemption amount)
+    // Liabilities fall by the burned stablecoins
     let current_supply = env
         .storage()
         .instance()
-        .get(&symbol_short!("total_supply"))
+        .get(&symbol_short!("total_sup"))
         .unwrap_or(0u128);
-    let new_supply = current_supply - request.amount;
-    env.storage()
-        .instance()
-        .set(&symbol_short!("total_supply"), &new_supply);
+    crate::reserve_tracking::update_total_supply(
+        env.clone(),
+        current_supply.saturating_sub(request.amount),
+    )?;
 
-    // Update reserve snapshot
-    crate::reserve_tracking::update_total_supply(env.clone(), new_supply)?;
+    let this = env.current_contract_address();
+    TokenClient::new(&env, &crate::proof_of_reserves::stablecoin(&env)?).burn(&this, &amount);
+    TokenClient::new(&env, &payout_token).transfer(&this, &request.requester, &amount);
 
     // Log redemption processing
     env.events().publish(
-        (symbol_short!("redemption"), symbol_short!("processed")),
-        (request.requester, request.amount, request_id),
+        (symbol_short!("redeem"), symbol_short!("processed")),
+        (
+            request.requester.clone(),
+            request.amount,
+            request_id,
+            request.payout_asset,
+        ),
     );
 
     Ok(())
 }
 
-pub fn approve_redemption(env: Env, request_id: u64) -> Result<(), ReserveError> {
+pub fn approve_redemption(env: Env, admin: Address, request_id: u64) -> Result<(), ReserveError> {
     // Check authorization
-    if !shared::governance::has_role(
-        env.clone(),
-        env.invoker(),
-        shared::governance::GovernanceRole::Admin,
-    ) {
-        return Err(ReserveError::Unauthorized);
-    }
-
-    let mut requests = env
-        .storage()
-        .instance()
-        .get(&REDEMPTION_REQUESTS)
-        .ok_or(ReserveError::RedemptionAmountTooSmall)?;
+    crate::require_role(&env, &admin, GovernanceRole::Admin)?;
 
-    let mut request = get_request_by_id(&requests, request_id)?;
+    let mut request = get_redemption_request(env.clone(), request_id)?;
 
     if request.status != RedemptionStatus::Pending {
-        return Err(ReserveError::RedemptionAmountTooSmall);
+        return Err(ReserveError::RedemptionNotApproved);
     }
 
     request.status = RedemptionStatus::Approved;
-    update_request(env.clone(), request_id, request)?;
+    update_request(env.clone(), request_id, request.clone())?;
 
     // Log approval
     env.events().publish(
-        (symbol_short!("redemption"), symbol_short!("approved")),
+        (symbol_short!("redeem"), symbol_short!("approved")),
         (request.requester, request.amount, request_id),
     );
 
     Ok(())
 }
 
-pub fn reject_redemption(env: Env, request_id: u64, reason: Symbol) -> Result<(), ReserveError> {
+/// Reject a request that has not been paid out and refund its escrow
+pub fn reject_redemption(
+    env: Env,
+    admin: Address,
+    request_id: u64,
+    reason: Symbol,
+) -> Result<(), ReserveError> {
     // Check authorization
-    if !shared::governance::has_role(
-        env.clone(),
-        env.invoker(),
-        shared::governance::GovernanceRole::Admin,
-    ) {
-        return Err(ReserveError::Unauthorized);
-    }
+    crate::require_role(&env, &admin, GovernanceRole::Admin)?;
 
-    let mut requests = env
-        .storage()
-        .instance()
-        .get(&REDEMPTION_REQUESTS)
-        .ok_or(ReserveError::RedemptionAmountTooSmall)?;
+    let mut request = get_redemption_request(env.clone(), request_id)?;
 
-    let mut request = get_request_by_id(&requests, request_id)?;
-
-    if request.status != RedemptionStatus::Pending {
-        return Err(ReserveError::RedemptionAmountTooSmall);
+    if request.status != RedemptionStatus::Pending && request.status != RedemptionStatus::Approved
+    {
+        return Err(ReserveError::RedemptionNotApproved);
     }
 
     request.status = RedemptionStatus::Rejected;
-    update_request(env.clone(), request_id, request)?;
+    request.processed_time = Some(env.ledger().timestamp());
+    update_request(env.clone(), request_id, request.clone())?;
 
     // Remove from queue
     remove_from_queue(env.clone(), request_id, request.amount)?;
 
+    let refund =
+        i128::try_from(request.amount).map_err(|_| ReserveError::RedemptionAmountTooLarge)?;
+    TokenClient::new(&env, &crate::proof_of_reserves::stablecoin(&env)?).transfer(
+        &env.current_contract_address(),
+        &request.requester,
+        &refund,
+    );
+
     // Log rejection
     env.events().publish(
-        (symbol_short!("redemption"), symbol_short!("rejected")),
+        (symbol_short!("redeem"), symbol_short!("rejected")),
         (request.requester, request.amount, request_id, reason),
     );
 
@@ -255,7 +269,7 @@ pub fn get_redemption_request(
         .storage()
         .instance()
         .get(&REDEMPTION_REQUESTS)
-        .ok_or(ReserveError::RedemptionAmountTooSmall)?;
+        .ok_or(ReserveError::RedemptionNotFound)?;
 
     get_request_by_id(&requests, request_id)
 }
@@ -265,7 +279,7 @@ pub fn get_pending_redemptions(env: Env) -> Result<Vec<RedemptionRequest>, Reser
         .storage()
         .instance()
         .get(&REDEMPTION_REQUESTS)
-        .ok_or(ReserveError::RedemptionAmountTooSmall)?;
+        .unwrap_or(Vec::<RedemptionRequest>::new(&env));
 
     let mut pending: Vec<RedemptionRequest> = Vec::new(&env);
     for request in requests.iter() {
@@ -278,27 +292,19 @@ pub fn get_pending_redemptions(env: Env) -> Result<Vec<RedemptionRequest>, Reser
 }
 
 pub fn get_redemption_queue(env: Env) -> Result<RedemptionQueue, ReserveError> {
-    env.storage()
-        .instance()
-        .get(&REDEMPTION_QUEUE)
-        .ok_or(ReserveError::RedemptionAmountTooSmall)
+    Ok(load_queue(&env))
 }
 
 pub fn update_redemption_config(
     env: Env,
+    admin: Address,
     large_holder_threshold: u128,
     processing_delay: u64,
     max_daily_redemption: u128,
     emergency_pause: bool,
 ) -> Result<(), ReserveError> {
     // Check authorization
-    if !shared::governance::has_role(
-        env.clone(),
-        env.invoker(),
-        shared::governance::GovernanceRole::Admin,
-    ) {
-        return Err(ReserveError::Unauthorized);
-    }
+    crate::require_role(&env, &admin, GovernanceRole::Admin)?;
 
     let config = RedemptionConfig {
         large_holder_threshold,
@@ -309,11 +315,11 @@ pub fn update_redemption_config(
 
     env.storage()
         .instance()
-        .set(&symbol_short!("redemption_config"), &config);
+        .set(&symbol_short!("rdm_cfg"), &config);
 
     // Log config update
     env.events().publish(
-        (symbol_short!("redemption"), symbol_short!("config_updated")),
+        (symbol_short!("redeem"), symbol_short!("config")),
         (
             large_holder_threshold,
             processing_delay,
@@ -336,16 +342,19 @@ fn get_next_request_id(env: Env) -> Result<u64, ReserveError> {
     Ok(next_id)
 }
 
-fn add_to_queue(env: Env, request_id: u64, amount: u128) -> Result<(), ReserveError> {
-    let mut queue = env
-        .storage()
+fn load_queue(env: &Env) -> RedemptionQueue {
+    env.storage()
         .instance()
         .get(&REDEMPTION_QUEUE)
         .unwrap_or(RedemptionQueue {
-            pending_requests: Vec::new(&env),
+            pending_requests: Vec::new(env),
             total_pending_amount: 0,
             last_processed: 0,
-        });
+        })
+}
+
+fn add_to_queue(env: Env, request_id: u64, amount: u128) -> Result<(), ReserveError> {
+    let mut queue = load_queue(&env);
 
     queue.pending_requests.push_back(request_id);
     queue.total_pending_amount += amount;
@@ -355,42 +364,60 @@ fn add_to_queue(env: Env, request_id: u64, amount: u128) -> Result<(), ReserveEr
 }
 
 fn remove_from_queue(env: Env, request_id: u64, amount: u128) -> Result<(), ReserveError> {
-    let mut queue = env
-        .storage()
-        .instance()
-        .get(&REDEMPTION_QUEUE)
-        .ok_or(ReserveError::RedemptionAmountTooSmall)?;
+    let mut queue = load_queue(&env);
 
     // Remove request ID from queue
-    let mut new_requests: Vec<u64> = Vec::new(&env);
-    for req_id in queue.pending_requests.iter() {
-        if req_id != request_id {
-            new_requests.push_back(req_id);
-        }
-    }
-
-    queue.pending_requests = new_requests;
-    queue.total_pending_amount = queue.total_pending_amount.saturating_sub(amount);
+    let position = queue
+        .pending_requests
+        .first_index_of(request_id)
+        .ok_or(ReserveError::RedemptionNotFound)?;
+    queue.pending_requests.remove(position);
+    queue.total_pending_amount -= amount;
     queue.last_processed = env.ledger().timestamp();
 
     env.storage().instance().set(&REDEMPTION_QUEUE, &queue);
     Ok(())
 }
 
+/// Amount still queued for payout in `asset_type`
+fn get_queued_amount(env: Env, asset_type: AssetType) -> Result<u128, ReserveError> {
+    let mut queued = 0u128;
+    for request_id in load_queue(&env).pending_requests.iter() {
+        let request = get_redemption_request(env.clone(), request_id)?;
+        if request.payout_asset == asset_type {
+            queued += request.amount;
+        }
+    }
+    Ok(queued)
+}
+
+fn get_asset_amount(env: &Env, asset_type: AssetType) -> u128 {
+    let assets: Vec<ReserveAsset> = env
+        .storage()
+        .instance()
+        .get(&symbol_short!("rsv_asset"))
+        .unwrap_or(Vec::new(env));
+    assets
+        .iter()
+        .filter(|asset| asset.asset_type == asset_type)
+        .map(|asset| asset.amount)
+        .sum()
+}
+
 fn update_request(
     env: Env,
     request_id: u64,
     request: RedemptionRequest,
 ) -> Result<(), ReserveError> {
-    let mut requests = env
+    let mut requests: Vec<RedemptionRequest> = env
         .storage()
         .instance()
         .get(&REDEMPTION_REQUESTS)
-        .ok_or(ReserveError::RedemptionAmountTooSmall)?;
+        .ok_or(ReserveError::RedemptionNotFound)?;
 
     for i in 0..requests.len() {
         let req = requests.get(i).unwrap();
-        if get_request_id(&req) == request_id {
+        if req.id == request_id {
             requests.set(i, request);
             break;
         }
@@ -406,19 +433,12 @@ fn get_request_by_id(
     requests: &Vec<RedemptionRequest>,
     request_id: u64,
 ) -> Result<RedemptionRequest, ReserveError> {
-    for i in 0..requests.len() {
-        let request = requests.get(i).unwrap();
-        if get_request_id(&request) == request_id {
+    for request in requests.iter() {
+        if request.id == request_id {
             return Ok(request);
         }
     }
-    Err(ReserveError::RedemptionAmountTooSmall)
-}
-
-fn get_request_id(request: &RedemptionRequest) -> u64 {
-    // In a real implementation, we'd store the request ID
-    // For now, we'll use the request time as a proxy
-    request.request_time
+    Err(ReserveError::RedemptionNotFound)
 }
 
 fn get_daily_redemption_total(env: Env) -> Result<u128, ReserveError> {
@@ -429,11 +449,13 @@ fn get_daily_redemption_total(env: Env) -> Result<u128, ReserveError> {
         .storage()
         .instance()
         .get(&REDEMPTION_REQUESTS)
-        .ok_or(ReserveError::RedemptionAmountTooSmall)?;
+        .unwrap_or(Vec::<RedemptionRequest>::new(&env));
 
+    // Count requests when they are made, so queued requests cannot together
+    // exceed the cap; only rejected (refunded) requests free it up again
     let mut daily_total = 0u128;
     for request in requests.iter() {
-        if request.request_time >= start_of_day && request.status == RedemptionStatus::Processed {
+        if request.request_time >= start_of_day && request.status != RedemptionStatus::Rejected {
             daily_total += request.amount;
         }
     }
@@ -442,15 +464,14 @@ fn get_daily_redemption_total(env: Env) -> Result<u128, ReserveError> {
 }
 
 fn get_redemption_config(env: Env) -> Result<RedemptionConfig, ReserveError> {
-    env.storage()
+    Ok(env
+        .storage()
         .instance()
-        .get(&symbol_short!("redemption_config"))
-        .ok_or_else(|| {
-            Ok(RedemptionConfig {
-                large_holder_threshold: LARGE_HOLDER_THRESHOLD,
-                processing_delay: 24 * 60 * 60, // 24 hours
-                max_daily_redemption: 10_000_000_000_000u128, // $10M daily limit
-                emergency_pause: false,
-            })
-        })
+        .get(&symbol_short!("rdm_cfg"))
+        .unwrap_or(RedemptionConfig {
+            large_holder_threshold: LARGE_HOLDER_THRESHOLD,
+            processing_delay: 24 * 60 * 60, // 24 hours
+            max_daily_redemption: 10_000_000_000_000u128, // $10M daily limit
+            emergency_pause: false,
+        }))
 }
@@ -1,9 +1,9 @@
 use crate::{AssetType, ReserveAsset, ReserveError, ReserveSnapshot};
-use soroban_sdk::{contracttype, symbol_short, Address, Env, Map, Symbol, Vec};
+use soroban_sdk::{contracttype, symbol_short, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Vec};
 
-const REPORTING_HISTORY: Symbol = symbol_short!("report_history");
-const LAST_MONTHLY_REPORT: Symbol = symbol_short!("last_monthly");
-const LAST_DAILY_REPORT: Symbol = symbol_short!("last_daily");
+const REPORTING_HISTORY: Symbol = symbol_short!("rpt_hist");
+const LAST_MONTHLY_REPORT: Symbol = symbol_short!("last_mon");
+const LAST_DAILY_REPORT: Symbol = symbol_short!("last_day");
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -16,7 +16,7 @@ pub struct RegulatoryReport {
     pub asset_breakdown: Vec<AssetBreakdown>,
     pub compliance_status: ComplianceStatus,
     pub custodian_verifications: Vec<CustodianVerification>,
-    pub merkle_root: [u8; 32],
+    pub merkle_root: BytesN<32>,
 }
 
 #[contracttype]
@@ -54,7 +54,7 @@ pub enum ComplianceStatus {
 pub struct CustodianVerification {
     pub custodian: Address,
     pub verification_time: u64,
-    pub verification_hash: [u8; 32],
+    pub verification_hash: BytesN<32>,
     pub status: VerificationStatus,
 }
 
@@ -67,7 +67,9 @@ pub enum VerificationStatus {
     Failed = 2,
 }
 
-pub fn generate_report(env: Env) -> Result<Symbol, ReserveError> {
+/// Build and store a report of the current reserves. Reports are identified
+/// by their timestamp.
+pub fn generate_report(env: Env) -> Result<u64, ReserveError> {
     let now = env.ledger().timestamp();
     let report_type = determine_report_type(env.clone(), now)?;
 
@@ -93,7 +95,7 @@ pub fn generate_report(env: Env) -> Result<Symbol, ReserveError> {
         asset_breakdown,
         compliance_status,
         custodian_verifications,
-        merkle_root: snapshot.merkle_root.to_array(),
+        merkle_root: snapshot.merkle_root,
     };
 
     // Store report
@@ -106,30 +108,24 @@ pub fn generate_report(env: Env) -> Result<Symbol, ReserveError> {
         _ => {}
     }
 
-    // Generate report ID
-    let report_id = format!("report_{}", now);
-    let report_symbol = Symbol::from_str(&env, &report_id);
-
     // Log report generation
     env.events().publish(
         (symbol_short!("report"), symbol_short!("generated")),
-        (report_symbol, report_type, compliance_status),
+        (now, report_type, compliance_status),
     );
 
-    Ok(report_symbol)
+    Ok(now)
 }
 
-pub fn get_report(env: Env, report_id: Symbol) -> Result<RegulatoryReport, ReserveError> {
-    let reports = env
+pub fn get_report(env: Env, report_id: u64) -> Result<RegulatoryReport, ReserveError> {
+    let reports: Vec<RegulatoryReport> = env
         .storage()
         .instance()
         .get(&REPORTING_HISTORY)
         .ok_or(ReserveError::ReportingError)?;
 
     for report in reports.iter() {
-        let current_id = format!("report_{}", report.timestamp);
-        let current_symbol = Symbol::from_str(&env, &current_id);
-        if current_symbol == report_id {
+        if report.timestamp == report_id {
             return Ok(report);
         }
     }
@@ -141,7 +137,7 @@ pub fn get_reports_by_type(
     env: Env,
     report_type: ReportType,
 ) -> Result<Vec<RegulatoryReport>, ReserveError> {
-    let reports = env
+    let reports: Vec<RegulatoryReport> = env
         .storage()
         .instance()
         .get(&REPORTING_HISTORY)
@@ -238,7 +234,7 @@ fn generate_asset_breakdown(
     let mut breakdown: Vec<AssetBreakdown> = Vec::new(&env);
 
     for asset in assets.iter() {
-        let percentage = (asset.amount * 10000) / total_reserves;
+        let percentage = ((asset.amount * 10000) / total_reserves) as u64;
         let asset_breakdown = AssetBreakdown {
             asset_type: asset.asset_type,
             amount: asset.amount,
@@ -262,9 +258,8 @@ fn check_compliance(
     }
 
     // Check if any asset needs verification (older than 24 hours)
-    let now = env.ledger().timestamp();
     for asset in snapshot.assets.iter() {
-        if now - asset.last_verified > 24 * 60 * 60 {
+        if !crate::reserve_tracking::verify_asset_compliance(env.clone(), &asset)? {
             return Ok(ComplianceStatus::Warning);
         }
     }
@@ -282,7 +277,7 @@ fn get_custodian_verifications(
         let verification = CustodianVerification {
             custodian: asset.custodian,
             verification_time: asset.last_verified,
-            verification_hash: asset.verification_hash.to_array(),
+            verification_hash: asset.verification_hash,
             status: VerificationStatus::Verified,
         };
         verifications.push_back(verification);
@@ -292,7 +287,7 @@ fn get_custodian_verifications(
 }
 
 fn store_report(env: Env, report: RegulatoryReport) -> Result<(), ReserveError> {
-    let mut reports = env
+    let mut reports: Vec<RegulatoryReport> = env
         .storage()
         .instance()
         .get(&REPORTING_HISTORY)
@@ -309,10 +304,9 @@ fn store_report(env: Env, report: RegulatoryReport) -> Result<(), ReserveError>
     Ok(())
 }
 
-pub fn export_report_data(env: Env, report_id: Symbol) -> Result<Vec<u8>, ReserveError> {
+pub fn export_report_data(env: Env, report_id: u64) -> Result<Bytes, ReserveError> {
     let report = get_report(env.clone(), report_id)?;
 
     // Convert report to XDR format for export
-    let xdr_data = report.to_xdr();
-    Ok(xdr_data)
+    Ok(report.to_xdr(&env))
 }
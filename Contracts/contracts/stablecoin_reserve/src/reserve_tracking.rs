@@ -1,12 +1,12 @@
 use crate::{AssetType, ReserveAsset, ReserveError, ReserveSnapshot};
-use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol, Vec};
+use soroban_sdk::{symbol_short, Address, Bytes, BytesN, Env, Symbol, Vec};
 
-const RESERVE_ASSETS: Symbol = symbol_short!("reserve_assets");
+const RESERVE_ASSETS: Symbol = symbol_short!("rsv_asset");
 const RESERVE_SNAPSHOTS: Symbol = symbol_short!("snapshots");
-const CURRENT_SNAPSHOT: Symbol = symbol_short!("current_snapshot");
-const TOTAL_SUPPLY: Symbol = symbol_short!("total_supply");
+const CURRENT_SNAPSHOT: Symbol = symbol_short!("cur_snap");
+const TOTAL_SUPPLY: Symbol = symbol_short!("total_sup");
 
-pub fn initialize(env: Env) {
+pub fn initialize(env: Env) -> Result<(), ReserveError> {
     // Initialize empty reserve assets list
     env.storage()
         .instance()
@@ -19,6 +19,8 @@ pub fn initialize(env: Env) {
 
     // Initialize total supply to 0
     env.storage().instance().set(&TOTAL_SUPPLY, &0u128);
+
+    update_snapshot(env)
 }
 
 pub fn add_asset(
@@ -28,7 +30,7 @@ pub fn add_asset(
     custodian: Address,
     verification_hash: BytesN<32>,
 ) -> Result<(), ReserveError> {
-    let mut assets = env
+    let mut assets: Vec<ReserveAsset> = env
         .storage()
         .instance()
         .get(&RESERVE_ASSETS)
@@ -38,7 +40,7 @@ pub fn add_asset(
     let new_asset = ReserveAsset {
         asset_type,
         amount,
-        custodian,
+        custodian: custodian.clone(),
         last_verified: env.ledger().timestamp(),
         verification_hash,
     };
@@ -51,7 +53,7 @@ pub fn add_asset(
 
     // Log asset addition
     env.events().publish(
-        (symbol_short!("reserve"), symbol_short!("asset_added")),
+        (symbol_short!("reserve"), symbol_short!("asset_add")),
         (asset_type, amount, custodian),
     );
 
@@ -64,17 +66,17 @@ pub fn update_asset(
     new_amount: u128,
     verification_hash: BytesN<32>,
 ) -> Result<(), ReserveError> {
-    let mut assets = env
+    let mut assets: Vec<ReserveAsset> = env
         .storage()
         .instance()
         .get(&RESERVE_ASSETS)
         .ok_or(ReserveError::InvalidAsset)?;
 
-    if asset_index as usize >= assets.len() {
+    if asset_index >= assets.len() {
         return Err(ReserveError::InvalidAsset);
     }
 
-    let asset = assets.get(asset_index as usize).unwrap();
+    let asset = assets.get(asset_index).unwrap();
     let updated_asset = ReserveAsset {
         amount: new_amount,
         last_verified: env.ledger().timestamp(),
@@ -82,7 +84,7 @@ pub fn update_asset(
         ..asset
     };
 
-    assets.set(asset_index as usize, updated_asset);
+    assets.set(asset_index, updated_asset);
     env.storage().instance().set(&RESERVE_ASSETS, &assets);
 
     // Update snapshot
@@ -90,7 +92,7 @@ pub fn update_asset(
 
     // Log asset update
     env.events().publish(
-        (symbol_short!("reserve"), symbol_short!("asset_updated")),
+        (symbol_short!("reserve"), symbol_short!("asset_upd")),
         (asset_index, new_amount),
     );
 
@@ -119,8 +121,8 @@ pub fn update_total_supply(env: Env, new_supply: u128) -> Result<(), ReserveErro
     update_snapshot(env.clone())
 }
 
-fn update_snapshot(env: Env) -> Result<(), ReserveError> {
-    let assets = env
+pub fn update_snapshot(env: Env) -> Result<(), ReserveError> {
+    let assets: Vec<ReserveAsset> = env
         .storage()
         .instance()
         .get(&RESERVE_ASSETS)
@@ -131,11 +133,9 @@ fn update_snapshot(env: Env) -> Result<(), ReserveError> {
     let total_reserves: u128 = assets.iter().map(|asset| asset.amount).sum();
 
     // Calculate reserve ratio (in basis points)
-    let reserve_ratio = if total_supply > 0 {
-        (total_reserves * 10000) / total_supply
-    } else {
-        10000 // 100% if no supply
-    };
+    let reserve_ratio = (total_reserves * 10000)
+        .checked_div(total_supply)
+        .map_or(10000, |ratio| u64::try_from(ratio).unwrap_or(u64::MAX)); // 100% if no supply
 
     // Generate Merkle root (simplified - in production would use proper Merkle tree)
     let merkle_root = generate_merkle_root(env.clone(), &assets)?;
@@ -171,7 +171,7 @@ fn update_snapshot(env: Env) -> Result<(), ReserveError> {
     if reserve_ratio < 10000 {
         // Less than 100%
         env.events().publish(
-            (symbol_short!("reserve"), symbol_short!("ratio_warning")),
+            (symbol_short!("reserve"), symbol_short!("ratio_low")),
             (reserve_ratio, total_reserves, total_supply),
         );
     }
@@ -184,10 +184,13 @@ fn generate_merkle_root(env: Env, assets: &Vec<ReserveAsset>) -> Result<BytesN<3
     // In production, this would be a proper Merkle tree implementation
     let mut combined_hash = [0u8; 32];
 
-    for (i, asset) in assets.iter().enumerate() {
-        let asset_bytes = env.crypto().keccak256(&asset.amount.to_be_bytes());
-        for j in 0..32 {
-            combined_hash[j] ^= asset_bytes[j];
+    for asset in assets.iter() {
+        let asset_bytes = env
+            .crypto()
+            .keccak256(&Bytes::from_array(&env, &asset.amount.to_be_bytes()))
+            .to_array();
+        for (byte, asset_byte) in combined_hash.iter_mut().zip(asset_bytes.iter()) {
+            *byte ^= asset_byte;
         }
     }
 
@@ -1,11 +1,19 @@
 #![no_std]
 
 use shared::events::{
-    extended_topics, AssetRegisteredEvent, CdpClosedEvent, CdpLiquidatedEvent, CdpOpenedEvent,
-    CollateralAddedEvent, PriceUpdatedEvent,
+    extended_topics, AssetRegisteredEvent, AuctionBidEvent, AuctionStartedEvent, CdpClosedEvent,
+    CdpLiquidatedEvent, CdpOpenedEvent, CdpSettledEvent, CollateralAddedEvent,
+    CollateralWithdrawnEvent, PriceUpdatedEvent, StabilityFeeAccruedEvent, SyntheticRedeemedEvent,
 };
+use shared::oracle::fetch_aggregate_price;
 use soroban_sdk::token::{Client as TokenClient, StellarAssetClient};
-use soroban_sdk::{contract, contracterror, contractimpl, contracttype, Address, Env, Symbol};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, Address, Env, Map, Symbol, Vec,
+};
+
+mod liquidation;
+
+pub use liquidation::{Auction, BasketLot, LiquidationConfig};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -26,18 +34,59 @@ pub enum Error {
     // so existing ABI codes for InvalidPrice (10) and PriceStale (11) are
     // preserved across the merge.
     InvalidConfig = 12,
+    AuctionNotFound = 13,
+    AuctionClosed = 14,
+    // The asset liquidates through `start_auction` rather than `liquidate`
+    AuctionOnly = 15,
+    AuctionsDisabled = 16,
+    CollateralNotApproved = 17,
+    Shutdown = 18,
+    NotShutdown = 19,
+    RedemptionNotOpen = 20,
+    // Price conversion overflowed; folded in from the former standalone
+    // price-validation contract
+    ArithmeticError = 21,
+    DebtCeilingExceeded = 22,
+    // A CDP's debt would be left above zero but below the asset's `min_debt`
+    DebtBelowMinimum = 23,
+    // Settlement left no collateral to redeem synthetic tokens for
+    RedemptionPoolEmpty = 24,
+    // `open_cdp` over a CDP that still holds collateral or debt
+    CDPAlreadyOpen = 25,
 }
 
+/// Fixed-point precision of `SyntheticConfig.rate_index` (1.0 = 1e12)
+pub const RATE_PRECISION: i128 = 1_000_000_000_000;
+
+/// Seconds in a 365-day year, used to annualise `stability_fee_bps`
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
 #[contracttype]
 #[derive(Clone)]
 pub struct CDP {
     pub owner: Address,
     pub collateral_amount: i128, // in base units
-    pub minted_amount: i128,     // synthetic tokens minted
+    pub minted_amount: i128,     // debt incl. accrued stability fees
     pub collateral_ratio: i128,  // scaled by 10000 (15000 = 150%)
     pub is_active: bool,
+    pub normalized_debt: i128, // debt / rate_index; grows into minted_amount as fees accrue
+    pub basket: Map<Address, i128>, // approved extra collateral held alongside collateral_amount
+}
+
+/// Live health of a CDP, recomputed against the current price
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CdpHealth {
+    pub owner: Address,
+    pub debt: i128,             // live debt incl. accrued stability fees
+    pub collateral_value: i128, // haircut-weighted basket value in synthetic units
+    pub collateral_ratio: i128, // live ratio scaled by 10000 (i128::MAX without debt)
+    pub liquidatable: bool,
 }
 
+/// Max CDPs `get_liquidatable` inspects per call
+pub const MAX_HEALTH_SCAN: u32 = 50;
+
 #[contracttype]
 #[derive(Clone)]
 pub struct SyntheticConfig {
@@ -52,11 +101,64 @@ pub struct SyntheticConfig {
     pub synthetic_token: Address,   // SAC minted/burned for synthetic debt
     pub last_updated: u64,          // ledger timestamp of last valid oracle update
     pub price_max_age_seconds: u64, // max age before the price is considered stale
+    pub rate_index: i128,            // accumulated stability fee, scaled by RATE_PRECISION
+    pub last_accrual: u64,           // ledger timestamp rate_index was last brought forward
+    pub total_normalized_debt: i128, // sum of CDP normalized_debt
+    pub surplus: i128,               // accrued fees minted to the contract's surplus buffer
+    pub debt_ceiling: i128,          // cap on total_minted; 0 = uncapped
+    pub min_debt: i128,              // smallest non-zero CDP debt ("dust"); 0 = none
+}
+
+/// An extra collateral token approved for an asset's CDPs. The asset's own
+/// `collateral_token` is always accepted, at `oracle_price` and no haircut.
+#[contracttype]
+#[derive(Clone)]
+pub struct CollateralType {
+    pub haircut_bps: i128, // discount on the token's value in ratio checks
+    pub price: i128,       // token units per synthetic, scaled by 1_000_000
+    pub last_updated: u64, // ledger timestamp of the last price update
+}
+
+/// External price sources for an asset in oracle mode
+#[contracttype]
+#[derive(Clone)]
+pub struct OracleConfig {
+    pub sources: Vec<Address>,  // contracts exposing get_price(feed) -> (i128, u64)
+    pub feed: Symbol,           // feed id passed to every source
+    pub min_sources: u32,       // fresh, agreeing answers needed for a median
+    pub max_deviation_bps: u32, // max move from the last good price per update
+}
+
+/// Per-asset state of a global settlement
+#[contracttype]
+#[derive(Clone)]
+pub struct Settlement {
+    pub final_price: i128,         // oracle_price frozen at shutdown
+    pub supply: i128,              // synthetic tokens still redeemable
+    pub pool: Map<Address, i128>,  // collateral set aside for redemptions
+    pub shortfall: i128,           // settled debt the collateral could not cover
+    // Fixed when redemptions open: each token pays open_pool / open_supply
+    // per synthetic token redeemed. open_supply is 0 until then.
+    pub open_supply: i128,
+    pub open_pool: Map<Address, i128>,
 }
 
 mod keys {
     use soroban_sdk::{symbol_short, Symbol};
     pub const ADMIN: Symbol = symbol_short!("admin");
+    pub const LIQ_CONFIG: Symbol = symbol_short!("liq_cfg");
+    pub const AUCTION: Symbol = symbol_short!("auction");
+    pub const AUCTION_COUNT: Symbol = symbol_short!("auc_cnt");
+    pub const COLLATERAL_TYPES: Symbol = symbol_short!("col_types");
+    pub const ORACLE: Symbol = symbol_short!("oracle");
+    pub const ASSETS: Symbol = symbol_short!("assets");
+    pub const SHUTDOWN: Symbol = symbol_short!("shutdown");
+    pub const SETTLEMENT: Symbol = symbol_short!("settle");
+    pub const CDP_INDEX: Symbol = symbol_short!("cdp_idx");
+    pub const CDP_SLOT: Symbol = symbol_short!("cdp_slot");
+    pub const CDP_COUNT: Symbol = symbol_short!("cdp_cnt");
+    pub const GLOBAL_CEILING: Symbol = symbol_short!("glob_ceil");
+    pub const OPEN_AUCTIONS: Symbol = symbol_short!("auc_open");
 }
 
 #[contract]
@@ -87,6 +189,7 @@ impl SyntheticAssetsContract {
     ) -> Result<(), Error> {
         caller.require_auth();
         Self::require_admin(&env, &caller)?;
+        Self::require_not_shutdown(&env)?;
 
         // Sanity-check configuration to prevent unsafe parameters at registration
         // time. We require:
@@ -124,9 +227,21 @@ impl SyntheticAssetsContract {
             synthetic_token,
             last_updated: 0,
             price_max_age_seconds,
+            rate_index: RATE_PRECISION,
+            last_accrual: env.ledger().timestamp(),
+            total_normalized_debt: 0,
+            surplus: 0,
+            debt_ceiling: 0,
+            min_debt: 0,
         };
         env.storage().persistent().set(&asset_symbol, &config);
 
+        let mut assets = Self::assets(&env);
+        if !assets.contains(&asset_symbol) {
+            assets.push_back(asset_symbol.clone());
+            env.storage().persistent().set(&keys::ASSETS, &assets);
+        }
+
         env.events().publish(
             (extended_topics::ASSET_REGISTERED,),
             AssetRegisteredEvent {
@@ -139,7 +254,8 @@ impl SyntheticAssetsContract {
         Ok(())
     }
 
-    /// Update oracle price (called by authorized oracle)
+    /// Update oracle price (called by authorized oracle). In oracle mode this
+    /// acts as a manual override; the next pull is checked against it.
     pub fn update_price(
         env: Env,
         caller: Address,
@@ -148,6 +264,7 @@ impl SyntheticAssetsContract {
     ) -> Result<(), Error> {
         caller.require_auth();
         Self::require_admin(&env, &caller)?;
+        Self::require_not_shutdown(&env)?;
 
         // Reject non-positive prices to prevent division-by-zero in CDP math
         // and to keep the oracle from silently feeding bogus data downstream.
@@ -179,6 +296,154 @@ impl SyntheticAssetsContract {
         Ok(())
     }
 
+    /// Put the asset in oracle mode: prices are pulled from `sources` and
+    /// medianised whenever a CDP operation needs them
+    pub fn set_oracle_config(
+        env: Env,
+        caller: Address,
+        asset_symbol: Symbol,
+        oracle: OracleConfig,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_admin(&env, &caller)?;
+
+        if !env.storage().persistent().has(&asset_symbol) {
+            return Err(Error::AssetNotFound);
+        }
+        if oracle.min_sources == 0
+            || oracle.min_sources > oracle.sources.len()
+            || oracle.max_deviation_bps == 0
+        {
+            return Err(Error::InvalidConfig);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Self::oracle_key(&asset_symbol), &oracle);
+
+        env.events().publish(
+            (extended_topics::ORACLE_CONFIG_UPDATED, asset_symbol),
+            (oracle.sources, oracle.min_sources, oracle.max_deviation_bps),
+        );
+        Ok(())
+    }
+
+    /// Return the asset to admin-pushed prices
+    pub fn clear_oracle_config(
+        env: Env,
+        caller: Address,
+        asset_symbol: Symbol,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_admin(&env, &caller)?;
+
+        env.storage()
+            .persistent()
+            .remove(&Self::oracle_key(&asset_symbol));
+
+        env.events().publish(
+            (extended_topics::ORACLE_CONFIG_UPDATED, asset_symbol),
+            (),
+        );
+        Ok(())
+    }
+
+    /// Pull the asset's oracle price and store it. Permissionless; returns
+    /// the price CDP operations would use now.
+    pub fn refresh_price(env: Env, asset_symbol: Symbol) -> Result<i128, Error> {
+        let mut config: SyntheticConfig = env
+            .storage()
+            .persistent()
+            .get(&asset_symbol)
+            .ok_or(Error::AssetNotFound)?;
+
+        Self::require_valid_price(&env, &asset_symbol, &mut config)?;
+        env.storage().persistent().set(&asset_symbol, &config);
+        Ok(config.oracle_price)
+    }
+
+    /// Approve an extra collateral token for the asset's CDPs, or update the
+    /// haircut of an approved one. Its value counts towards the collateral
+    /// ratio discounted by `haircut_bps`.
+    pub fn approve_collateral(
+        env: Env,
+        caller: Address,
+        asset_symbol: Symbol,
+        token: Address,
+        haircut_bps: i128,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_admin(&env, &caller)?;
+
+        let config: SyntheticConfig = env
+            .storage()
+            .persistent()
+            .get(&asset_symbol)
+            .ok_or(Error::AssetNotFound)?;
+        if token == config.collateral_token || !(0..10000).contains(&haircut_bps) {
+            return Err(Error::InvalidConfig);
+        }
+
+        let mut types = Self::collateral_types(&env, &asset_symbol);
+        let ct = match types.get(token.clone()) {
+            Some(mut ct) => {
+                ct.haircut_bps = haircut_bps;
+                ct
+            }
+            None => CollateralType {
+                haircut_bps,
+                price: 0,
+                last_updated: 0,
+            },
+        };
+        types.set(token.clone(), ct);
+        env.storage()
+            .persistent()
+            .set(&Self::collateral_types_key(&asset_symbol), &types);
+
+        env.events().publish(
+            (extended_topics::COLLATERAL_APPROVED, asset_symbol),
+            (token, haircut_bps),
+        );
+        Ok(())
+    }
+
+    /// Update the price feed of an approved basket collateral token, in
+    /// token units per synthetic scaled by 1_000_000
+    pub fn update_collateral_price(
+        env: Env,
+        caller: Address,
+        asset_symbol: Symbol,
+        token: Address,
+        new_price: i128,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_admin(&env, &caller)?;
+        Self::require_not_shutdown(&env)?;
+
+        if new_price <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+
+        let mut types = Self::collateral_types(&env, &asset_symbol);
+        let mut ct = types
+            .get(token.clone())
+            .ok_or(Error::CollateralNotApproved)?;
+        let old_price = ct.price;
+        ct.price = new_price;
+        ct.last_updated = env.ledger().timestamp();
+        types.set(token.clone(), ct);
+        env.storage()
+            .persistent()
+            .set(&Self::collateral_types_key(&asset_symbol), &types);
+
+        env.events().publish(
+            (extended_topics::COLLATERAL_PRICE_UPDATED, asset_symbol),
+            (token, old_price, new_price),
+        );
+        Ok(())
+    }
+
     /// Open CDP: transfer collateral from owner into the contract
     pub fn open_cdp(
         env: Env,
@@ -187,6 +452,7 @@ impl SyntheticAssetsContract {
         collateral_amount: i128,
     ) -> Result<(), Error> {
         owner.require_auth();
+        Self::require_not_shutdown(&env)?;
 
         if collateral_amount <= 0 {
             return Err(Error::InvalidAmount);
@@ -199,6 +465,16 @@ impl SyntheticAssetsContract {
             .ok_or(Error::AssetNotFound)?;
 
         let cdp_key = Self::cdp_key(&owner, &asset_symbol);
+        // Overwriting a live CDP would strand its basket and orphan its debt
+        if let Some(existing) = env.storage().persistent().get::<_, CDP>(&cdp_key) {
+            if existing.is_active
+                || existing.normalized_debt != 0
+                || existing.collateral_amount != 0
+                || !existing.basket.is_empty()
+            {
+                return Err(Error::CDPAlreadyOpen);
+            }
+        }
         let cdp = CDP {
             owner: owner.clone(),
             collateral_amount,
@@ -209,6 +485,8 @@ impl SyntheticAssetsContract {
             // CDP.
             collateral_ratio: i128::MAX,
             is_active: true,
+            normalized_debt: 0,
+            basket: Map::new(&env),
         };
         env.storage().persistent().set(&cdp_key, &cdp);
 
@@ -239,18 +517,20 @@ impl SyntheticAssetsContract {
         mint_amount: i128,
     ) -> Result<i128, Error> {
         owner.require_auth();
+        Self::require_not_shutdown(&env)?;
 
         if mint_amount <= 0 {
             return Err(Error::InvalidAmount);
         }
 
-        let config: SyntheticConfig = env
+        let mut config: SyntheticConfig = env
             .storage()
             .persistent()
             .get(&asset_symbol)
             .ok_or(Error::AssetNotFound)?;
 
-        Self::require_valid_price(&env, &config)?;
+        Self::require_valid_price(&env, &asset_symbol, &mut config)?;
+        Self::accrue(&env, &asset_symbol, &mut config);
 
         let cdp_key = Self::cdp_key(&owner, &asset_symbol);
         let mut cdp: CDP = env
@@ -259,21 +539,37 @@ impl SyntheticAssetsContract {
             .get(&cdp_key)
             .ok_or(Error::CDPNotFound)?;
 
-        let new_minted = cdp.minted_amount + mint_amount;
-        let collateral_usd = cdp.collateral_amount * 1_000_000 / config.oracle_price;
-        let cratio = collateral_usd * 10000 / new_minted;
+        // Round the added normalized debt up so rounding never favours the borrower
+        let added_normalized = Self::ceil_div(
+            mint_amount
+                .checked_mul(RATE_PRECISION)
+                .ok_or(Error::ArithmeticError)?,
+            config.rate_index,
+        );
+        let new_normalized = cdp.normalized_debt + added_normalized;
+        let new_minted = Self::debt_of(new_normalized, config.rate_index);
+        let (collateral_usd, _) = Self::cdp_values(&env, &asset_symbol, &config, &cdp)?;
+        let cratio =
+            liquidation::cratio(collateral_usd, new_minted).ok_or(Error::ArithmeticError)?;
 
         if cratio < config.min_cratio {
             return Err(Error::BelowMinCratio);
         }
+        if new_minted < config.min_debt {
+            return Err(Error::DebtBelowMinimum);
+        }
+        Self::require_within_ceilings(&env, &asset_symbol, &config, mint_amount)?;
 
+        cdp.normalized_debt = new_normalized;
         cdp.minted_amount = new_minted;
         cdp.collateral_ratio = cratio;
 
         let mut updated_config = config.clone();
         updated_config.total_minted += mint_amount;
+        updated_config.total_normalized_debt += added_normalized;
 
         env.storage().persistent().set(&cdp_key, &cdp);
+        Self::index_cdp(&env, &asset_symbol, &owner, true);
         env.storage()
             .persistent()
             .set(&asset_symbol, &updated_config);
@@ -292,18 +588,20 @@ impl SyntheticAssetsContract {
         burn_amount: i128,
     ) -> Result<(), Error> {
         owner.require_auth();
+        Self::require_not_shutdown(&env)?;
 
         if burn_amount <= 0 {
             return Err(Error::InvalidAmount);
         }
 
-        let config: SyntheticConfig = env
+        let mut config: SyntheticConfig = env
             .storage()
             .persistent()
             .get(&asset_symbol)
             .ok_or(Error::AssetNotFound)?;
 
-        Self::require_valid_price(&env, &config)?;
+        Self::require_valid_price(&env, &asset_symbol, &mut config)?;
+        Self::accrue(&env, &asset_symbol, &mut config);
 
         let cdp_key = Self::cdp_key(&owner, &asset_symbol);
         let mut cdp: CDP = env
@@ -312,15 +610,28 @@ impl SyntheticAssetsContract {
             .get(&cdp_key)
             .ok_or(Error::CDPNotFound)?;
 
-        if burn_amount > cdp.minted_amount {
+        let debt = Self::debt_of(cdp.normalized_debt, config.rate_index);
+        if burn_amount > debt {
             return Err(Error::InvalidAmount);
         }
 
+        // Repaying the full debt clears the position exactly; partial
+        // repayments round the normalized reduction down.
+        let removed_normalized = if burn_amount == debt {
+            cdp.normalized_debt
+        } else {
+            (burn_amount * RATE_PRECISION / config.rate_index).min(cdp.normalized_debt)
+        };
+
         // Update CDP and aggregate state before performing the external token
         // burn so the on-chain accounting matches the token movement. (Soroban
         // rolls back atomically on panic, so a failed burn will still revert
         // the storage write.)
-        cdp.minted_amount -= burn_amount;
+        cdp.normalized_debt -= removed_normalized;
+        cdp.minted_amount = Self::debt_of(cdp.normalized_debt, config.rate_index);
+        if cdp.minted_amount > 0 && cdp.minted_amount < config.min_debt {
+            return Err(Error::DebtBelowMinimum);
+        }
 
         if cdp.minted_amount > 0 {
             if config.oracle_price <= 0 {
@@ -330,18 +641,23 @@ impl SyntheticAssetsContract {
                 // processes decide what to do next.
                 cdp.collateral_ratio = 0;
             } else {
-                let collateral_usd =
-                    cdp.collateral_amount * 1_000_000 / config.oracle_price;
-                cdp.collateral_ratio = collateral_usd * 10000 / cdp.minted_amount;
+                let (collateral_usd, _) =
+                    Self::cdp_values(&env, &asset_symbol, &config, &cdp)?;
+                cdp.collateral_ratio = liquidation::cratio(collateral_usd, cdp.minted_amount)
+                    .ok_or(Error::ArithmeticError)?;
             }
         } else {
             cdp.collateral_ratio = i128::MAX;
         }
 
         let mut updated_config = config.clone();
-        updated_config.total_minted -= burn_amount;
+        updated_config.total_minted = (updated_config.total_minted - burn_amount).max(0);
+        updated_config.total_normalized_debt -= removed_normalized;
 
         env.storage().persistent().set(&cdp_key, &cdp);
+        if cdp.normalized_debt == 0 {
+            Self::index_cdp(&env, &asset_symbol, &owner, false);
+        }
         env.storage()
             .persistent()
             .set(&asset_symbol, &updated_config);
@@ -361,18 +677,21 @@ impl SyntheticAssetsContract {
         amount: i128,
     ) -> Result<(), Error> {
         owner.require_auth();
+        Self::require_not_shutdown(&env)?;
 
         if amount <= 0 {
             return Err(Error::InvalidAmount);
         }
 
-        let config: SyntheticConfig = env
+        let mut config: SyntheticConfig = env
             .storage()
             .persistent()
             .get(&asset_symbol)
             .ok_or(Error::AssetNotFound)?;
 
-        Self::require_valid_price(&env, &config)?;
+        Self::require_valid_price(&env, &asset_symbol, &mut config)?;
+        Self::accrue(&env, &asset_symbol, &mut config);
+        env.storage().persistent().set(&asset_symbol, &config);
 
         let cdp_key = Self::cdp_key(&owner, &asset_symbol);
         let mut cdp: CDP = env
@@ -382,14 +701,16 @@ impl SyntheticAssetsContract {
             .ok_or(Error::CDPNotFound)?;
 
         cdp.collateral_amount += amount;
+        cdp.minted_amount = Self::debt_of(cdp.normalized_debt, config.rate_index);
 
         if cdp.minted_amount > 0 {
             if config.oracle_price <= 0 {
                 cdp.collateral_ratio = 0;
             } else {
-                let collateral_usd =
-                    cdp.collateral_amount * 1_000_000 / config.oracle_price;
-                cdp.collateral_ratio = collateral_usd * 10000 / cdp.minted_amount;
+                let (collateral_usd, _) =
+                    Self::cdp_values(&env, &asset_symbol, &config, &cdp)?;
+                cdp.collateral_ratio = liquidation::cratio(collateral_usd, cdp.minted_amount)
+                    .ok_or(Error::ArithmeticError)?;
             }
         }
 
@@ -412,105 +733,98 @@ impl SyntheticAssetsContract {
         Ok(())
     }
 
-    /// Liquidate an undercollateralized CDP
-    pub fn liquidate(
+    /// Deposit `amount` of an approved collateral token into the CDP's
+    /// basket. Depositing the asset's own `collateral_token` is the same as
+    /// `add_collateral`.
+    pub fn deposit_collateral(
         env: Env,
-        liquidator: Address,
-        cdp_owner: Address,
+        owner: Address,
         asset_symbol: Symbol,
-    ) -> Result<i128, Error> {
-        liquidator.require_auth();
-
-        let config: SyntheticConfig = env
+        token: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let mut config: SyntheticConfig = env
             .storage()
             .persistent()
             .get(&asset_symbol)
             .ok_or(Error::AssetNotFound)?;
+        if token == config.collateral_token {
+            return Self::add_collateral(env, owner, asset_symbol, amount);
+        }
 
-        Self::require_valid_price(&env, &config)?;
+        owner.require_auth();
+        Self::require_not_shutdown(&env)?;
 
-        let cdp_key = Self::cdp_key(&cdp_owner, &asset_symbol);
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if !Self::collateral_types(&env, &asset_symbol).contains_key(token.clone()) {
+            return Err(Error::CollateralNotApproved);
+        }
+
+        Self::require_valid_price(&env, &asset_symbol, &mut config)?;
+        Self::accrue(&env, &asset_symbol, &mut config);
+        env.storage().persistent().set(&asset_symbol, &config);
+
+        let cdp_key = Self::cdp_key(&owner, &asset_symbol);
         let mut cdp: CDP = env
             .storage()
             .persistent()
             .get(&cdp_key)
             .ok_or(Error::CDPNotFound)?;
 
-        // Recompute the *live* ratio from current state. `cdp.collateral_ratio`
-        // is a mint-time snapshot that is not refreshed by `update_price`, so
-        // an oracle movement is only visible through the live recomputation
-        // below:
-        //   * zero debt       => effectively infinitely healthy (MAX),
-        //                        so the CDP is never liquidatable;
-        //   * zero oracle     => immediately liquidatable (live cratio = 0);
-        //   * otherwise       => (collateral * 1e6 / price * 10000) / debt.
-        let live_cratio: i128 = if cdp.minted_amount <= 0 {
-            i128::MAX
-        } else if config.oracle_price <= 0 {
-            0
-        } else {
-            let collateral_usd =
-                cdp.collateral_amount * 1_000_000 / config.oracle_price;
-            collateral_usd * 10000 / cdp.minted_amount
-        };
-        if live_cratio >= config.liq_cratio {
-            return Err(Error::NotLiquidatable);
+        let held = cdp.basket.get(token.clone()).unwrap_or(0);
+        Self::set_basket_amount(&mut cdp, &token, held + amount);
+        cdp.minted_amount = Self::debt_of(cdp.normalized_debt, config.rate_index);
+        if cdp.minted_amount > 0 {
+            let (collateral_usd, _) = Self::cdp_values(&env, &asset_symbol, &config, &cdp)?;
+            cdp.collateral_ratio = liquidation::cratio(collateral_usd, cdp.minted_amount)
+                .ok_or(Error::ArithmeticError)?;
         }
 
-        let penalty_collateral = cdp.collateral_amount * config.liq_penalty / 10000;
-        let seized = cdp
-            .collateral_amount
-            .min(cdp.collateral_amount - penalty_collateral);
-
-        let debt = cdp.minted_amount;
-
-        let mut updated_config = config.clone();
-        updated_config.total_minted -= debt;
-
-        cdp.is_active = false;
-        cdp.minted_amount = 0;
-        cdp.collateral_amount = 0;
-
         env.storage().persistent().set(&cdp_key, &cdp);
-        env.storage()
-            .persistent()
-            .set(&asset_symbol, &updated_config);
-
-        // Liquidator burns the debt tokens to repay the position
-        TokenClient::new(&env, &config.synthetic_token).burn(&liquidator, &debt);
 
-        // Transfer seized collateral to the liquidator
-        TokenClient::new(&env, &config.collateral_token).transfer(
-            &env.current_contract_address(),
-            &liquidator,
-            &seized,
-        );
+        TokenClient::new(&env, &token).transfer(&owner, &env.current_contract_address(), &amount);
 
         env.events().publish(
-            (extended_topics::CDP_LIQUIDATED,),
-            CdpLiquidatedEvent {
-                owner: cdp_owner,
-                liquidator,
+            (extended_topics::COLLATERAL_ADDED,),
+            CollateralAddedEvent {
+                owner,
                 asset_symbol,
-                collateral_seized: seized,
-                debt_repaid: debt,
+                amount,
+                new_ratio: cdp.collateral_ratio as u32,
                 timestamp: env.ledger().timestamp(),
             },
         );
-
-        Ok(seized)
+        Ok(())
     }
 
-    /// Close a CDP with zero debt and return collateral
-    pub fn close_cdp(env: Env, owner: Address, asset_symbol: Symbol) -> Result<i128, Error> {
+    /// Withdraw `amount` of a collateral token, primary or basket, from the
+    /// CDP. A CDP with debt must stay at or above `min_cratio`.
+    pub fn withdraw_collateral(
+        env: Env,
+        owner: Address,
+        asset_symbol: Symbol,
+        token: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
         owner.require_auth();
+        Self::require_not_shutdown(&env)?;
 
-        let config: SyntheticConfig = env
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut config: SyntheticConfig = env
             .storage()
             .persistent()
             .get(&asset_symbol)
             .ok_or(Error::AssetNotFound)?;
 
+        Self::require_valid_price(&env, &asset_symbol, &mut config)?;
+        Self::accrue(&env, &asset_symbol, &mut config);
+        env.storage().persistent().set(&asset_symbol, &config);
+
         let cdp_key = Self::cdp_key(&owner, &asset_symbol);
         let mut cdp: CDP = env
             .storage()
@@ -518,60 +832,1147 @@ impl SyntheticAssetsContract {
             .get(&cdp_key)
             .ok_or(Error::CDPNotFound)?;
 
-        if cdp.minted_amount > 0 {
-            return Err(Error::InsufficientCollateral);
+        if token == config.collateral_token {
+            if amount > cdp.collateral_amount {
+                return Err(Error::InsufficientCollateral);
+            }
+            cdp.collateral_amount -= amount;
+        } else {
+            let held = cdp.basket.get(token.clone()).unwrap_or(0);
+            if amount > held {
+                return Err(Error::InsufficientCollateral);
+            }
+            Self::set_basket_amount(&mut cdp, &token, held - amount);
         }
 
-        let returned = cdp.collateral_amount;
-        cdp.is_active = false;
-        cdp.collateral_amount = 0;
+        cdp.minted_amount = Self::debt_of(cdp.normalized_debt, config.rate_index);
+        if cdp.minted_amount > 0 {
+            let (collateral_usd, _) = Self::cdp_values(&env, &asset_symbol, &config, &cdp)?;
+            cdp.collateral_ratio = liquidation::cratio(collateral_usd, cdp.minted_amount)
+                .ok_or(Error::ArithmeticError)?;
+            if cdp.collateral_ratio < config.min_cratio {
+                return Err(Error::BelowMinCratio);
+            }
+        }
 
         env.storage().persistent().set(&cdp_key, &cdp);
 
-        // Return collateral to owner
-        TokenClient::new(&env, &config.collateral_token).transfer(
-            &env.current_contract_address(),
-            &owner,
-            &returned,
-        );
+        TokenClient::new(&env, &token).transfer(&env.current_contract_address(), &owner, &amount);
 
         env.events().publish(
-            (extended_topics::CDP_CLOSED,),
-            CdpClosedEvent {
+            (extended_topics::COLLATERAL_WITHDRAWN,),
+            CollateralWithdrawnEvent {
                 owner,
                 asset_symbol,
-                collateral_returned: returned,
+                token,
+                amount,
+                new_ratio: cdp.collateral_ratio as u32,
                 timestamp: env.ledger().timestamp(),
             },
         );
-        Ok(returned)
+        Ok(())
     }
 
-    /// View CDP info
-    pub fn get_cdp(env: Env, owner: Address, asset_symbol: Symbol) -> Result<CDP, Error> {
-        let cdp_key = Self::cdp_key(&owner, &asset_symbol);
-        env.storage()
-            .persistent()
-            .get(&cdp_key)
-            .ok_or(Error::CDPNotFound)
-    }
+    /// Set the asset's close factor and Dutch-auction parameters
+    pub fn set_liquidation_config(
+        env: Env,
+        caller: Address,
+        asset_symbol: Symbol,
+        liq_config: LiquidationConfig,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_admin(&env, &caller)?;
+
+        if !env.storage().persistent().has(&asset_symbol) {
+            return Err(Error::AssetNotFound);
+        }
+        if !liq_config.is_valid() {
+            return Err(Error::InvalidConfig);
+        }
 
-    /// View asset config
-    pub fn get_config(env: Env, asset_symbol: Symbol) -> Result<SyntheticConfig, Error> {
         env.storage()
             .persistent()
-            .get(&asset_symbol)
-            .ok_or(Error::AssetNotFound)
-    }
+            .set(&Self::liq_config_key(&asset_symbol), &liq_config);
+
+        env.events().publish(
+            (extended_topics::LIQ_CONFIG_UPDATED, asset_symbol),
+            liq_config,
+        );
+        Ok(())
+    }
+
+    /// Set an asset's debt ceiling (cap on `total_minted`) and minimum CDP
+    /// debt. Zero disables either limit. Lowering the ceiling below the
+    /// outstanding supply only blocks further minting.
+    pub fn set_debt_limits(
+        env: Env,
+        caller: Address,
+        asset_symbol: Symbol,
+        debt_ceiling: i128,
+        min_debt: i128,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_admin(&env, &caller)?;
+
+        let mut config: SyntheticConfig = env
+            .storage()
+            .persistent()
+            .get(&asset_symbol)
+            .ok_or(Error::AssetNotFound)?;
+        if debt_ceiling < 0 || min_debt < 0 {
+            return Err(Error::InvalidConfig);
+        }
+        if debt_ceiling > 0 && min_debt > debt_ceiling {
+            return Err(Error::InvalidConfig);
+        }
+
+        config.debt_ceiling = debt_ceiling;
+        config.min_debt = min_debt;
+        env.storage().persistent().set(&asset_symbol, &config);
+
+        env.events().publish(
+            (extended_topics::DEBT_LIMITS_UPDATED, asset_symbol),
+            (debt_ceiling, min_debt),
+        );
+        Ok(())
+    }
+
+    /// Set the ceiling on the value of `total_minted` summed over every
+    /// asset; zero disables it. Each asset's debt is valued at its
+    /// `oracle_price`, so the ceiling is in collateral units and only
+    /// meaningful when every asset quotes against collateral of the same
+    /// value.
+    pub fn set_global_debt_ceiling(
+        env: Env,
+        caller: Address,
+        debt_ceiling: i128,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_admin(&env, &caller)?;
+
+        if debt_ceiling < 0 {
+            return Err(Error::InvalidConfig);
+        }
+        env.storage()
+            .instance()
+            .set(&keys::GLOBAL_CEILING, &debt_ceiling);
+
+        env.events().publish(
+            (extended_topics::GLOBAL_CEILING_UPDATED,),
+            (caller, debt_ceiling),
+        );
+        Ok(())
+    }
+
+    /// Liquidate an undercollateralized CDP.
+    ///
+    /// Repays at most `close_factor_bps` of the debt — just enough to lift
+    /// the CDP back to `liq_cratio` — and pays the liquidator that amount
+    /// plus `liq_penalty` in collateral, primary collateral first and then
+    /// the basket; the CDP stays open. A CDP too undercollateralized for
+    /// that to help is liquidated in full, its basket tokens net of the
+    /// penalty share going to the liquidator and that share back to the
+    /// owner. Returns the primary collateral seized; seized basket tokens
+    /// are transferred alongside.
+    pub fn liquidate(
+        env: Env,
+        liquidator: Address,
+        cdp_owner: Address,
+        asset_symbol: Symbol,
+    ) -> Result<i128, Error> {
+        liquidator.require_auth();
+        Self::require_not_shutdown(&env)?;
+
+        let mut config: SyntheticConfig = env
+            .storage()
+            .persistent()
+            .get(&asset_symbol)
+            .ok_or(Error::AssetNotFound)?;
+
+        Self::require_valid_price(&env, &asset_symbol, &mut config)?;
+        if Self::liquidation_config(&env, &asset_symbol).auction_enabled {
+            return Err(Error::AuctionOnly);
+        }
+        Self::accrue(&env, &asset_symbol, &mut config);
+
+        let cdp_key = Self::cdp_key(&cdp_owner, &asset_symbol);
+        let mut cdp: CDP = env
+            .storage()
+            .persistent()
+            .get(&cdp_key)
+            .ok_or(Error::CDPNotFound)?;
+        cdp.minted_amount = Self::debt_of(cdp.normalized_debt, config.rate_index);
+
+        // Recompute the *live* ratio from current state. `cdp.collateral_ratio`
+        // is a mint-time snapshot that is not refreshed by `update_price`, so
+        // an oracle movement is only visible through the live recomputation
+        // below:
+        //   * zero debt       => effectively infinitely healthy (MAX),
+        //                        so the CDP is never liquidatable;
+        //   * otherwise       => (haircut-weighted basket value * 10000) / debt.
+        let (value, market_value) = Self::cdp_values(&env, &asset_symbol, &config, &cdp)?;
+        let live_cratio =
+            liquidation::cratio(value, cdp.minted_amount).ok_or(Error::ArithmeticError)?;
+        if live_cratio >= config.liq_cratio {
+            return Err(Error::NotLiquidatable);
+        }
+
+        let mut basket_seized: Vec<(Address, i128)> = Vec::new(&env);
+        let mut basket_returned: Vec<(Address, i128)> = Vec::new(&env);
+        let (seized, debt, removed_normalized) =
+            if liquidation::is_underwater(market_value, cdp.minted_amount, config.liq_penalty) {
+                let penalty_collateral = cdp.collateral_amount * config.liq_penalty / 10000;
+                let seized = cdp
+                    .collateral_amount
+                    .min(cdp.collateral_amount - penalty_collateral);
+                // The basket's penalty share goes back to the owner
+                for (token, amount) in cdp.basket.iter() {
+                    let kept = amount * config.liq_penalty / 10000;
+                    basket_seized.push_back((token.clone(), amount - kept));
+                    if kept > 0 {
+                        basket_returned.push_back((token, kept));
+                    }
+                }
+                let debt = cdp.minted_amount;
+                let removed_normalized = cdp.normalized_debt;
+
+                cdp.is_active = false;
+                cdp.minted_amount = 0;
+                cdp.normalized_debt = 0;
+                cdp.collateral_amount = 0;
+                cdp.basket = Map::new(&env);
+                (seized, debt, removed_normalized)
+            } else {
+                let close_factor = Self::liquidation_config(&env, &asset_symbol).close_factor_bps;
+                // A primary-only CDP is solved in collateral units, a basket
+                // from its weighted value
+                let (collateral, price) = if cdp.basket.is_empty() {
+                    (cdp.collateral_amount, config.oracle_price)
+                } else {
+                    (value, liquidation::PRICE_SCALE)
+                };
+                let mut debt = liquidation::partial_repay(
+                    collateral,
+                    cdp.minted_amount,
+                    price,
+                    config.liq_cratio,
+                    config.liq_penalty,
+                    close_factor,
+                );
+                // Never leave dust behind: repay the whole debt instead
+                if cdp.minted_amount - debt < config.min_debt {
+                    debt = cdp.minted_amount;
+                }
+
+                // Seize the primary collateral first, then the basket in
+                // token order, until debt plus penalty is covered
+                let mut owed = liquidation::seize_value_bps(debt, config.liq_penalty);
+                let (seized, covered) =
+                    liquidation::take_collateral(owed, config.oracle_price, cdp.collateral_amount);
+                owed -= covered;
+                cdp.collateral_amount -= seized;
+                if owed > 0 {
+                    let types = Self::collateral_types(&env, &asset_symbol);
+                    for (token, amount) in cdp.basket.iter() {
+                        let Some(ct) = types.get(token.clone()) else {
+                            continue;
+                        };
+                        let (taken, covered) =
+                            liquidation::take_collateral(owed, ct.price, amount);
+                        owed -= covered;
+                        if taken > 0 {
+                            Self::set_basket_amount(&mut cdp, &token, amount - taken);
+                            basket_seized.push_back((token, taken));
+                        }
+                        if owed == 0 {
+                            break;
+                        }
+                    }
+                }
+
+                // Round the normalized reduction down, as a partial `burn` does
+                let removed_normalized = if debt == cdp.minted_amount {
+                    cdp.normalized_debt
+                } else {
+                    (debt * RATE_PRECISION / config.rate_index).min(cdp.normalized_debt)
+                };
+
+                cdp.normalized_debt -= removed_normalized;
+                cdp.minted_amount = Self::debt_of(cdp.normalized_debt, config.rate_index);
+                let (value, _) = Self::cdp_values(&env, &asset_symbol, &config, &cdp)?;
+                cdp.collateral_ratio =
+                    liquidation::cratio(value, cdp.minted_amount).ok_or(Error::ArithmeticError)?;
+                (seized, debt, removed_normalized)
+            };
+
+        let mut updated_config = config.clone();
+        updated_config.total_minted = (updated_config.total_minted - debt).max(0);
+        updated_config.total_normalized_debt -= removed_normalized;
+
+        env.storage().persistent().set(&cdp_key, &cdp);
+        if cdp.normalized_debt == 0 {
+            Self::index_cdp(&env, &asset_symbol, &cdp_owner, false);
+        }
+        env.storage()
+            .persistent()
+            .set(&asset_symbol, &updated_config);
+
+        // Liquidator burns the debt tokens to repay the position
+        TokenClient::new(&env, &config.synthetic_token).burn(&liquidator, &debt);
+
+        // Transfer seized collateral to the liquidator
+        TokenClient::new(&env, &config.collateral_token).transfer(
+            &env.current_contract_address(),
+            &liquidator,
+            &seized,
+        );
+        for (token, amount) in basket_seized.iter() {
+            TokenClient::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &liquidator,
+                &amount,
+            );
+        }
+        for (token, amount) in basket_returned.iter() {
+            TokenClient::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &cdp_owner,
+                &amount,
+            );
+        }
+
+        env.events().publish(
+            (extended_topics::CDP_LIQUIDATED,),
+            CdpLiquidatedEvent {
+                owner: cdp_owner,
+                liquidator,
+                asset_symbol,
+                collateral_seized: seized,
+                debt_repaid: debt,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+
+        Ok(seized)
+    }
+
+    /// Move an undercollateralized CDP into a Dutch auction. The whole
+    /// collateral, basket included, is offered to bidders at a price
+    /// decaying from `auction_start_bps` to `auction_floor_bps` of its
+    /// current feed value; whatever is left once the debt and penalty are
+    /// raised goes back to the owner. Permissionless; returns the auction id.
+    pub fn start_auction(
+        env: Env,
+        caller: Address,
+        cdp_owner: Address,
+        asset_symbol: Symbol,
+    ) -> Result<u64, Error> {
+        caller.require_auth();
+        Self::require_not_shutdown(&env)?;
+
+        let mut config: SyntheticConfig = env
+            .storage()
+            .persistent()
+            .get(&asset_symbol)
+            .ok_or(Error::AssetNotFound)?;
+
+        Self::require_valid_price(&env, &asset_symbol, &mut config)?;
+        if !Self::liquidation_config(&env, &asset_symbol).auction_enabled {
+            return Err(Error::AuctionsDisabled);
+        }
+        Self::accrue(&env, &asset_symbol, &mut config);
+
+        let cdp_key = Self::cdp_key(&cdp_owner, &asset_symbol);
+        let mut cdp: CDP = env
+            .storage()
+            .persistent()
+            .get(&cdp_key)
+            .ok_or(Error::CDPNotFound)?;
+        let debt = Self::debt_of(cdp.normalized_debt, config.rate_index);
+
+        let (value, _) = Self::cdp_values(&env, &asset_symbol, &config, &cdp)?;
+        if liquidation::cratio(value, debt).ok_or(Error::ArithmeticError)? >= config.liq_cratio {
+            return Err(Error::NotLiquidatable);
+        }
+
+        let types = Self::collateral_types(&env, &asset_symbol);
+        let mut basket = Map::new(&env);
+        for (token, amount) in cdp.basket.iter() {
+            let price = types.get(token.clone()).map(|ct| ct.price).unwrap_or(0);
+            basket.set(token, BasketLot { amount, price });
+        }
+
+        let id: u64 = env
+            .storage()
+            .persistent()
+            .get(&keys::AUCTION_COUNT)
+            .unwrap_or(0u64)
+            + 1;
+        let auction = Auction {
+            id,
+            owner: cdp_owner.clone(),
+            asset_symbol: asset_symbol.clone(),
+            collateral: cdp.collateral_amount,
+            debt,
+            penalty: debt * config.liq_penalty / 10000,
+            start_price: config.oracle_price,
+            started_at: env.ledger().timestamp(),
+            is_active: true,
+            basket,
+        };
+
+        // The debt leaves the CDP books now; the synthetic supply backing it
+        // is burned as bidders repay it.
+        config.total_normalized_debt -= cdp.normalized_debt;
+        cdp.is_active = false;
+        cdp.minted_amount = 0;
+        cdp.normalized_debt = 0;
+        cdp.collateral_amount = 0;
+        cdp.basket = Map::new(&env);
+        Self::index_cdp(&env, &asset_symbol, &cdp_owner, false);
+
+        env.storage().persistent().set(&cdp_key, &cdp);
+        env.storage().persistent().set(&asset_symbol, &config);
+        env.storage()
+            .persistent()
+            .set(&Self::auction_key(id), &auction);
+        env.storage().persistent().set(&keys::AUCTION_COUNT, &id);
+        Self::count_open_auction(&env, &asset_symbol, true);
+
+        env.events().publish(
+            (extended_topics::AUCTION_STARTED,),
+            AuctionStartedEvent {
+                auction_id: id,
+                owner: cdp_owner,
+                asset_symbol,
+                collateral: auction.collateral,
+                debt: auction.debt,
+                penalty: auction.penalty,
+                start_price: auction.start_price,
+                timestamp: auction.started_at,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Buy primary collateral from an auction at its current price, paying
+    /// at most `max_payment` synthetic tokens. Payments burn the outstanding
+    /// debt first; the penalty that follows is booked to the surplus buffer.
+    /// Returns the collateral bought.
+    pub fn bid(
+        env: Env,
+        bidder: Address,
+        auction_id: u64,
+        max_payment: i128,
+    ) -> Result<i128, Error> {
+        bidder.require_auth();
+        Self::require_not_shutdown(&env)?;
+        Self::do_bid(&env, bidder, auction_id, None, max_payment)
+    }
+
+    /// Buy basket collateral `token` from an auction; see `bid`.
+    pub fn bid_basket(
+        env: Env,
+        bidder: Address,
+        auction_id: u64,
+        token: Address,
+        max_payment: i128,
+    ) -> Result<i128, Error> {
+        bidder.require_auth();
+        Self::require_not_shutdown(&env)?;
+        Self::do_bid(&env, bidder, auction_id, Some(token), max_payment)
+    }
+
+    /// Close a CDP with zero debt and return collateral. Returns the primary
+    /// collateral; basket tokens are returned alongside.
+    pub fn close_cdp(env: Env, owner: Address, asset_symbol: Symbol) -> Result<i128, Error> {
+        owner.require_auth();
+
+        let config: SyntheticConfig = env
+            .storage()
+            .persistent()
+            .get(&asset_symbol)
+            .ok_or(Error::AssetNotFound)?;
+
+        let cdp_key = Self::cdp_key(&owner, &asset_symbol);
+        let mut cdp: CDP = env
+            .storage()
+            .persistent()
+            .get(&cdp_key)
+            .ok_or(Error::CDPNotFound)?;
+
+        // Any outstanding normalized debt keeps accruing fees and must be repaid
+        if cdp.normalized_debt > 0 {
+            return Err(Error::InsufficientCollateral);
+        }
+
+        let returned = cdp.collateral_amount;
+        let basket = cdp.basket.clone();
+        cdp.is_active = false;
+        cdp.collateral_amount = 0;
+        cdp.basket = Map::new(&env);
+
+        env.storage().persistent().set(&cdp_key, &cdp);
+
+        // Return collateral to owner
+        TokenClient::new(&env, &config.collateral_token).transfer(
+            &env.current_contract_address(),
+            &owner,
+            &returned,
+        );
+        for (token, amount) in basket.iter() {
+            TokenClient::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &owner,
+                &amount,
+            );
+        }
+
+        env.events().publish(
+            (extended_topics::CDP_CLOSED,),
+            CdpClosedEvent {
+                owner,
+                asset_symbol,
+                collateral_returned: returned,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        Ok(returned)
+    }
+
+    // ── Global settlement ─────────────────────────────────────────────────────
+
+    /// Emergency shutdown: freeze the final price of every registered
+    /// synthetic and stop minting, repayments, liquidations and price
+    /// updates. CDP debt is then settled at the frozen prices through
+    /// `settle_cdp` and `settle_auction`, and holders `redeem` synthetic
+    /// tokens for a pro-rata share of the settled collateral once
+    /// `redemption_delay` seconds have passed and every CDP and auction of
+    /// the asset has been settled.
+    pub fn shutdown(env: Env, caller: Address, redemption_delay: u64) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_admin(&env, &caller)?;
+        Self::require_not_shutdown(&env)?;
+
+        for asset_symbol in Self::assets(&env).iter() {
+            let Some(mut config) = env
+                .storage()
+                .persistent()
+                .get::<_, SyntheticConfig>(&asset_symbol)
+            else {
+                continue;
+            };
+            Self::accrue(&env, &asset_symbol, &mut config);
+            config.is_active = false;
+
+            // Fees minted to the surplus buffer are not redeemable
+            let settlement = Settlement {
+                final_price: config.oracle_price,
+                supply: (config.total_minted - config.surplus).max(0),
+                pool: Map::new(&env),
+                shortfall: 0,
+                open_supply: 0,
+                open_pool: Map::new(&env),
+            };
+            env.storage().persistent().set(&asset_symbol, &config);
+            env.storage()
+                .persistent()
+                .set(&Self::settlement_key(&asset_symbol), &settlement);
+        }
+
+        let now = env.ledger().timestamp();
+        let opens_at = now.saturating_add(redemption_delay);
+        env.storage().instance().set(&keys::SHUTDOWN, &opens_at);
+
+        env.events()
+            .publish((extended_topics::SHUTDOWN,), (caller, now, opens_at));
+        Ok(())
+    }
+
+    /// After shutdown, settle a CDP's debt at the frozen prices: collateral
+    /// covering it moves into the redemption pool, primary collateral first,
+    /// and the owner can `close_cdp` for the rest. Permissionless; returns
+    /// the debt settled.
+    pub fn settle_cdp(env: Env, cdp_owner: Address, asset_symbol: Symbol) -> Result<i128, Error> {
+        let mut settlement = Self::settlement(&env, &asset_symbol)?;
+        let mut config: SyntheticConfig = env
+            .storage()
+            .persistent()
+            .get(&asset_symbol)
+            .ok_or(Error::AssetNotFound)?;
+
+        let cdp_key = Self::cdp_key(&cdp_owner, &asset_symbol);
+        let mut cdp: CDP = env
+            .storage()
+            .persistent()
+            .get(&cdp_key)
+            .ok_or(Error::CDPNotFound)?;
+        if cdp.normalized_debt == 0 {
+            // Nothing to settle, but never leave it holding redemptions shut
+            Self::index_cdp(&env, &asset_symbol, &cdp_owner, false);
+            return Ok(0);
+        }
+
+        let debt = Self::debt_of(cdp.normalized_debt, config.rate_index);
+        let shortfall = Self::skim(
+            &env,
+            &asset_symbol,
+            &config,
+            &mut settlement,
+            &mut cdp.collateral_amount,
+            &mut cdp.basket,
+            debt,
+        );
+        settlement.shortfall += shortfall;
+
+        config.total_normalized_debt -= cdp.normalized_debt;
+        cdp.normalized_debt = 0;
+        cdp.minted_amount = 0;
+        cdp.collateral_ratio = i128::MAX;
+        Self::index_cdp(&env, &asset_symbol, &cdp_owner, false);
+
+        env.storage().persistent().set(&cdp_key, &cdp);
+        env.storage().persistent().set(&asset_symbol, &config);
+        env.storage()
+            .persistent()
+            .set(&Self::settlement_key(&asset_symbol), &settlement);
+
+        env.events().publish(
+            (extended_topics::CDP_SETTLED,),
+            CdpSettledEvent {
+                owner: cdp_owner,
+                asset_symbol,
+                debt_settled: debt,
+                shortfall,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        Ok(debt)
+    }
+
+    /// After shutdown, cancel a running auction: collateral covering its
+    /// remaining debt moves into the redemption pool and the rest goes back
+    /// to the CDP owner; the penalty is waived. Permissionless; returns the
+    /// debt settled.
+    pub fn settle_auction(env: Env, auction_id: u64) -> Result<i128, Error> {
+        let mut auction: Auction = env
+            .storage()
+            .persistent()
+            .get(&Self::auction_key(auction_id))
+            .ok_or(Error::AuctionNotFound)?;
+        if !auction.is_active {
+            return Err(Error::AuctionClosed);
+        }
+
+        let mut settlement = Self::settlement(&env, &auction.asset_symbol)?;
+        let config: SyntheticConfig = env
+            .storage()
+            .persistent()
+            .get(&auction.asset_symbol)
+            .ok_or(Error::AssetNotFound)?;
+
+        let mut collateral = auction.collateral;
+        let mut basket = Map::new(&env);
+        for (token, lot) in auction.basket.iter() {
+            if lot.amount > 0 {
+                basket.set(token, lot.amount);
+            }
+        }
+        let debt = auction.debt;
+        let shortfall = Self::skim(
+            &env,
+            &auction.asset_symbol,
+            &config,
+            &mut settlement,
+            &mut collateral,
+            &mut basket,
+            debt,
+        );
+        settlement.shortfall += shortfall;
+
+        auction.collateral = 0;
+        auction.basket = Map::new(&env);
+        auction.debt = 0;
+        auction.penalty = 0;
+        auction.is_active = false;
+        Self::count_open_auction(&env, &auction.asset_symbol, false);
+
+        env.storage()
+            .persistent()
+            .set(&Self::auction_key(auction_id), &auction);
+        env.storage()
+            .persistent()
+            .set(&Self::settlement_key(&auction.asset_symbol), &settlement);
+
+        if collateral > 0 {
+            TokenClient::new(&env, &config.collateral_token).transfer(
+                &env.current_contract_address(),
+                &auction.owner,
+                &collateral,
+            );
+        }
+        for (token, amount) in basket.iter() {
+            TokenClient::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &auction.owner,
+                &amount,
+            );
+        }
+
+        env.events().publish(
+            (extended_topics::CDP_SETTLED,),
+            CdpSettledEvent {
+                owner: auction.owner,
+                asset_symbol: auction.asset_symbol,
+                debt_settled: debt,
+                shortfall,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        Ok(debt)
+    }
+
+    /// After shutdown, burn `amount` synthetic tokens for the same share of
+    /// the asset's redemption pool. Redemptions open once `redemption_delay`
+    /// has passed and every CDP and auction of the asset is settled; the
+    /// first redemption then fixes the per-token rate every holder is paid
+    /// at. Returns the primary collateral paid; basket tokens in the pool
+    /// are paid alongside.
+    pub fn redeem(
+        env: Env,
+        holder: Address,
+        asset_symbol: Symbol,
+        amount: i128,
+    ) -> Result<i128, Error> {
+        holder.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let opens_at: u64 = env
+            .storage()
+            .instance()
+            .get(&keys::SHUTDOWN)
+            .ok_or(Error::NotShutdown)?;
+        if env.ledger().timestamp() < opens_at {
+            return Err(Error::RedemptionNotOpen);
+        }
+
+        let mut settlement = Self::settlement(&env, &asset_symbol)?;
+        if amount > settlement.supply {
+            return Err(Error::InvalidAmount);
+        }
+        let mut config: SyntheticConfig = env
+            .storage()
+            .persistent()
+            .get(&asset_symbol)
+            .ok_or(Error::AssetNotFound)?;
+
+        if settlement.open_supply == 0 {
+            // Debt still on CDPs or in auctions has not reached the pool yet.
+            // Every indexed CDP can be cleared by `settle_cdp`, so the gate
+            // counts index slots rather than the debt aggregate.
+            if Self::cdp_count(&env, &asset_symbol) > 0
+                || Self::open_auctions(&env, &asset_symbol) > 0
+            {
+                return Err(Error::RedemptionNotOpen);
+            }
+            if settlement.pool.values().iter().all(|held| held <= 0) {
+                return Err(Error::RedemptionPoolEmpty);
+            }
+            settlement.open_supply = settlement.supply;
+            settlement.open_pool = settlement.pool.clone();
+        }
+
+        let mut payouts: Vec<(Address, i128)> = Vec::new(&env);
+        for (token, rate_amount) in settlement.open_pool.iter() {
+            let held = settlement.pool.get(token.clone()).unwrap_or(0);
+            let share = (rate_amount * amount / settlement.open_supply).min(held);
+            if share > 0 {
+                settlement.pool.set(token.clone(), held - share);
+                payouts.push_back((token, share));
+            }
+        }
+        if payouts.is_empty() {
+            return Err(Error::InvalidAmount);
+        }
+        settlement.supply -= amount;
+        config.total_minted = (config.total_minted - amount).max(0);
+
+        env.storage().persistent().set(&asset_symbol, &config);
+        env.storage()
+            .persistent()
+            .set(&Self::settlement_key(&asset_symbol), &settlement);
+
+        TokenClient::new(&env, &config.synthetic_token).burn(&holder, &amount);
+        let mut primary_paid = 0;
+        for (token, share) in payouts.iter() {
+            if token == config.collateral_token {
+                primary_paid = share;
+            }
+            TokenClient::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &holder,
+                &share,
+            );
+        }
+
+        env.events().publish(
+            (extended_topics::SYNTHETIC_REDEEMED,),
+            SyntheticRedeemedEvent {
+                holder,
+                asset_symbol,
+                amount,
+                collateral_paid: primary_paid,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        Ok(primary_paid)
+    }
+
+    /// Bring the asset's stability fee index up to date, minting the fees
+    /// accrued since the last update into the surplus buffer.
+    ///
+    /// Permissionless. Returns the amount of fees accrued by this call.
+    pub fn accrue_stability_fee(env: Env, asset_symbol: Symbol) -> Result<i128, Error> {
+        let mut config: SyntheticConfig = env
+            .storage()
+            .persistent()
+            .get(&asset_symbol)
+            .ok_or(Error::AssetNotFound)?;
+
+        let accrued = Self::accrue(&env, &asset_symbol, &mut config);
+        env.storage().persistent().set(&asset_symbol, &config);
+        Ok(accrued)
+    }
+
+    /// View CDP info; `minted_amount` includes stability fees accrued up to
+    /// the current ledger time.
+    pub fn get_cdp(env: Env, owner: Address, asset_symbol: Symbol) -> Result<CDP, Error> {
+        let cdp_key = Self::cdp_key(&owner, &asset_symbol);
+        let mut cdp: CDP = env
+            .storage()
+            .persistent()
+            .get(&cdp_key)
+            .ok_or(Error::CDPNotFound)?;
+
+        if let Some(config) = env
+            .storage()
+            .persistent()
+            .get::<_, SyntheticConfig>(&asset_symbol)
+        {
+            cdp.minted_amount = Self::debt_of(cdp.normalized_debt, Self::live_rate(&env, &config));
+        }
+        Ok(cdp)
+    }
+
+    /// Live health of a CDP: debt with accrued fees and the collateral
+    /// ratio recomputed against the current (or freshly pulled) price
+    pub fn get_cdp_health(
+        env: Env,
+        owner: Address,
+        asset_symbol: Symbol,
+    ) -> Result<CdpHealth, Error> {
+        let config = Self::live_config(&env, &asset_symbol)?;
+        let cdp: CDP = env
+            .storage()
+            .persistent()
+            .get(&Self::cdp_key(&owner, &asset_symbol))
+            .ok_or(Error::CDPNotFound)?;
+        Self::cdp_health(&env, &asset_symbol, &config, &cdp)
+    }
+
+    /// Liquidatable CDPs among index slots `[cursor, cursor + limit)` of the
+    /// asset's CDPs carrying debt. `limit` is capped at `MAX_HEALTH_SCAN`;
+    /// page with `get_cdp_count`. CDPs whose basket has no fresh price are
+    /// skipped.
+    ///
+    /// Slots are not stable: when a CDP leaves the index the last one moves
+    /// into its slot, so between pages a CDP can be skipped or seen twice.
+    /// Keepers should rescan from 0 rather than rely on a saved cursor.
+    pub fn get_liquidatable(
+        env: Env,
+        asset_symbol: Symbol,
+        cursor: u32,
+        limit: u32,
+    ) -> Result<Vec<CdpHealth>, Error> {
+        let config = Self::live_config(&env, &asset_symbol)?;
+        let end = cursor
+            .saturating_add(limit.min(MAX_HEALTH_SCAN))
+            .min(Self::cdp_count(&env, &asset_symbol));
+
+        let mut candidates = Vec::new(&env);
+        for slot in cursor..end {
+            let Some(owner) = env
+                .storage()
+                .persistent()
+                .get::<_, Address>(&Self::cdp_index_key(&asset_symbol, slot))
+            else {
+                continue;
+            };
+            let Some(cdp) = env
+                .storage()
+                .persistent()
+                .get::<_, CDP>(&Self::cdp_key(&owner, &asset_symbol))
+            else {
+                continue;
+            };
+            if let Ok(health) = Self::cdp_health(&env, &asset_symbol, &config, &cdp) {
+                if health.liquidatable {
+                    candidates.push_back(health);
+                }
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Number of CDPs carrying debt indexed for an asset
+    pub fn get_cdp_count(env: Env, asset_symbol: Symbol) -> u32 {
+        Self::cdp_count(&env, &asset_symbol)
+    }
+
+    /// View the asset's oracle sources, if it is in oracle mode
+    pub fn get_oracle_config(env: Env, asset_symbol: Symbol) -> Option<OracleConfig> {
+        env.storage()
+            .persistent()
+            .get(&Self::oracle_key(&asset_symbol))
+    }
+
+    /// View the basket collateral tokens approved for an asset
+    pub fn get_collateral_types(env: Env, asset_symbol: Symbol) -> Map<Address, CollateralType> {
+        Self::collateral_types(&env, &asset_symbol)
+    }
+
+    /// View an asset's liquidation parameters (defaults if never set)
+    pub fn get_liquidation_config(env: Env, asset_symbol: Symbol) -> LiquidationConfig {
+        Self::liquidation_config(&env, &asset_symbol)
+    }
+
+    /// View an auction
+    pub fn get_auction(env: Env, auction_id: u64) -> Result<Auction, Error> {
+        env.storage()
+            .persistent()
+            .get(&Self::auction_key(auction_id))
+            .ok_or(Error::AuctionNotFound)
+    }
+
+    /// Current auction price in bps of the oracle value frozen at its start
+    pub fn get_auction_price(env: Env, auction_id: u64) -> Result<i128, Error> {
+        let auction = Self::get_auction(env.clone(), auction_id)?;
+        let liq_config = Self::liquidation_config(&env, &auction.asset_symbol);
+        Ok(liquidation::auction_price_bps(
+            &liq_config,
+            auction.started_at,
+            env.ledger().timestamp(),
+        ))
+    }
+
+    /// Number of auctions started; ids run from 1 to this value
+    pub fn get_auction_count(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&keys::AUCTION_COUNT)
+            .unwrap_or(0)
+    }
+
+    /// View when redemptions open, if the system has been shut down
+    pub fn get_shutdown(env: Env) -> Option<u64> {
+        env.storage().instance().get(&keys::SHUTDOWN)
+    }
+
+    /// View an asset's global settlement state
+    pub fn get_settlement(env: Env, asset_symbol: Symbol) -> Result<Settlement, Error> {
+        Self::settlement(&env, &asset_symbol)
+    }
+
+    /// View the ceiling on debt value across all assets (0 = uncapped)
+    pub fn get_global_debt_ceiling(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&keys::GLOBAL_CEILING)
+            .unwrap_or(0)
+    }
+
+    /// View asset config
+    pub fn get_config(env: Env, asset_symbol: Symbol) -> Result<SyntheticConfig, Error> {
+        env.storage()
+            .persistent()
+            .get(&asset_symbol)
+            .ok_or(Error::AssetNotFound)
+    }
+
+    // ── Helpers ────────────────────────────────────────────────────────────────
+
+    fn do_bid(
+        env: &Env,
+        bidder: Address,
+        auction_id: u64,
+        token: Option<Address>,
+        max_payment: i128,
+    ) -> Result<i128, Error> {
+        if max_payment <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut auction: Auction = env
+            .storage()
+            .persistent()
+            .get(&Self::auction_key(auction_id))
+            .ok_or(Error::AuctionNotFound)?;
+        if !auction.is_active {
+            return Err(Error::AuctionClosed);
+        }
+
+        let mut config: SyntheticConfig = env
+            .storage()
+            .persistent()
+            .get(&auction.asset_symbol)
+            .ok_or(Error::AssetNotFound)?;
+
+        let (lot_token, available, lot_price) = match &token {
+            None => (
+                config.collateral_token.clone(),
+                auction.collateral,
+                auction.start_price,
+            ),
+            Some(t) => {
+                let lot = auction
+                    .basket
+                    .get(t.clone())
+                    .ok_or(Error::CollateralNotApproved)?;
+                (t.clone(), lot.amount, lot.price)
+            }
+        };
+
+        let liq_config = Self::liquidation_config(env, &auction.asset_symbol);
+        let price_bps = liquidation::auction_price_bps(
+            &liq_config,
+            auction.started_at,
+            env.ledger().timestamp(),
+        );
+
+        let mut paid = max_payment.min(auction.tab());
+        let mut bought = liquidation::auction_lot(paid, lot_price, price_bps);
+        if bought >= available {
+            bought = available;
+            paid = paid.min(liquidation::auction_cost(bought, lot_price, price_bps));
+        }
+        if bought <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let debt_paid = paid.min(auction.debt);
+        let penalty_paid = paid - debt_paid;
+        auction.debt -= debt_paid;
+        auction.penalty -= penalty_paid;
+        match &token {
+            None => auction.collateral -= bought,
+            Some(t) => auction.basket.set(
+                t.clone(),
+                BasketLot {
+                    amount: available - bought,
+                    price: lot_price,
+                },
+            ),
+        }
+
+        // Once the tab is raised the owner keeps the remaining collateral;
+        // a sold-out auction with debt left over closes as bad debt.
+        let mut refunds: Vec<(Address, i128)> = Vec::new(env);
+        if auction.tab() == 0 {
+            refunds.push_back((config.collateral_token.clone(), auction.collateral));
+            for (t, lot) in auction.basket.iter() {
+                refunds.push_back((t, lot.amount));
+            }
+            auction.collateral = 0;
+            auction.basket = Map::new(env);
+        }
+        if auction.tab() == 0 || auction.is_sold_out() {
+            auction.is_active = false;
+            Self::count_open_auction(env, &auction.asset_symbol, false);
+        }
+
+        config.total_minted = (config.total_minted - debt_paid).max(0);
+        config.surplus += penalty_paid;
+
+        env.storage()
+            .persistent()
+            .set(&Self::auction_key(auction_id), &auction);
+        env.storage()
+            .persistent()
+            .set(&auction.asset_symbol, &config);
+
+        let synthetic = TokenClient::new(env, &config.synthetic_token);
+        if debt_paid > 0 {
+            synthetic.burn(&bidder, &debt_paid);
+        }
+        if penalty_paid > 0 {
+            synthetic.transfer(&bidder, &env.current_contract_address(), &penalty_paid);
+        }
+
+        TokenClient::new(env, &lot_token).transfer(
+            &env.current_contract_address(),
+            &bidder,
+            &bought,
+        );
+        for (t, amount) in refunds.iter() {
+            if amount > 0 {
+                TokenClient::new(env, &t).transfer(
+                    &env.current_contract_address(),
+                    &auction.owner,
+                    &amount,
+                );
+            }
+        }
+
+        env.events().publish(
+            (extended_topics::AUCTION_BID,),
+            AuctionBidEvent {
+                auction_id,
+                bidder,
+                collateral_bought: bought,
+                paid,
+                remaining_tab: auction.tab(),
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        Ok(bought)
+    }
 
-    // ── Helpers ────────────────────────────────────────────────────────────────
 
     /// Ensure the stored oracle price is strictly positive, has been initialized,
     /// and has not aged beyond the per-asset `price_max_age_seconds` window.
     ///
+    /// In oracle mode the price is first pulled from the asset's sources and
+    /// written into `config` (the caller persists it); if the sources fail or
+    /// move further than `max_deviation_bps`, the last good price is used
+    /// while it is still within the window.
+    ///
     /// Called by every CDP operation that depends on the oracle price so that
     /// division-by-zero and stale-price scenarios cannot trap users.
-    fn require_valid_price(env: &Env, config: &SyntheticConfig) -> Result<(), Error> {
+    fn require_valid_price(
+        env: &Env,
+        asset_symbol: &Symbol,
+        config: &mut SyntheticConfig,
+    ) -> Result<(), Error> {
+        if let Some((new_price, updated_at)) = Self::oracle_quote(env, asset_symbol, config) {
+            let old_price = config.oracle_price;
+            config.oracle_price = new_price;
+            config.last_updated = updated_at;
+            if new_price != old_price {
+                env.events().publish(
+                    (extended_topics::PRICE_UPDATED,),
+                    PriceUpdatedEvent {
+                        asset_symbol: asset_symbol.clone(),
+                        old_price,
+                        new_price,
+                        updated_by: env.current_contract_address(),
+                        timestamp: env.ledger().timestamp(),
+                    },
+                );
+            }
+        }
+
         // `oracle_price <= 0` covers both "never initialized" (register_asset
         // seeds 0) and any explicit zero/negative update (rejected by
         // update_price itself). We intentionally do NOT gate on
@@ -580,17 +1981,405 @@ impl SyntheticAssetsContract {
         // conflating that with "uninitialized" would brick every test that
         // touches mint/burn/add_collateral/liquidate without bumping the
         // ledger. Staleness is enforced separately below.
-        if config.oracle_price <= 0 {
+        Self::require_fresh_price(
+            env,
+            config.oracle_price,
+            config.last_updated,
+            config.price_max_age_seconds,
+        )
+    }
+
+    /// Pull and medianise the asset's external oracle answers, returning the
+    /// price and its timestamp. `None` when the asset is settled, has no
+    /// oracle sources, too few fresh answers agree, or the median moved more
+    /// than `max_deviation_bps` from a last good price that is still fresh.
+    /// Once the last good price has aged out the median is accepted as is, so
+    /// a market that moved and stayed there is not rejected indefinitely.
+    /// Future-dated answers are dropped by `shared::oracle`.
+    fn oracle_quote(
+        env: &Env,
+        asset_symbol: &Symbol,
+        config: &SyntheticConfig,
+    ) -> Option<(i128, u64)> {
+        if !config.is_active {
+            return None;
+        }
+        let oracle: OracleConfig = env
+            .storage()
+            .persistent()
+            .get(&Self::oracle_key(asset_symbol))?;
+
+        let aggregate = fetch_aggregate_price(
+            env,
+            &oracle.sources,
+            &oracle.feed,
+            config.price_max_age_seconds,
+            oracle.min_sources,
+        )
+        .ok()?;
+
+        let new_price = aggregate.median_price;
+        if new_price <= 0 {
+            return None;
+        }
+        let old_price = config.oracle_price;
+        let fallback_fresh = env.ledger().timestamp().saturating_sub(config.last_updated)
+            <= config.price_max_age_seconds;
+        if old_price > 0 && fallback_fresh {
+            let moved_bps = (new_price - old_price).abs() * 10000 / old_price;
+            if moved_bps > oracle.max_deviation_bps as i128 {
+                return None;
+            }
+        }
+        Some((new_price, aggregate.oldest_timestamp))
+    }
+
+    /// The asset's config as a CDP operation would see it now: oracle
+    /// answers applied, freshness checked and `rate_index` brought forward.
+    /// Nothing is persisted.
+    fn live_config(env: &Env, asset_symbol: &Symbol) -> Result<SyntheticConfig, Error> {
+        let mut config: SyntheticConfig = env
+            .storage()
+            .persistent()
+            .get(asset_symbol)
+            .ok_or(Error::AssetNotFound)?;
+        if let Some((price, updated_at)) = Self::oracle_quote(env, asset_symbol, &config) {
+            config.oracle_price = price;
+            config.last_updated = updated_at;
+        }
+        Self::require_fresh_price(
+            env,
+            config.oracle_price,
+            config.last_updated,
+            config.price_max_age_seconds,
+        )?;
+        config.rate_index = Self::live_rate(env, &config);
+        Ok(config)
+    }
+
+    fn cdp_health(
+        env: &Env,
+        asset_symbol: &Symbol,
+        config: &SyntheticConfig,
+        cdp: &CDP,
+    ) -> Result<CdpHealth, Error> {
+        let debt = Self::debt_of(cdp.normalized_debt, config.rate_index);
+        let (collateral_value, _) = Self::cdp_values(env, asset_symbol, config, cdp)?;
+        let collateral_ratio =
+            liquidation::cratio(collateral_value, debt).ok_or(Error::ArithmeticError)?;
+        Ok(CdpHealth {
+            owner: cdp.owner.clone(),
+            debt,
+            collateral_value,
+            collateral_ratio,
+            liquidatable: config.is_active
+                && cdp.is_active
+                && debt > 0
+                && collateral_ratio < config.liq_cratio,
+        })
+    }
+
+    fn require_fresh_price(
+        env: &Env,
+        price: i128,
+        last_updated: u64,
+        max_age: u64,
+    ) -> Result<(), Error> {
+        if price <= 0 {
             return Err(Error::InvalidPrice);
         }
         let now = env.ledger().timestamp();
         // saturating_sub guards against any future-dated `last_updated` values.
-        if now.saturating_sub(config.last_updated) > config.price_max_age_seconds {
+        if now.saturating_sub(last_updated) > max_age {
             return Err(Error::PriceStale);
         }
         Ok(())
     }
 
+    /// Haircut-weighted and market value of a CDP's collateral in synthetic
+    /// units. Every basket token the CDP holds needs a fresh feed price;
+    /// the caller has already validated the primary `oracle_price`. Fails
+    /// with `ArithmeticError` rather than trapping on overflow.
+    fn cdp_values(
+        env: &Env,
+        asset_symbol: &Symbol,
+        config: &SyntheticConfig,
+        cdp: &CDP,
+    ) -> Result<(i128, i128), Error> {
+        let primary = liquidation::collateral_value(cdp.collateral_amount, config.oracle_price)
+            .ok_or(Error::ArithmeticError)?;
+        let mut weighted = primary;
+        let mut market = primary;
+        if cdp.basket.is_empty() {
+            return Ok((weighted, market));
+        }
+
+        let types = Self::collateral_types(env, asset_symbol);
+        for (token, amount) in cdp.basket.iter() {
+            let ct = types.get(token).ok_or(Error::CollateralNotApproved)?;
+            Self::require_fresh_price(
+                env,
+                ct.price,
+                ct.last_updated,
+                config.price_max_age_seconds,
+            )?;
+            let weighted_amount = liquidation::weighted_value(amount, ct.price, ct.haircut_bps);
+            let market_amount = liquidation::collateral_value(amount, ct.price);
+            weighted = weighted_amount
+                .and_then(|v| weighted.checked_add(v))
+                .ok_or(Error::ArithmeticError)?;
+            market = market_amount
+                .and_then(|v| market.checked_add(v))
+                .ok_or(Error::ArithmeticError)?;
+        }
+        Ok((weighted, market))
+    }
+
+    fn collateral_types(env: &Env, asset: &Symbol) -> Map<Address, CollateralType> {
+        env.storage()
+            .persistent()
+            .get(&Self::collateral_types_key(asset))
+            .unwrap_or(Map::new(env))
+    }
+
+    /// Set a basket balance, dropping the token once it reaches zero.
+    fn set_basket_amount(cdp: &mut CDP, token: &Address, amount: i128) {
+        if amount > 0 {
+            cdp.basket.set(token.clone(), amount);
+        } else {
+            cdp.basket.remove(token.clone());
+        }
+    }
+
+    /// `rate_index` brought forward to now: simple interest at
+    /// `stability_fee_bps` per year since `last_accrual`, compounding on
+    /// every accrual. Frozen once the asset is settled.
+    fn live_rate(env: &Env, config: &SyntheticConfig) -> i128 {
+        let elapsed = env.ledger().timestamp().saturating_sub(config.last_accrual);
+        if elapsed == 0 || config.stability_fee_bps <= 0 || !config.is_active {
+            return config.rate_index;
+        }
+        let growth = config.rate_index * config.stability_fee_bps as i128 * elapsed as i128
+            / (10000 * SECONDS_PER_YEAR as i128);
+        config.rate_index + growth
+    }
+
+    /// Accrue stability fees on the asset's total debt. The fees are minted
+    /// as synthetic tokens to the contract and booked to `surplus`; the
+    /// caller persists `config`.
+    fn accrue(env: &Env, asset_symbol: &Symbol, config: &mut SyntheticConfig) -> i128 {
+        let new_rate = Self::live_rate(env, config);
+        let now = env.ledger().timestamp();
+        if new_rate == config.rate_index {
+            config.last_accrual = now;
+            return 0;
+        }
+
+        let accrued = Self::debt_of(config.total_normalized_debt, new_rate)
+            - Self::debt_of(config.total_normalized_debt, config.rate_index);
+        config.rate_index = new_rate;
+        config.last_accrual = now;
+        if accrued <= 0 {
+            return 0;
+        }
+
+        config.total_minted += accrued;
+        config.surplus += accrued;
+        StellarAssetClient::new(env, &config.synthetic_token)
+            .mint(&env.current_contract_address(), &accrued);
+
+        env.events().publish(
+            (extended_topics::FEES_ACCRUED,),
+            StabilityFeeAccruedEvent {
+                asset_symbol: asset_symbol.clone(),
+                rate_index: new_rate,
+                fees_accrued: accrued,
+                surplus: config.surplus,
+                timestamp: now,
+            },
+        );
+        accrued
+    }
+
+    /// Move collateral worth `debt` at the frozen prices into the asset's
+    /// redemption pool, primary collateral first and then the basket in
+    /// token order. Returns the debt the collateral could not cover.
+    fn skim(
+        env: &Env,
+        asset_symbol: &Symbol,
+        config: &SyntheticConfig,
+        settlement: &mut Settlement,
+        collateral: &mut i128,
+        basket: &mut Map<Address, i128>,
+        debt: i128,
+    ) -> i128 {
+        let mut owed = debt * liquidation::BPS;
+        if settlement.final_price > 0 {
+            let (taken, covered) =
+                liquidation::take_collateral(owed, settlement.final_price, *collateral);
+            *collateral -= taken;
+            owed -= covered;
+            Self::add_to_pool(settlement, &config.collateral_token, taken);
+        }
+
+        if owed > 0 {
+            let types = Self::collateral_types(env, asset_symbol);
+            for (token, amount) in basket.iter() {
+                let price = types.get(token.clone()).map(|ct| ct.price).unwrap_or(0);
+                if price <= 0 {
+                    continue;
+                }
+                let (taken, covered) = liquidation::take_collateral(owed, price, amount);
+                owed -= covered;
+                if taken > 0 {
+                    if amount > taken {
+                        basket.set(token.clone(), amount - taken);
+                    } else {
+                        basket.remove(token.clone());
+                    }
+                    Self::add_to_pool(settlement, &token, taken);
+                }
+                if owed == 0 {
+                    break;
+                }
+            }
+        }
+        Self::ceil_div(owed, liquidation::BPS)
+    }
+
+    fn add_to_pool(settlement: &mut Settlement, token: &Address, amount: i128) {
+        if amount > 0 {
+            let held = settlement.pool.get(token.clone()).unwrap_or(0);
+            settlement.pool.set(token.clone(), held + amount);
+        }
+    }
+
+    /// Debt owed for `normalized` units at `rate_index`, rounded up.
+    fn debt_of(normalized: i128, rate_index: i128) -> i128 {
+        Self::ceil_div(normalized * rate_index, RATE_PRECISION)
+    }
+
+    fn ceil_div(a: i128, b: i128) -> i128 {
+        (a + b - 1) / b
+    }
+
+    /// Check that minting `amount` more of `asset` stays within its own
+    /// debt ceiling and the global one. Other assets count at their stored
+    /// `total_minted`, without accruing their fees first.
+    fn require_within_ceilings(
+        env: &Env,
+        asset: &Symbol,
+        config: &SyntheticConfig,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let asset_total = config
+            .total_minted
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticError)?;
+        if config.debt_ceiling > 0 && asset_total > config.debt_ceiling {
+            return Err(Error::DebtCeilingExceeded);
+        }
+
+        let global_ceiling = Self::get_global_debt_ceiling(env.clone());
+        if global_ceiling == 0 {
+            return Ok(());
+        }
+        let mut total = liquidation::debt_value(asset_total, config.oracle_price)
+            .ok_or(Error::ArithmeticError)?;
+        for other in Self::assets(env).iter() {
+            if other == *asset {
+                continue;
+            }
+            if let Some(other_config) = env.storage().persistent().get::<_, SyntheticConfig>(&other)
+            {
+                total =
+                    liquidation::debt_value(other_config.total_minted, other_config.oracle_price)
+                        .and_then(|value| total.checked_add(value))
+                        .ok_or(Error::ArithmeticError)?;
+            }
+        }
+        if total > global_ceiling {
+            return Err(Error::DebtCeilingExceeded);
+        }
+        Ok(())
+    }
+
+    fn require_not_shutdown(env: &Env) -> Result<(), Error> {
+        if env.storage().instance().has(&keys::SHUTDOWN) {
+            return Err(Error::Shutdown);
+        }
+        Ok(())
+    }
+
+    fn settlement(env: &Env, asset: &Symbol) -> Result<Settlement, Error> {
+        env.storage()
+            .persistent()
+            .get(&Self::settlement_key(asset))
+            .ok_or(Error::NotShutdown)
+    }
+
+    /// Auctions of `asset` still running
+    fn open_auctions(env: &Env, asset: &Symbol) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(keys::OPEN_AUCTIONS, asset.clone()))
+            .unwrap_or(0)
+    }
+
+    fn count_open_auction(env: &Env, asset: &Symbol, opened: bool) {
+        let open = Self::open_auctions(env, asset);
+        let open = if opened {
+            open + 1
+        } else {
+            open.saturating_sub(1)
+        };
+        env.storage()
+            .persistent()
+            .set(&(keys::OPEN_AUCTIONS, asset.clone()), &open);
+    }
+
+    fn assets(env: &Env) -> Vec<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&keys::ASSETS)
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn cdp_count(env: &Env, asset: &Symbol) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(keys::CDP_COUNT, asset.clone()))
+            .unwrap_or(0)
+    }
+
+    /// Add or remove `owner` from the asset's index of CDPs carrying debt.
+    /// Each slot is its own entry; removal moves the last slot into the
+    /// freed one, so no operation touches more than two slots.
+    fn index_cdp(env: &Env, asset: &Symbol, owner: &Address, active: bool) {
+        let storage = env.storage().persistent();
+        let slot_key = (keys::CDP_SLOT, owner.clone(), asset.clone());
+        let count = Self::cdp_count(env, asset);
+        match (storage.get::<_, u32>(&slot_key), active) {
+            (None, true) => {
+                storage.set(&Self::cdp_index_key(asset, count), owner);
+                storage.set(&slot_key, &count);
+                storage.set(&(keys::CDP_COUNT, asset.clone()), &(count + 1));
+            }
+            (Some(slot), false) => {
+                let last = count - 1;
+                if slot != last {
+                    let moved: Address = storage.get(&Self::cdp_index_key(asset, last)).unwrap();
+                    storage.set(&Self::cdp_index_key(asset, slot), &moved);
+                    storage.set(&(keys::CDP_SLOT, moved, asset.clone()), &slot);
+                }
+                storage.remove(&Self::cdp_index_key(asset, last));
+                storage.remove(&slot_key);
+                storage.set(&(keys::CDP_COUNT, asset.clone()), &last);
+            }
+            _ => {}
+        }
+    }
+
     fn require_admin(env: &Env, caller: &Address) -> Result<(), Error> {
         let admin: Address = env
             .storage()
@@ -606,6 +2395,37 @@ impl SyntheticAssetsContract {
     fn cdp_key(owner: &Address, asset: &Symbol) -> (Address, Symbol) {
         (owner.clone(), asset.clone())
     }
+
+    fn liq_config_key(asset: &Symbol) -> (Symbol, Symbol) {
+        (keys::LIQ_CONFIG, asset.clone())
+    }
+
+    fn collateral_types_key(asset: &Symbol) -> (Symbol, Symbol) {
+        (keys::COLLATERAL_TYPES, asset.clone())
+    }
+
+    fn oracle_key(asset: &Symbol) -> (Symbol, Symbol) {
+        (keys::ORACLE, asset.clone())
+    }
+
+    fn cdp_index_key(asset: &Symbol, slot: u32) -> (Symbol, Symbol, u32) {
+        (keys::CDP_INDEX, asset.clone(), slot)
+    }
+
+    fn settlement_key(asset: &Symbol) -> (Symbol, Symbol) {
+        (keys::SETTLEMENT, asset.clone())
+    }
+
+    fn auction_key(id: u64) -> (Symbol, u64) {
+        (keys::AUCTION, id)
+    }
+
+    fn liquidation_config(env: &Env, asset: &Symbol) -> LiquidationConfig {
+        env.storage()
+            .persistent()
+            .get(&Self::liq_config_key(asset))
+            .unwrap_or_default()
+    }
 }
 
 #[cfg(test)]
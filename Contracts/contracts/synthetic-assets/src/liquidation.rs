@@ -0,0 +1,210 @@
+//! Liquidation math for synthetic-asset CDPs.
+//!
+//! Values are expressed in synthetic units: `amount` of a collateral token
+//! priced at `price` is worth `amount * 1e6 / price`, and a CDP's value is
+//! the haircut-weighted sum over its basket. Ratios, haircuts and the
+//! liquidation penalty are in basis points.
+//!
+//! A partial liquidation repays `x` of the debt and seizes collateral worth
+//! `x * (1 + penalty)`. The smallest `x` restoring the ratio to
+//! `liq_cratio` solves
+//!
+//! ```text
+//! (value - x * (1 + penalty)) / (debt - x) >= liq_cratio
+//! x >= (liq_cratio * debt - value) / (liq_cratio - 1 - penalty)
+//! ```
+//!
+//! and is capped at `close_factor` of the debt. Dutch auctions sell the
+//! collateral at a price that starts at `auction_start_bps` of the oracle
+//! value and decays linearly to `auction_floor_bps` over `auction_duration`.
+
+use soroban_sdk::{contracttype, Address, Map, Symbol};
+
+/// Basis-point denominator
+pub const BPS: i128 = 10_000;
+
+/// Oracle price scale (1.0 = 1_000_000)
+pub const PRICE_SCALE: i128 = 1_000_000;
+
+/// Per-asset liquidation parameters set by the admin
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LiquidationConfig {
+    /// Max share of a CDP's debt repaid by one partial liquidation (bps)
+    pub close_factor_bps: i128,
+    /// Sell liquidated collateral through a Dutch auction instead
+    pub auction_enabled: bool,
+    /// Seconds for the auction price to decay from start to floor
+    pub auction_duration: u64,
+    /// Opening auction price as a share of the oracle value (12000 = 120%)
+    pub auction_start_bps: i128,
+    /// Price the auction settles at once `auction_duration` has elapsed
+    pub auction_floor_bps: i128,
+}
+
+impl Default for LiquidationConfig {
+    fn default() -> Self {
+        Self {
+            close_factor_bps: 5_000,
+            auction_enabled: false,
+            auction_duration: 3_600,
+            auction_start_bps: 12_000,
+            auction_floor_bps: 8_000,
+        }
+    }
+}
+
+impl LiquidationConfig {
+    pub fn is_valid(&self) -> bool {
+        self.close_factor_bps > 0
+            && self.close_factor_bps <= BPS
+            && self.auction_duration > 0
+            && self.auction_floor_bps > 0
+            && self.auction_start_bps >= self.auction_floor_bps
+    }
+}
+
+/// A running Dutch auction of a liquidated CDP's collateral
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Auction {
+    pub id: u64,
+    pub owner: Address,
+    pub asset_symbol: Symbol,
+    /// Primary collateral still for sale
+    pub collateral: i128,
+    /// Debt still to be repaid (burned) by bidders
+    pub debt: i128,
+    /// Liquidation penalty still to be raised for the surplus buffer
+    pub penalty: i128,
+    /// Oracle price frozen when the auction started
+    pub start_price: i128,
+    pub started_at: u64,
+    pub is_active: bool,
+    /// Basket collateral still for sale, sold through `bid_basket`
+    pub basket: Map<Address, BasketLot>,
+}
+
+/// Basket collateral of one token in an auction
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BasketLot {
+    pub amount: i128,
+    /// Feed price frozen when the auction started
+    pub price: i128,
+}
+
+impl Auction {
+    /// Synthetic tokens still to be raised (`debt + penalty`)
+    pub fn tab(&self) -> i128 {
+        self.debt + self.penalty
+    }
+
+    /// `true` once every lot, primary and basket, has been sold
+    pub fn is_sold_out(&self) -> bool {
+        self.collateral == 0 && self.basket.values().iter().all(|lot| lot.amount == 0)
+    }
+}
+
+/// Value of `collateral` in synthetic units at `price`; `None` for a
+/// non-positive price or on overflow.
+pub fn collateral_value(collateral: i128, price: i128) -> Option<i128> {
+    if price <= 0 {
+        return None;
+    }
+    collateral.checked_mul(PRICE_SCALE)?.checked_div(price)
+}
+
+/// Value of `debt` synthetic units in collateral units at `price`; `None`
+/// on overflow.
+pub fn debt_value(debt: i128, price: i128) -> Option<i128> {
+    debt.checked_mul(price)?.checked_div(PRICE_SCALE)
+}
+
+/// Value of `collateral` after a `haircut_bps` discount.
+pub fn weighted_value(collateral: i128, price: i128, haircut_bps: i128) -> Option<i128> {
+    collateral_value(collateral, price)?
+        .checked_mul(BPS - haircut_bps)?
+        .checked_div(BPS)
+}
+
+/// Live collateral ratio in bps; `i128::MAX` for debt-free positions and
+/// `None` on overflow.
+pub fn cratio(value: i128, debt: i128) -> Option<i128> {
+    if debt <= 0 {
+        Some(i128::MAX)
+    } else {
+        value.checked_mul(BPS)?.checked_div(debt)
+    }
+}
+
+/// `true` when seizing `debt * (1 + penalty)` would exhaust the collateral
+/// (at market value), leaving nothing for a partial liquidation to restore.
+pub fn is_underwater(market_value: i128, debt: i128, penalty_bps: i128) -> bool {
+    market_value * BPS <= debt * (BPS + penalty_bps)
+}
+
+/// Debt a partial liquidation repays: the amount restoring `liq_cratio`,
+/// capped at `close_factor_bps` of the debt.
+///
+/// Solved in collateral units to avoid flooring the value first, with one
+/// synthetic unit of value held back so the contract's floored ratio check
+/// sees the CDP restored. A basket is passed as its haircut-weighted value
+/// at `PRICE_SCALE`; seizing haircut collateral lowers that value by less
+/// than its market value, so the amount is an upper bound on what a mixed
+/// basket needs.
+pub fn partial_repay(
+    collateral: i128,
+    debt: i128,
+    price: i128,
+    liq_cratio: i128,
+    penalty_bps: i128,
+    close_factor_bps: i128,
+) -> i128 {
+    let cap = (debt * close_factor_bps / BPS).max(1).min(debt);
+    let margin = liq_cratio - BPS - penalty_bps;
+    if margin <= 0 {
+        // Seizing at a penalty can never lift the ratio; repay the cap
+        return cap;
+    }
+    let shortfall = liq_cratio * debt * price - (collateral * PRICE_SCALE - price) * BPS;
+    let denominator = margin * price;
+    let needed = (shortfall + denominator - 1) / denominator;
+    needed.max(1).min(cap)
+}
+
+/// Value, scaled by `BPS`, paid to a liquidator repaying `repay` of debt.
+pub fn seize_value_bps(repay: i128, penalty_bps: i128) -> i128 {
+    repay * (BPS + penalty_bps)
+}
+
+/// Collateral at `price` covering `value_bps` (value scaled by `BPS`),
+/// capped at `available`. Returns the amount and the value it covers.
+pub fn take_collateral(value_bps: i128, price: i128, available: i128) -> (i128, i128) {
+    let wanted = value_bps * price / (BPS * PRICE_SCALE);
+    if wanted < available {
+        return (wanted, value_bps);
+    }
+    let covered = (available * PRICE_SCALE * BPS / price).min(value_bps);
+    (available, covered)
+}
+
+/// Current auction price as a share of the starting oracle value.
+pub fn auction_price_bps(config: &LiquidationConfig, started_at: u64, now: u64) -> i128 {
+    let elapsed = now.saturating_sub(started_at).min(config.auction_duration) as i128;
+    let decay = (config.auction_start_bps - config.auction_floor_bps) * elapsed
+        / config.auction_duration as i128;
+    config.auction_start_bps - decay
+}
+
+/// Collateral bought by paying `payment` synthetic tokens at `price_bps`.
+pub fn auction_lot(payment: i128, start_price: i128, price_bps: i128) -> i128 {
+    payment * start_price / PRICE_SCALE * BPS / price_bps
+}
+
+/// Synthetic tokens needed to buy `collateral` at `price_bps`, rounded up.
+pub fn auction_cost(collateral: i128, start_price: i128, price_bps: i128) -> i128 {
+    let numerator = collateral * PRICE_SCALE * price_bps;
+    let denominator = start_price * BPS;
+    (numerator + denominator - 1) / denominator
+}
@@ -101,6 +101,58 @@ fn test_cdp_full_lifecycle() {
     assert!(!cdp.is_active);
 }
 
+#[test]
+fn test_open_cdp_twice_keeps_the_live_position() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register_contract(None, SyntheticAssetsContract);
+    let (coll_addr, coll_client, coll_admin) = create_collateral_token(&env, &admin);
+    let synth_addr = env.register_stellar_asset_contract(contract_id.clone());
+
+    let sc = SyntheticAssetsContractClient::new(&env, &contract_id);
+    sc.initialize(&admin);
+
+    let asset = symbol_short!("sUSD");
+    sc.register_asset(
+        &admin,
+        &asset,
+        &15000,
+        &12000,
+        &1300,
+        &50,
+        &coll_addr,
+        &synth_addr,
+        &86_400_u64,
+    );
+    sc.update_price(&admin, &asset, &1_000_000);
+    coll_admin.mint(&user, &10000);
+
+    sc.open_cdp(&user, &asset, &1500);
+    sc.mint(&user, &asset, &1000);
+
+    // A second open would wipe the debt and collateral of the first
+    assert_eq!(
+        sc.try_open_cdp(&user, &asset, &100),
+        Err(Ok(Error::CDPAlreadyOpen))
+    );
+    let cdp = sc.get_cdp(&user, &asset);
+    assert_eq!(cdp.collateral_amount, 1500);
+    assert_eq!(cdp.minted_amount, 1000);
+    assert_eq!(sc.get_cdp_count(&asset), 1);
+    assert_eq!(coll_client.balance(&contract_id), 1500);
+
+    // Once closed, the slot can be opened again
+    sc.burn(&user, &asset, &1000);
+    sc.close_cdp(&user, &asset);
+    sc.open_cdp(&user, &asset, &100);
+    assert_eq!(sc.get_cdp(&user, &asset).collateral_amount, 100);
+    assert_eq!(coll_client.balance(&contract_id), 100);
+}
+
 #[test]
 fn test_add_collateral_transfers_tokens() {
     let env = Env::default();
@@ -261,6 +313,46 @@ fn test_update_price_rejects_zero() {
     );
 }
 
+#[test]
+fn test_mint_reports_overflow_as_arithmetic_error() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let contract_id = env.register_contract(None, SyntheticAssetsContract);
+    let (coll_addr, _coll_client, coll_admin) = create_collateral_token(&env, &admin);
+    let synth_addr = env.register_stellar_asset_contract(contract_id.clone());
+
+    let sc = SyntheticAssetsContractClient::new(&env, &contract_id);
+    sc.initialize(&admin);
+
+    let asset = symbol_short!("sUSD");
+    sc.register_asset(
+        &admin,
+        &asset,
+        &15000,
+        &12000,
+        &1300,
+        &50,
+        &coll_addr,
+        &synth_addr,
+        &86_400_u64,
+    );
+    sc.update_price(&admin, &asset, &1);
+
+    // collateral * 1e6 no longer fits in an i128
+    let huge = i128::MAX / 1_000;
+    coll_admin.mint(&user, &huge);
+    sc.open_cdp(&user, &asset, &huge);
+
+    assert_eq!(
+        sc.try_mint(&user, &asset, &1_000_i128)
+            .expect_err("overflowing valuation should be rejected"),
+        Ok(Error::ArithmeticError)
+    );
+}
+
 #[test]
 fn test_mint_rejects_without_price() {
     let env = Env::default();
@@ -432,3 +524,820 @@ fn test_valid_price_path_after_rejection() {
     // default test clock of 0 this is legitimately 0, so we don't assert > 0.
     assert_eq!(cfg.last_updated, env.ledger().timestamp());
 }
+
+// ── Stability fee accrual ──────────────────────────────────────────────────
+
+#[test]
+fn test_stability_fee_accrues_into_debt_and_surplus() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let contract_id = env.register_contract(None, SyntheticAssetsContract);
+    let (coll_addr, coll_client, coll_admin) = create_collateral_token(&env, &admin);
+    let synth_addr = env.register_stellar_asset_contract(contract_id.clone());
+    let synth_client = TokenClient::new(&env, &synth_addr);
+
+    let sc = SyntheticAssetsContractClient::new(&env, &contract_id);
+    sc.initialize(&admin);
+
+    let asset = symbol_short!("sUSD");
+    sc.register_asset(
+        &admin,
+        &asset,
+        &15000,
+        &12000,
+        &1300,
+        &200, // 2% a year
+        &coll_addr,
+        &synth_addr,
+        &(2 * SECONDS_PER_YEAR),
+    );
+    sc.update_price(&admin, &asset, &1_000_000);
+
+    coll_admin.mint(&user, &10_000);
+    sc.open_cdp(&user, &asset, &3_000);
+    sc.mint(&user, &asset, &1_000);
+
+    // One year later the debt has grown by 2%, visible before any accrual call
+    env.ledger().with_mut(|li| li.timestamp += SECONDS_PER_YEAR);
+    assert_eq!(sc.get_cdp(&user, &asset).minted_amount, 1_020);
+
+    // Accruing mints the fees into the surplus buffer
+    assert_eq!(sc.accrue_stability_fee(&asset), 20);
+    let cfg = sc.get_config(&asset);
+    assert_eq!(cfg.surplus, 20);
+    assert_eq!(cfg.total_minted, 1_020);
+    assert_eq!(cfg.rate_index, RATE_PRECISION * 102 / 100);
+    assert_eq!(synth_client.balance(&contract_id), 20);
+    assert_eq!(sc.accrue_stability_fee(&asset), 0);
+
+    // Repaying the principal alone leaves the fee outstanding (partial
+    // repayments round the remaining debt up by at most one unit)
+    sc.burn(&user, &asset, &1_000);
+    assert_eq!(sc.get_cdp(&user, &asset).minted_amount, 21);
+    assert_eq!(
+        sc.try_close_cdp(&user, &asset).expect_err("fees still owed"),
+        Ok(Error::InsufficientCollateral)
+    );
+    assert_eq!(
+        sc.try_burn(&user, &asset, &22).expect_err("cannot repay more than owed"),
+        Ok(Error::InvalidAmount)
+    );
+
+    // The owner buys the extra synthetic tokens and settles in full
+    StellarAssetClient::new(&env, &synth_addr).mint(&user, &21);
+    sc.burn(&user, &asset, &21);
+    let cdp = sc.get_cdp(&user, &asset);
+    assert_eq!(cdp.minted_amount, 0);
+    assert_eq!(cdp.normalized_debt, 0);
+    assert_eq!(sc.get_config(&asset).total_normalized_debt, 0);
+
+    assert_eq!(sc.close_cdp(&user, &asset), 3_000);
+    assert_eq!(coll_client.balance(&user), 10_000);
+}
+
+// ── Partial liquidation and Dutch auctions ─────────────────────────────────
+
+#[test]
+fn test_partial_liquidation_restores_cdp_up_to_close_factor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let contract_id = env.register_contract(None, SyntheticAssetsContract);
+    let (coll_addr, coll_client, coll_admin) = create_collateral_token(&env, &admin);
+    let synth_addr = env.register_stellar_asset_contract(contract_id.clone());
+    let synth_client = TokenClient::new(&env, &synth_addr);
+
+    let sc = SyntheticAssetsContractClient::new(&env, &contract_id);
+    sc.initialize(&admin);
+
+    let asset = symbol_short!("sBTC");
+    sc.register_asset(
+        &admin,
+        &asset,
+        &15000,
+        &12000,
+        &1300,
+        &50,
+        &coll_addr,
+        &synth_addr,
+        &86_400_u64,
+    );
+    sc.update_price(&admin, &asset, &1_000_000);
+
+    coll_admin.mint(&user, &10_000);
+    sc.open_cdp(&user, &asset, &1500);
+    sc.mint(&user, &asset, &1000);
+    synth_client.transfer(&user, &liquidator, &1000_i128);
+
+    // 1500 collateral is now worth 1153 against 1000 debt (115%): still
+    // above debt + penalty, so only part of the position is liquidated.
+    sc.update_price(&admin, &asset, &1_300_000);
+
+    // Restoring 120% needs ~674 repaid; the default 50% close factor caps it
+    // at 500, for 500 * 1.13 * 1.3 = 734 collateral.
+    assert_eq!(sc.liquidate(&liquidator, &user, &asset), 734);
+    let cdp = sc.get_cdp(&user, &asset);
+    assert!(cdp.is_active);
+    assert_eq!(cdp.collateral_amount, 766);
+    assert_eq!(cdp.minted_amount, 500);
+    assert_eq!(synth_client.balance(&liquidator), 500);
+    assert_eq!(coll_client.balance(&liquidator), 734);
+
+    // The second pass repays just enough to lift the CDP back over 120%
+    assert_eq!(sc.liquidate(&liquidator, &user, &asset), 248);
+    let cdp = sc.get_cdp(&user, &asset);
+    assert_eq!(cdp.collateral_amount, 518);
+    assert_eq!(cdp.minted_amount, 331);
+    assert!(cdp.collateral_ratio >= 12000);
+    assert_eq!(sc.get_config(&asset).total_minted, 331);
+
+    assert_eq!(
+        sc.try_liquidate(&liquidator, &user, &asset)
+            .expect_err("restored CDP is healthy"),
+        Ok(Error::NotLiquidatable)
+    );
+}
+
+#[test]
+fn test_dutch_auction_sells_collateral_and_returns_surplus_to_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let early = Address::generate(&env);
+    let late = Address::generate(&env);
+    let contract_id = env.register_contract(None, SyntheticAssetsContract);
+    let (coll_addr, coll_client, coll_admin) = create_collateral_token(&env, &admin);
+    let synth_addr = env.register_stellar_asset_contract(contract_id.clone());
+    let synth_client = TokenClient::new(&env, &synth_addr);
+
+    let sc = SyntheticAssetsContractClient::new(&env, &contract_id);
+    sc.initialize(&admin);
+
+    let asset = symbol_short!("sBTC");
+    sc.register_asset(
+        &admin,
+        &asset,
+        &15000,
+        &12000,
+        &1300,
+        &50,
+        &coll_addr,
+        &synth_addr,
+        &86_400_u64,
+    );
+    sc.update_price(&admin, &asset, &1_000_000);
+
+    let bad = LiquidationConfig {
+        auction_start_bps: 7000,
+        ..LiquidationConfig::default()
+    };
+    assert_eq!(
+        sc.try_set_liquidation_config(&admin, &asset, &bad)
+            .expect_err("start below floor"),
+        Ok(Error::InvalidConfig)
+    );
+    sc.set_liquidation_config(
+        &admin,
+        &asset,
+        &LiquidationConfig {
+            auction_enabled: true,
+            ..LiquidationConfig::default()
+        },
+    );
+
+    coll_admin.mint(&user, &10_000);
+    sc.open_cdp(&user, &asset, &1500);
+    sc.mint(&user, &asset, &1000);
+    synth_client.transfer(&user, &early, &600_i128);
+    synth_client.transfer(&user, &late, &400_i128);
+    StellarAssetClient::new(&env, &synth_addr).mint(&late, &200);
+
+    sc.update_price(&admin, &asset, &1_300_000);
+    assert_eq!(
+        sc.try_liquidate(&keeper, &user, &asset)
+            .expect_err("auction mode"),
+        Ok(Error::AuctionOnly)
+    );
+
+    let id = sc.start_auction(&keeper, &user, &asset);
+    assert_eq!(sc.get_auction_count(), 1);
+    let auction = sc.get_auction(&id);
+    assert_eq!(auction.collateral, 1500);
+    assert_eq!(auction.debt, 1000);
+    assert_eq!(auction.penalty, 130);
+    assert_eq!(sc.get_cdp(&user, &asset).collateral_amount, 0);
+    assert_eq!(sc.get_auction_price(&id), 12000);
+
+    // At 120% of the oracle value, 600 buys 600 * 1.3 / 1.2 = 650 collateral
+    assert_eq!(sc.bid(&early, &id, &600), 650);
+    assert_eq!(synth_client.balance(&early), 0);
+
+    // Halfway through the price has decayed to par; the late bidder only
+    // pays the remaining 530 tab for 689 collateral
+    env.ledger().with_mut(|li| li.timestamp += 1_800);
+    assert_eq!(sc.get_auction_price(&id), 10000);
+    assert_eq!(sc.bid(&late, &id, &1_000), 689);
+    assert_eq!(synth_client.balance(&late), 70);
+
+    let auction = sc.get_auction(&id);
+    assert!(!auction.is_active);
+    assert_eq!(auction.tab(), 0);
+
+    // The owner keeps the unsold 161 and the penalty lands in the surplus
+    assert_eq!(coll_client.balance(&user), 8_500 + 161);
+    assert_eq!(coll_client.balance(&contract_id), 0);
+    let cfg = sc.get_config(&asset);
+    assert_eq!(cfg.surplus, 130);
+    assert_eq!(cfg.total_minted, 0);
+    assert_eq!(synth_client.balance(&contract_id), 130);
+
+    assert_eq!(
+        sc.try_bid(&late, &id, &10).expect_err("auction settled"),
+        Ok(Error::AuctionClosed)
+    );
+}
+
+// ── Multi-collateral baskets ───────────────────────────────────────────────
+
+#[test]
+fn test_basket_collateral_counts_with_haircut_and_is_seized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let contract_id = env.register_contract(None, SyntheticAssetsContract);
+    let (coll_addr, coll_client, coll_admin) = create_collateral_token(&env, &admin);
+    let (extra_addr, extra_client, extra_admin) = create_collateral_token(&env, &admin);
+    let synth_addr = env.register_stellar_asset_contract(contract_id.clone());
+    let synth_client = TokenClient::new(&env, &synth_addr);
+
+    let sc = SyntheticAssetsContractClient::new(&env, &contract_id);
+    sc.initialize(&admin);
+
+    let asset = symbol_short!("sUSD");
+    sc.register_asset(
+        &admin,
+        &asset,
+        &15000,
+        &12000,
+        &1300,
+        &50,
+        &coll_addr,
+        &synth_addr,
+        &86_400_u64,
+    );
+    sc.update_price(&admin, &asset, &1_000_000);
+
+    assert_eq!(
+        sc.try_approve_collateral(&admin, &asset, &coll_addr, &0)
+            .expect_err("primary token is implicit"),
+        Ok(Error::InvalidConfig)
+    );
+    coll_admin.mint(&user, &1_000);
+    extra_admin.mint(&user, &1_000);
+    sc.open_cdp(&user, &asset, &1_000);
+    assert_eq!(
+        sc.try_deposit_collateral(&user, &asset, &extra_addr, &1_000)
+            .expect_err("not approved yet"),
+        Ok(Error::CollateralNotApproved)
+    );
+
+    // The extra token counts at 80% of its feed value
+    sc.approve_collateral(&admin, &asset, &extra_addr, &2000);
+    sc.update_collateral_price(&admin, &asset, &extra_addr, &1_000_000);
+    sc.deposit_collateral(&user, &asset, &extra_addr, &1_000);
+    assert_eq!(extra_client.balance(&contract_id), 1_000);
+
+    // 1000 + 1000 * 0.8 = 1800 of value supports 1200 at 150%
+    sc.mint(&user, &asset, &1_200);
+    assert_eq!(sc.get_cdp(&user, &asset).collateral_ratio, 15000);
+    assert_eq!(
+        sc.try_withdraw_collateral(&user, &asset, &extra_addr, &200)
+            .expect_err("would drop below min_cratio"),
+        Ok(Error::BelowMinCratio)
+    );
+
+    // The extra token halves in value: 1000 + 400 = 1400 against 1200 debt
+    // is below 120%, so the CDP is partially liquidated from its primary
+    // collateral first
+    synth_client.transfer(&user, &liquidator, &1_200_i128);
+    sc.update_collateral_price(&admin, &asset, &extra_addr, &2_000_000);
+    assert_eq!(sc.liquidate(&liquidator, &user, &asset), 662);
+    let cdp = sc.get_cdp(&user, &asset);
+    assert_eq!(cdp.collateral_amount, 338);
+    assert_eq!(cdp.basket.get(extra_addr.clone()), Some(1_000));
+    assert_eq!(cdp.minted_amount, 614);
+    assert!(cdp.collateral_ratio >= 12000);
+
+    // A primary crash leaves the basket underwater; the full liquidation
+    // seizes both tokens net of the penalty
+    sc.update_price(&admin, &asset, &3_000_000);
+    assert_eq!(sc.liquidate(&liquidator, &user, &asset), 295);
+    assert_eq!(coll_client.balance(&liquidator), 662 + 295);
+    assert_eq!(extra_client.balance(&liquidator), 870);
+    assert_eq!(extra_client.balance(&user), 130);
+    assert_eq!(extra_client.balance(&contract_id), 0);
+    assert_eq!(synth_client.balance(&liquidator), 0);
+    let cdp = sc.get_cdp(&user, &asset);
+    assert!(!cdp.is_active);
+    assert!(cdp.basket.is_empty());
+}
+
+// ── Oracle mode ────────────────────────────────────────────────────────────
+
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, feed: Symbol, value: i128, ts: u64) {
+        env.storage().instance().set(&feed, &(value, ts));
+    }
+
+    pub fn get_price(env: Env, feed: Symbol) -> (i128, u64) {
+        env.storage().instance().get(&feed).unwrap()
+    }
+}
+
+#[test]
+fn test_oracle_mode_pulls_median_and_falls_back_to_last_good_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let contract_id = env.register_contract(None, SyntheticAssetsContract);
+    let (coll_addr, _, coll_admin) = create_collateral_token(&env, &admin);
+    let synth_addr = env.register_stellar_asset_contract(contract_id.clone());
+
+    let sc = SyntheticAssetsContractClient::new(&env, &contract_id);
+    sc.initialize(&admin);
+
+    let asset = symbol_short!("sUSD");
+    sc.register_asset(
+        &admin,
+        &asset,
+        &15000,
+        &12000,
+        &1300,
+        &50,
+        &coll_addr,
+        &synth_addr,
+        &3_600_u64,
+    );
+
+    let feed = symbol_short!("USD");
+    let mut sources = soroban_sdk::Vec::new(&env);
+    for _ in 0..3 {
+        sources.push_back(env.register_contract(None, MockOracle));
+    }
+    let quote = |prices: [i128; 3]| {
+        let now = env.ledger().timestamp();
+        for (id, price) in sources.iter().zip(prices) {
+            MockOracleClient::new(&env, &id).set_price(&feed, &price, &now);
+        }
+    };
+
+    let mut oracle = OracleConfig {
+        sources: sources.clone(),
+        feed: feed.clone(),
+        min_sources: 4,
+        max_deviation_bps: 2000,
+    };
+    assert_eq!(
+        sc.try_set_oracle_config(&admin, &asset, &oracle)
+            .expect_err("more sources required than configured"),
+        Ok(Error::InvalidConfig)
+    );
+    oracle.min_sources = 2;
+    sc.set_oracle_config(&admin, &asset, &oracle);
+
+    // CDP operations pull the median of the sources
+    quote([990_000, 1_000_000, 1_010_000]);
+    coll_admin.mint(&user, &1_500);
+    sc.open_cdp(&user, &asset, &1_500);
+    sc.mint(&user, &asset, &1_000);
+    assert_eq!(sc.get_config(&asset).oracle_price, 1_000_000);
+
+    // A 50% jump exceeds the 20% band: the last good price is kept
+    quote([1_500_000, 1_500_000, 1_500_000]);
+    assert_eq!(sc.refresh_price(&asset), 1_000_000);
+
+    // ...but only while it is within price_max_age_seconds; after that the
+    // market's new level is accepted instead of leaving the asset stale
+    env.ledger().with_mut(|li| li.timestamp += 3_601);
+    quote([1_500_000, 1_500_000, 1_500_000]);
+    assert_eq!(sc.refresh_price(&asset), 1_500_000);
+
+    // A move within the band is accepted and stamped with the answers' time
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    quote([1_600_000, 1_600_000, 1_590_000]);
+    assert_eq!(sc.refresh_price(&asset), 1_600_000);
+    assert_eq!(sc.get_config(&asset).last_updated, env.ledger().timestamp());
+
+    // Future-dated answers are ignored; with none left the last good price stands
+    let later = env.ledger().timestamp() + 60;
+    for id in sources.iter() {
+        MockOracleClient::new(&env, &id).set_price(&feed, &1_700_000, &later);
+    }
+    assert_eq!(sc.refresh_price(&asset), 1_600_000);
+}
+
+// ── Global settlement ──────────────────────────────────────────────────────
+
+#[test]
+fn test_shutdown_settles_cdps_and_redeems_pro_rata() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let contract_id = env.register_contract(None, SyntheticAssetsContract);
+    let (coll_addr, coll_client, coll_admin) = create_collateral_token(&env, &admin);
+    let synth_addr = env.register_stellar_asset_contract(contract_id.clone());
+
+    let sc = SyntheticAssetsContractClient::new(&env, &contract_id);
+    sc.initialize(&admin);
+
+    let asset = symbol_short!("sUSD");
+    sc.register_asset(
+        &admin,
+        &asset,
+        &15000,
+        &12000,
+        &1300,
+        &50,
+        &coll_addr,
+        &synth_addr,
+        &86_400_u64,
+    );
+    sc.update_price(&admin, &asset, &1_000_000);
+
+    coll_admin.mint(&alice, &3_000);
+    coll_admin.mint(&bob, &1_500);
+    sc.open_cdp(&alice, &asset, &3_000);
+    sc.mint(&alice, &asset, &1_000);
+    sc.open_cdp(&bob, &asset, &1_500);
+    sc.mint(&bob, &asset, &1_000);
+
+    // Bob's CDP is underwater at the price frozen by the shutdown
+    sc.update_price(&admin, &asset, &1_600_000);
+    sc.shutdown(&admin, &100);
+    assert_eq!(sc.get_shutdown(), Some(100));
+    assert_eq!(
+        sc.try_mint(&alice, &asset, &1).expect_err("minting stopped"),
+        Ok(Error::Shutdown)
+    );
+    assert_eq!(
+        sc.try_update_price(&admin, &asset, &1_000_000)
+            .expect_err("price frozen"),
+        Ok(Error::Shutdown)
+    );
+
+    let settlement = sc.get_settlement(&asset);
+    assert_eq!(settlement.final_price, 1_600_000);
+    assert_eq!(settlement.supply, 2_000);
+
+    // 1000 debt takes 1600 of Alice's collateral; she withdraws the rest
+    assert_eq!(sc.settle_cdp(&alice, &asset), 1_000);
+    assert_eq!(sc.close_cdp(&alice, &asset), 1_400);
+
+    // Bob's 1500 only covers 937.5 of his debt
+    assert_eq!(sc.settle_cdp(&bob, &asset), 1_000);
+    let settlement = sc.get_settlement(&asset);
+    assert_eq!(settlement.pool.get(coll_addr.clone()), Some(3_100));
+    assert_eq!(settlement.shortfall, 63);
+    assert_eq!(sc.get_cdp(&bob, &asset).collateral_amount, 0);
+
+    assert_eq!(
+        sc.try_redeem(&alice, &asset, &1_000)
+            .expect_err("redemption delay"),
+        Ok(Error::RedemptionNotOpen)
+    );
+    env.ledger().with_mut(|li| li.timestamp += 100);
+
+    // Holders share the pool, and with it Bob's shortfall, pro rata
+    assert_eq!(sc.redeem(&alice, &asset, &1_000), 1_550);
+    assert_eq!(sc.redeem(&bob, &asset, &1_000), 1_550);
+    assert_eq!(coll_client.balance(&alice), 1_400 + 1_550);
+    assert_eq!(coll_client.balance(&contract_id), 0);
+    assert_eq!(sc.get_settlement(&asset).supply, 0);
+    assert_eq!(sc.get_config(&asset).total_minted, 0);
+}
+
+#[test]
+fn test_redeem_waits_for_every_cdp_and_auction_to_settle() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let idle = Address::generate(&env);
+    let contract_id = env.register_contract(None, SyntheticAssetsContract);
+    let (coll_addr, coll_client, coll_admin) = create_collateral_token(&env, &admin);
+    let synth_addr = env.register_stellar_asset_contract(contract_id.clone());
+
+    let sc = SyntheticAssetsContractClient::new(&env, &contract_id);
+    sc.initialize(&admin);
+
+    let asset = symbol_short!("sUSD");
+    sc.register_asset(
+        &admin,
+        &asset,
+        &15000,
+        &12000,
+        &1300,
+        &50,
+        &coll_addr,
+        &synth_addr,
+        &86_400_u64,
+    );
+    sc.update_price(&admin, &asset, &1_000_000);
+    sc.set_liquidation_config(
+        &admin,
+        &asset,
+        &LiquidationConfig {
+            auction_enabled: true,
+            ..LiquidationConfig::default()
+        },
+    );
+
+    coll_admin.mint(&alice, &3_000);
+    coll_admin.mint(&bob, &1_500);
+    sc.open_cdp(&alice, &asset, &3_000);
+    sc.mint(&alice, &asset, &1_000);
+    sc.open_cdp(&bob, &asset, &1_500);
+    sc.mint(&bob, &asset, &1_000);
+    // A CDP without debt, and a second open over Alice's, hold nothing back
+    coll_admin.mint(&idle, &500);
+    sc.open_cdp(&idle, &asset, &500);
+    assert_eq!(
+        sc.try_open_cdp(&alice, &asset, &1),
+        Err(Ok(Error::CDPAlreadyOpen))
+    );
+
+    // Bob's CDP goes to auction and is still running at shutdown
+    sc.update_price(&admin, &asset, &1_300_000);
+    let id = sc.start_auction(&keeper, &bob, &asset);
+    sc.shutdown(&admin, &0);
+
+    // Nothing settled yet: the pool is empty and must not be paid out
+    assert_eq!(
+        sc.try_redeem(&alice, &asset, &1_000)
+            .expect_err("alice's cdp unsettled"),
+        Ok(Error::RedemptionNotOpen)
+    );
+
+    sc.settle_cdp(&alice, &asset);
+    assert_eq!(
+        sc.try_redeem(&alice, &asset, &1_000)
+            .expect_err("auction still open"),
+        Ok(Error::RedemptionNotOpen)
+    );
+
+    sc.settle_auction(&id);
+    assert_eq!(sc.settle_cdp(&idle, &asset), 0);
+    let pool = sc.get_settlement(&asset).pool.get(coll_addr.clone()).unwrap();
+
+    // The first redemption fixes the rate; the second holder gets the same
+    assert_eq!(sc.redeem(&alice, &asset, &1_000), pool / 2);
+    assert_eq!(sc.redeem(&bob, &asset, &1_000), pool / 2);
+    assert_eq!(sc.close_cdp(&alice, &asset), 1_700);
+    assert_eq!(sc.close_cdp(&idle, &asset), 500);
+    assert_eq!(coll_client.balance(&contract_id), pool % 2);
+}
+
+// ── Health queries ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_liquidatable_index_tracks_live_ratios() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let contract_id = env.register_contract(None, SyntheticAssetsContract);
+    let (coll_addr, _, coll_admin) = create_collateral_token(&env, &admin);
+    let synth_addr = env.register_stellar_asset_contract(contract_id.clone());
+    let synth_client = TokenClient::new(&env, &synth_addr);
+
+    let sc = SyntheticAssetsContractClient::new(&env, &contract_id);
+    sc.initialize(&admin);
+
+    let asset = symbol_short!("sUSD");
+    sc.register_asset(
+        &admin,
+        &asset,
+        &15000,
+        &12000,
+        &1300,
+        &50,
+        &coll_addr,
+        &synth_addr,
+        &86_400_u64,
+    );
+    sc.update_price(&admin, &asset, &1_000_000);
+
+    let risky = Address::generate(&env);
+    let safe = Address::generate(&env);
+    let idle = Address::generate(&env);
+    for (owner, debt) in [(&safe, 500), (&risky, 1_000), (&idle, 0)] {
+        coll_admin.mint(owner, &1_500);
+        sc.open_cdp(owner, &asset, &1_500);
+        if debt > 0 {
+            sc.mint(owner, &asset, &debt);
+        }
+    }
+    // Only CDPs carrying debt are indexed
+    assert_eq!(sc.get_cdp_count(&asset), 2);
+    assert!(sc.get_liquidatable(&asset, &0, &10).is_empty());
+
+    // The stored ratio is a mint-time snapshot; health is recomputed live
+    sc.update_price(&admin, &asset, &1_300_000);
+    assert_eq!(sc.get_cdp(&risky, &asset).collateral_ratio, 15000);
+    let health = sc.get_cdp_health(&risky, &asset);
+    assert_eq!(health.debt, 1_000);
+    assert_eq!(health.collateral_value, 1_153);
+    assert_eq!(health.collateral_ratio, 11530);
+    assert!(health.liquidatable);
+    assert_eq!(sc.get_cdp_health(&idle, &asset).collateral_ratio, i128::MAX);
+
+    let candidates = sc.get_liquidatable(&asset, &0, &10);
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates.get_unchecked(0).owner, risky);
+    assert!(sc.get_liquidatable(&asset, &0, &1).is_empty());
+
+    // Repaying the first slot moves the last CDP into it
+    sc.burn(&safe, &asset, &500);
+    assert_eq!(sc.get_cdp_count(&asset), 1);
+    assert_eq!(sc.get_liquidatable(&asset, &0, &1).get_unchecked(0).owner, risky);
+
+    // The first pass is capped by the close factor; the second restores
+    // the CDP and it leaves the candidate list
+    synth_client.transfer(&risky, &keeper, &1_000_i128);
+    sc.liquidate(&keeper, &risky, &asset);
+    assert_eq!(sc.get_liquidatable(&asset, &0, &10).len(), 1);
+    sc.liquidate(&keeper, &risky, &asset);
+    assert!(sc.get_liquidatable(&asset, &0, &10).is_empty());
+
+    // Closing a CDP without debt leaves the index untouched
+    sc.close_cdp(&idle, &asset);
+    assert_eq!(sc.get_cdp_count(&asset), 1);
+
+    env.ledger().with_mut(|li| li.timestamp += 86_401);
+    assert_eq!(
+        sc.try_get_liquidatable(&asset, &0, &10)
+            .expect_err("stale price"),
+        Ok(Error::PriceStale)
+    );
+}
+
+// ── Debt limits ────────────────────────────────────────────────────────────
+
+#[test]
+fn test_debt_ceilings_and_min_debt_bound_mint_and_burn() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+    let contract_id = env.register_contract(None, SyntheticAssetsContract);
+    let (coll_addr, _coll_client, coll_admin) = create_collateral_token(&env, &admin);
+    let usd_addr = env.register_stellar_asset_contract(contract_id.clone());
+    let eur_addr = env.register_stellar_asset_contract(contract_id.clone());
+
+    let sc = SyntheticAssetsContractClient::new(&env, &contract_id);
+    sc.initialize(&admin);
+
+    let usd = symbol_short!("sUSD");
+    let eur = symbol_short!("sEUR");
+    for (asset, synth) in [(&usd, &usd_addr), (&eur, &eur_addr)] {
+        sc.register_asset(
+            &admin,
+            asset,
+            &15000,
+            &12000,
+            &1300,
+            &50,
+            &coll_addr,
+            synth,
+            &86_400_u64,
+        );
+    }
+    sc.update_price(&admin, &usd, &1_000_000);
+    sc.update_price(&admin, &eur, &1_100_000);
+
+    assert_eq!(
+        sc.try_set_debt_limits(&admin, &usd, &100, &1000)
+            .expect_err("dust above the ceiling is rejected"),
+        Ok(Error::InvalidConfig)
+    );
+    sc.set_debt_limits(&admin, &usd, &1000, &100);
+    sc.set_global_debt_ceiling(&admin, &1500);
+    assert_eq!(sc.get_global_debt_ceiling(), 1500);
+
+    coll_admin.mint(&user, &10_000);
+    coll_admin.mint(&other, &10_000);
+    sc.open_cdp(&user, &usd, &3000);
+    sc.open_cdp(&other, &eur, &3000);
+
+    assert_eq!(
+        sc.try_mint(&user, &usd, &50)
+            .expect_err("a position below min_debt is rejected"),
+        Ok(Error::DebtBelowMinimum)
+    );
+    assert_eq!(
+        sc.try_mint(&user, &usd, &1001)
+            .expect_err("the asset ceiling caps total_minted"),
+        Ok(Error::DebtCeilingExceeded)
+    );
+    sc.mint(&user, &usd, &1000);
+
+    // sEUR has no ceiling of its own but shares the global one, valued at
+    // its price: 500 sEUR is worth 550 collateral
+    assert_eq!(
+        sc.try_mint(&other, &eur, &500)
+            .expect_err("the global ceiling spans every asset"),
+        Ok(Error::DebtCeilingExceeded)
+    );
+    sc.mint(&other, &eur, &450);
+
+    assert_eq!(
+        sc.try_burn(&user, &usd, &950)
+            .expect_err("a partial burn cannot leave dust"),
+        Ok(Error::DebtBelowMinimum)
+    );
+    sc.burn(&user, &usd, &900);
+    assert_eq!(sc.get_cdp(&user, &usd).minted_amount, 100);
+    sc.burn(&user, &usd, &100);
+    assert_eq!(sc.get_cdp(&user, &usd).minted_amount, 0);
+
+    // Repaid debt frees room under both ceilings
+    sc.mint(&other, &eur, &900);
+    assert_eq!(sc.get_config(&eur).total_minted, 1350);
+}
+
+#[test]
+fn test_partial_liquidation_repays_in_full_rather_than_leave_dust() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let contract_id = env.register_contract(None, SyntheticAssetsContract);
+    let (coll_addr, coll_client, coll_admin) = create_collateral_token(&env, &admin);
+    let synth_addr = env.register_stellar_asset_contract(contract_id.clone());
+    let synth_client = TokenClient::new(&env, &synth_addr);
+
+    let sc = SyntheticAssetsContractClient::new(&env, &contract_id);
+    sc.initialize(&admin);
+
+    let asset = symbol_short!("sBTC");
+    sc.register_asset(
+        &admin,
+        &asset,
+        &15000,
+        &12000,
+        &1300,
+        &50,
+        &coll_addr,
+        &synth_addr,
+        &86_400_u64,
+    );
+    sc.update_price(&admin, &asset, &1_000_000);
+    sc.set_debt_limits(&admin, &asset, &0, &600);
+
+    coll_admin.mint(&user, &10_000);
+    sc.open_cdp(&user, &asset, &1500);
+    sc.mint(&user, &asset, &1000);
+    synth_client.transfer(&user, &liquidator, &1000_i128);
+    sc.update_price(&admin, &asset, &1_300_000);
+
+    // The close factor would repay 500 and leave 500 of debt, under the 600
+    // minimum, so the whole 1000 is repaid for 1000 * 1.13 * 1.3 = 1469.
+    assert_eq!(sc.liquidate(&liquidator, &user, &asset), 1469);
+    let cdp = sc.get_cdp(&user, &asset);
+    assert_eq!(cdp.minted_amount, 0);
+    assert_eq!(cdp.normalized_debt, 0);
+    assert_eq!(cdp.collateral_amount, 31);
+    assert_eq!(synth_client.balance(&liquidator), 0);
+    assert_eq!(coll_client.balance(&liquidator), 1469);
+    assert_eq!(sc.get_config(&asset).total_minted, 0);
+}
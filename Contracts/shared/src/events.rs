@@ -609,6 +609,18 @@ pub struct CollateralAddedEvent {
     pub timestamp: u64,
 }
 
+/// Emitted when collateral is withdrawn from a CDP
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CollateralWithdrawnEvent {
+    pub owner: Address,
+    pub asset_symbol: Symbol,
+    pub token: Address,
+    pub amount: i128,
+    pub new_ratio: u32,
+    pub timestamp: u64,
+}
+
 /// Emitted when a CDP is liquidated
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -621,6 +633,65 @@ pub struct CdpLiquidatedEvent {
     pub timestamp: u64,
 }
 
+/// Emitted when a CDP's debt is settled at the frozen price after shutdown
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CdpSettledEvent {
+    pub owner: Address,
+    pub asset_symbol: Symbol,
+    pub debt_settled: i128,
+    pub shortfall: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when synthetic tokens are redeemed for collateral after shutdown
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SyntheticRedeemedEvent {
+    pub holder: Address,
+    pub asset_symbol: Symbol,
+    pub amount: i128,
+    pub collateral_paid: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a liquidated CDP's collateral is put up for Dutch auction
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AuctionStartedEvent {
+    pub auction_id: u64,
+    pub owner: Address,
+    pub asset_symbol: Symbol,
+    pub collateral: i128,
+    pub debt: i128,
+    pub penalty: i128,
+    pub start_price: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a bidder buys collateral from a Dutch auction
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AuctionBidEvent {
+    pub auction_id: u64,
+    pub bidder: Address,
+    pub collateral_bought: i128,
+    pub paid: i128,
+    pub remaining_tab: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when stability fees accrue on a synthetic asset's outstanding debt
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct StabilityFeeAccruedEvent {
+    pub asset_symbol: Symbol,
+    pub rate_index: i128,
+    pub fees_accrued: i128,
+    pub surplus: i128,
+    pub timestamp: u64,
+}
+
 /// Emitted when the oracle price for a synthetic asset is updated
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -753,8 +824,21 @@ pub mod extended_topics {
     pub const CDP_OPENED: Symbol              = symbol_short!("cdp_open");
     pub const CDP_CLOSED: Symbol              = symbol_short!("cdp_close");
     pub const COLLATERAL_ADDED: Symbol        = symbol_short!("col_add");
+    pub const COLLATERAL_WITHDRAWN: Symbol    = symbol_short!("col_wdr");
+    pub const COLLATERAL_APPROVED: Symbol     = symbol_short!("col_appr");
+    pub const COLLATERAL_PRICE_UPDATED: Symbol = symbol_short!("col_price");
     pub const CDP_LIQUIDATED: Symbol          = symbol_short!("cdp_liq");
     pub const PRICE_UPDATED: Symbol           = symbol_short!("price_upd");
+    pub const ORACLE_CONFIG_UPDATED: Symbol   = symbol_short!("orcl_cfg");
+    pub const SHUTDOWN: Symbol                = symbol_short!("shutdown");
+    pub const CDP_SETTLED: Symbol             = symbol_short!("cdp_settl");
+    pub const SYNTHETIC_REDEEMED: Symbol      = symbol_short!("synth_rdm");
+    pub const FEES_ACCRUED: Symbol            = symbol_short!("fee_accr");
+    pub const AUCTION_STARTED: Symbol         = symbol_short!("auc_start");
+    pub const AUCTION_BID: Symbol             = symbol_short!("auc_bid");
+    pub const LIQ_CONFIG_UPDATED: Symbol      = symbol_short!("liq_cfg");
+    pub const DEBT_LIMITS_UPDATED: Symbol     = symbol_short!("debt_lim");
+    pub const GLOBAL_CEILING_UPDATED: Symbol  = symbol_short!("glob_ceil");
 
     // TCR
     pub const TCR_APPLIED: Symbol             = symbol_short!("tcr_apply");
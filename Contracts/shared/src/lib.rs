@@ -17,6 +17,7 @@ pub mod events;
 pub mod fees;
 pub mod governance;
 pub mod nonce;
+pub mod oracle;
 pub mod reentrancy_guard;
 
 /// Standard contract error codes
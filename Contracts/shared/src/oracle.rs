@@ -0,0 +1,210 @@
+//! Multi-source oracle aggregation.
+//!
+//! Each source is a contract exposing `get_price(feed: Symbol) -> (i128, u64)`
+//! returning `(value, timestamp)`. [`fetch_aggregate_price`] queries every
+//! configured source and reduces the answers to a single robust value:
+//!
+//! ```text
+//! 1. call every source with try_invoke_contract — failed calls are dropped
+//! 2. drop future-dated samples, and samples older than max_staleness
+//!    (0 disables the age check)
+//! 3. require at least min_sources samples
+//! 4. drop samples deviating from the median by more than max_deviation_bps,
+//!    or MIN_DEVIATION_BAND if larger
+//! 5. require at least min_sources samples again
+//! 6. report median / mean / spread of the survivors
+//! ```
+//!
+//! Arithmetic on sample values is checked, so a source answering near
+//! `i128::MIN`/`i128::MAX` is rejected as an outlier or reported as
+//! [`OracleError::Overflow`], never a panic.
+
+use soroban_sdk::{contracttype, Address, Env, IntoVal, InvokeError, Symbol, Val, Vec};
+
+/// Deviation band applied by [`fetch_aggregate_price`] (10%).
+pub const DEFAULT_MAX_DEVIATION_BPS: u32 = 1_000;
+
+/// Smallest deviation band, in feed units. Keeps readings near zero (0/0/2 mm
+/// of rain, temperatures around 0) from all being outliers of each other.
+pub const MIN_DEVIATION_BAND: i128 = 10;
+
+/// Function every oracle source must expose.
+pub const GET_PRICE_FN: &str = "get_price";
+
+/// Aggregated result across all accepted oracle answers.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AggregatePrice {
+    /// Median of accepted values (mean of the two middle values for even counts)
+    pub median_price: i128,
+    /// Arithmetic mean of accepted values
+    pub mean_price: i128,
+    /// Highest minus lowest accepted value
+    pub spread: i128,
+    /// Number of sources whose answers were accepted
+    pub sources_used: u32,
+    /// Timestamp of the oldest accepted answer
+    pub oldest_timestamp: u64,
+}
+
+/// Oracle aggregation error codes
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum OracleError {
+    /// No oracle sources are configured
+    NoSources = 3001,
+    /// Fewer than `min_sources` answers survived staleness and outlier filtering
+    InsufficientSources = 3002,
+    /// Arithmetic on accepted values (sum, median, spread) overflowed
+    Overflow = 3003,
+}
+
+impl From<OracleError> for soroban_sdk::Error {
+    fn from(error: OracleError) -> Self {
+        soroban_sdk::Error::from_contract_error(error as u32)
+    }
+}
+
+/// Query `sources` for `feed` and aggregate the answers using the default
+/// deviation band.
+pub fn fetch_aggregate_price(
+    env: &Env,
+    sources: &Vec<Address>,
+    feed: &Symbol,
+    max_staleness: u64,
+    min_sources: u32,
+) -> Result<AggregatePrice, OracleError> {
+    fetch_aggregate_price_with_band(
+        env,
+        sources,
+        feed,
+        max_staleness,
+        min_sources,
+        DEFAULT_MAX_DEVIATION_BPS,
+    )
+}
+
+/// Like [`fetch_aggregate_price`] with an explicit outlier band in basis points
+/// of the median, floored at [`MIN_DEVIATION_BAND`]. A band of 0 disables
+/// outlier rejection.
+pub fn fetch_aggregate_price_with_band(
+    env: &Env,
+    sources: &Vec<Address>,
+    feed: &Symbol,
+    max_staleness: u64,
+    min_sources: u32,
+    max_deviation_bps: u32,
+) -> Result<AggregatePrice, OracleError> {
+    if sources.is_empty() {
+        return Err(OracleError::NoSources);
+    }
+
+    let now = env.ledger().timestamp();
+    let func = Symbol::new(env, GET_PRICE_FN);
+    let mut samples: Vec<(i128, u64)> = Vec::new(env);
+
+    for source in sources.iter() {
+        let args: Vec<Val> = (feed.clone(),).into_val(env);
+        let answer = env.try_invoke_contract::<(i128, u64), InvokeError>(&source, &func, args);
+        let (value, ts) = match answer {
+            Ok(Ok(v)) => v,
+            _ => continue,
+        };
+        // A future timestamp would never go stale
+        if ts > now || (max_staleness > 0 && now - ts > max_staleness) {
+            continue;
+        }
+        samples.push_back((value, ts));
+    }
+
+    aggregate_samples(env, &samples, min_sources, max_deviation_bps)
+}
+
+/// Aggregate already-collected `(value, timestamp)` samples. Exposed so callers
+/// that gather answers differently can reuse the filtering rules.
+pub fn aggregate_samples(
+    env: &Env,
+    samples: &Vec<(i128, u64)>,
+    min_sources: u32,
+    max_deviation_bps: u32,
+) -> Result<AggregatePrice, OracleError> {
+    let min_sources = min_sources.max(1);
+    if samples.len() < min_sources {
+        return Err(OracleError::InsufficientSources);
+    }
+
+    let mut values = Vec::new(env);
+    for (value, _) in samples.iter() {
+        values.push_back(value);
+    }
+    let first_median = median(&sorted(env, &values))?;
+
+    // Reject outliers relative to the unfiltered median. Saturating, so an
+    // extreme answer only ever widens its own deviation.
+    let band = (first_median
+        .saturating_abs()
+        .saturating_mul(max_deviation_bps as i128)
+        / 10_000)
+        .max(MIN_DEVIATION_BAND);
+    let mut accepted = Vec::new(env);
+    let mut oldest = u64::MAX;
+    for (value, ts) in samples.iter() {
+        if max_deviation_bps > 0 && value.saturating_sub(first_median).saturating_abs() > band {
+            continue;
+        }
+        accepted.push_back(value);
+        oldest = oldest.min(ts);
+    }
+    if accepted.len() < min_sources {
+        return Err(OracleError::InsufficientSources);
+    }
+
+    let accepted = sorted(env, &accepted);
+    let count = accepted.len() as i128;
+    let mut sum: i128 = 0;
+    for v in accepted.iter() {
+        sum = sum.checked_add(v).ok_or(OracleError::Overflow)?;
+    }
+
+    Ok(AggregatePrice {
+        median_price: median(&accepted)?,
+        mean_price: sum / count,
+        spread: accepted
+            .last()
+            .unwrap_or(0)
+            .checked_sub(accepted.first().unwrap_or(0))
+            .ok_or(OracleError::Overflow)?,
+        sources_used: accepted.len(),
+        oldest_timestamp: oldest,
+    })
+}
+
+/// Insertion sort; oracle source lists are small.
+fn sorted(env: &Env, values: &Vec<i128>) -> Vec<i128> {
+    let mut out: Vec<i128> = Vec::new(env);
+    for v in values.iter() {
+        let mut idx = out.len();
+        while idx > 0 && out.get_unchecked(idx - 1) > v {
+            idx -= 1;
+        }
+        out.insert(idx, v);
+    }
+    out
+}
+
+/// Median of a sorted, non-empty vector.
+fn median(sorted: &Vec<i128>) -> Result<i128, OracleError> {
+    let n = sorted.len();
+    if n == 0 {
+        return Ok(0);
+    }
+    if n % 2 == 1 {
+        Ok(sorted.get_unchecked(n / 2))
+    } else {
+        sorted
+            .get_unchecked(n / 2 - 1)
+            .checked_add(sorted.get_unchecked(n / 2))
+            .map(|sum| sum / 2)
+            .ok_or(OracleError::Overflow)
+    }
+}
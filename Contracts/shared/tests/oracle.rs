@@ -0,0 +1,242 @@
+//! Tests for the shared multi-source oracle aggregator.
+//!
+//! Each test registers a handful of mock oracle contracts serving
+//! `get_price(feed) -> (value, timestamp)` and drives
+//! [`shared::oracle::fetch_aggregate_price`] from inside a host contract
+//! context, covering staleness, failed sources, quorum and outlier rejection.
+
+use shared::oracle::{fetch_aggregate_price, fetch_aggregate_price_with_band, OracleError};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Ledger},
+    Address, Env, Symbol, Vec,
+};
+
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, feed: Symbol, value: i128, ts: u64) {
+        env.storage().instance().set(&feed, &(value, ts));
+    }
+
+    pub fn get_price(env: Env, feed: Symbol) -> (i128, u64) {
+        env.storage().instance().get(&feed).unwrap()
+    }
+}
+
+/// Host contract giving the aggregator a contract context to call from.
+#[contract]
+pub struct OracleHost;
+
+#[contractimpl]
+impl OracleHost {}
+
+const NOW: u64 = 1_000_000;
+
+fn setup(env: &Env) -> Address {
+    env.ledger().with_mut(|li| li.timestamp = NOW);
+    env.register_contract(None, OracleHost)
+}
+
+/// Register one mock oracle per `(value, ts)` answer for `feed`.
+fn make_sources(env: &Env, feed: &Symbol, answers: &[(i128, u64)]) -> Vec<Address> {
+    let mut sources = Vec::new(env);
+    for (value, ts) in answers {
+        let oracle = env.register_contract(None, MockOracle);
+        MockOracleClient::new(env, &oracle).set_price(feed, value, ts);
+        sources.push_back(oracle);
+    }
+    sources
+}
+
+#[test]
+fn median_mean_and_spread_of_agreeing_sources() {
+    let env = Env::default();
+    let host = setup(&env);
+    let feed = symbol_short!("XLM");
+    let sources = make_sources(&env, &feed, &[(100, NOW), (104, NOW - 10), (98, NOW)]);
+
+    let agg = env
+        .as_contract(&host, || fetch_aggregate_price(&env, &sources, &feed, 60, 3))
+        .unwrap();
+    assert_eq!(agg.median_price, 100);
+    assert_eq!(agg.mean_price, 100);
+    assert_eq!(agg.spread, 6);
+    assert_eq!(agg.sources_used, 3);
+    assert_eq!(agg.oldest_timestamp, NOW - 10);
+}
+
+#[test]
+fn even_source_count_averages_middle_values() {
+    let env = Env::default();
+    let host = setup(&env);
+    let feed = symbol_short!("XLM");
+    let sources = make_sources(&env, &feed, &[(100, NOW), (102, NOW), (104, NOW), (106, NOW)]);
+
+    let agg = env
+        .as_contract(&host, || fetch_aggregate_price(&env, &sources, &feed, 0, 1))
+        .unwrap();
+    assert_eq!(agg.median_price, 103);
+}
+
+#[test]
+fn stale_answers_are_dropped() {
+    let env = Env::default();
+    let host = setup(&env);
+    let feed = symbol_short!("XLM");
+    let sources = make_sources(&env, &feed, &[(100, NOW), (500, NOW - 3_601), (101, NOW)]);
+
+    let agg = env
+        .as_contract(&host, || fetch_aggregate_price(&env, &sources, &feed, 3_600, 2))
+        .unwrap();
+    assert_eq!(agg.sources_used, 2);
+    assert_eq!(agg.median_price, 100);
+
+    // Requiring all three fails once the stale one is dropped
+    let res = env.as_contract(&host, || fetch_aggregate_price(&env, &sources, &feed, 3_600, 3));
+    assert_eq!(res, Err(OracleError::InsufficientSources));
+}
+
+#[test]
+fn future_dated_answers_are_dropped() {
+    let env = Env::default();
+    let host = setup(&env);
+    let feed = symbol_short!("XLM");
+    let sources = make_sources(&env, &feed, &[(100, NOW), (500, NOW + 1), (101, NOW)]);
+
+    // Dropped even with the age check disabled
+    let agg = env
+        .as_contract(&host, || fetch_aggregate_price(&env, &sources, &feed, 0, 2))
+        .unwrap();
+    assert_eq!(agg.sources_used, 2);
+    assert_eq!(agg.oldest_timestamp, NOW);
+}
+
+#[test]
+fn summing_huge_values_reports_overflow() {
+    let env = Env::default();
+    let host = setup(&env);
+    let feed = symbol_short!("XLM");
+    let sources = make_sources(&env, &feed, &[(i128::MAX, NOW), (i128::MAX, NOW), (1, NOW)]);
+
+    let res = env.as_contract(&host, || {
+        fetch_aggregate_price_with_band(&env, &sources, &feed, 0, 3, 0)
+    });
+    assert_eq!(res, Err(OracleError::Overflow));
+}
+
+#[test]
+fn failing_sources_are_skipped() {
+    let env = Env::default();
+    let host = setup(&env);
+    let feed = symbol_short!("XLM");
+    let mut sources = make_sources(&env, &feed, &[(100, NOW), (102, NOW)]);
+    // A mock with no answer for the feed panics on get_price
+    sources.push_back(env.register_contract(None, MockOracle));
+    // Not a contract at all
+    sources.push_back(Address::generate(&env));
+
+    let agg = env
+        .as_contract(&host, || fetch_aggregate_price(&env, &sources, &feed, 0, 2))
+        .unwrap();
+    assert_eq!(agg.sources_used, 2);
+    assert_eq!(agg.median_price, 101);
+}
+
+#[test]
+fn outliers_beyond_band_are_rejected() {
+    let env = Env::default();
+    let host = setup(&env);
+    let feed = symbol_short!("XLM");
+    let sources = make_sources(&env, &feed, &[(100, NOW), (101, NOW), (99, NOW), (250, NOW)]);
+
+    let agg = env
+        .as_contract(&host, || fetch_aggregate_price(&env, &sources, &feed, 0, 3))
+        .unwrap();
+    assert_eq!(agg.sources_used, 3);
+    assert_eq!(agg.median_price, 100);
+    assert_eq!(agg.spread, 2);
+
+    // With the band disabled the outlier is kept
+    let agg = env
+        .as_contract(&host, || fetch_aggregate_price_with_band(&env, &sources, &feed, 0, 3, 0))
+        .unwrap();
+    assert_eq!(agg.sources_used, 4);
+}
+
+#[test]
+fn readings_near_zero_share_the_minimum_band() {
+    let env = Env::default();
+    let host = setup(&env);
+    let feed = symbol_short!("RAIN");
+    let sources = make_sources(&env, &feed, &[(0, NOW), (0, NOW), (2, NOW)]);
+
+    // 10% of a zero median is no band at all; the floor keeps the 2 mm reading
+    let agg = env
+        .as_contract(&host, || fetch_aggregate_price(&env, &sources, &feed, 0, 3))
+        .unwrap();
+    assert_eq!(agg.sources_used, 3);
+    assert_eq!(agg.median_price, 0);
+    assert_eq!(agg.spread, 2);
+}
+
+#[test]
+fn extreme_answers_are_outliers_not_panics() {
+    let env = Env::default();
+    let host = setup(&env);
+    let feed = symbol_short!("XLM");
+    let sources = make_sources(
+        &env,
+        &feed,
+        &[(100, NOW), (101, NOW), (99, NOW), (i128::MIN, NOW), (i128::MAX, NOW)],
+    );
+
+    let agg = env
+        .as_contract(&host, || fetch_aggregate_price(&env, &sources, &feed, 0, 3))
+        .unwrap();
+    assert_eq!(agg.sources_used, 3);
+    assert_eq!(agg.median_price, 100);
+
+    // Two hostile middle values overflow the even-count median
+    let sources = make_sources(
+        &env,
+        &feed,
+        &[(i128::MAX, NOW), (i128::MAX, NOW), (i128::MAX, NOW), (1, NOW)],
+    );
+    let res = env.as_contract(&host, || fetch_aggregate_price(&env, &sources, &feed, 0, 1));
+    assert_eq!(res, Err(OracleError::Overflow));
+
+    // With the band disabled, the spread of MIN..MAX overflows
+    let sources = make_sources(&env, &feed, &[(i128::MIN, NOW), (0, NOW), (i128::MAX, NOW)]);
+    let res = env.as_contract(&host, || {
+        fetch_aggregate_price_with_band(&env, &sources, &feed, 0, 1, 0)
+    });
+    assert_eq!(res, Err(OracleError::Overflow));
+}
+
+#[test]
+fn negative_feeds_are_supported() {
+    let env = Env::default();
+    let host = setup(&env);
+    let feed = symbol_short!("TEMP");
+    let sources = make_sources(&env, &feed, &[(-150, NOW), (-148, NOW), (-152, NOW)]);
+
+    let agg = env
+        .as_contract(&host, || fetch_aggregate_price(&env, &sources, &feed, 0, 3))
+        .unwrap();
+    assert_eq!(agg.median_price, -150);
+    assert_eq!(agg.spread, 4);
+}
+
+#[test]
+fn no_sources_is_an_error() {
+    let env = Env::default();
+    let host = setup(&env);
+    let feed = symbol_short!("XLM");
+    let sources = Vec::new(&env);
+
+    let res = env.as_contract(&host, || fetch_aggregate_price(&env, &sources, &feed, 0, 1));
+    assert_eq!(res, Err(OracleError::NoSources));
+}
@@ -1,74 +0,0 @@
-use soroban_sdk::{contract, contractimpl, contracterror, Env, Address, symbol_short, Symbol, log};
-
-@contracterror
-#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
-#[repr(u32)]
-pub enum Error {
-    InvalidPrice = 1,
-    AssetNotInitialized = 2,
-    ArithmeticError = 3,
-}
-
-#[contract]
-pub struct SyntheticAssetContract;
-
-#[contractimpl]
-impl SyntheticAssetContract {
-    /// Updates the tracked oracle price for a target asset identifier.
-    /// Strictly rejects non-positive (zero or negative) price evaluations.
-    pub fn update_price(env: Env, asset: Symbol, price: i128) -> Result<(), Error> {
-        if price <= 0 {
-            log!(&env, "Error: Oracle price update must be strictly positive. Value: {}", price);
-            return Err(Error::InvalidPrice);
-        }
-        
-        // Persist verified asset price metric state
-        env.storage().instance().set(&asset, &price);
-        Ok(())
-    }
-
-    /// Verifies asset health and retrieves the price, failing safely if uninitialized or non-positive.
-    pub fn get_valid_price(env: &Env, asset: &Symbol) -> Result<i128, Error> {
-        if !env.storage().instance().has(asset) {
-            return Err(Error::AssetNotInitialized);
-        }
-        
-        let price: i128 = env.storage().instance().get(asset).unwrap();
-        if price <= 0 {
-            return Err(Error::InvalidPrice);
-        }
-        
-        Ok(price)
-    }
-
-    /// Mints synthetic positions after validating the underlying collateral math.
-    pub fn mint(env: Env, user: Address, asset: Symbol, collateral_amount: i128) -> Result<(), Error> {
-        user.require_auth();
-        
-        // Gatekeeper check: Ensure asset has a strictly positive price validation entry
-        let oracle_price = Self::get_valid_price(&env, &asset)?;
-
-        // Safe division math execution avoiding zero-division panic traps
-        let mint_amount = collateral_amount
-            .checked_div(oracle_price)
-            .ok_or(Error::ArithmeticError)?;
-
-        log!(&env, "Successfully minted synthetic asset positions: {}", mint_amount);
-        // Position ledger adjustments continue below...
-        Ok(())
-    }
-
-    /// Burns synthetic asset positions safely checking current price indexes.
-    pub fn burn(env: Env, user: Address, asset: Symbol, burn_amount: i128) -> Result<(), Error> {
-        user.require_auth();
-        let oracle_price = Self::get_valid_price(&env, &asset)?;
-
-        // Operations calculation checking criteria logic
-        let collateral_to_return = burn_amount
-            .checked_mul(oracle_price)
-            .ok_or(Error::ArithmeticError)?;
-
-        log!(&env, "Processing position burn sequence matching collateral return value: {}", collateral_to_return);
-        Ok(())
-    }
-}
\ No newline at end of file